@@ -79,6 +79,7 @@
 //! Starting proof search with answer extraction...
 //!
 //! ✅ PROOF FOUND with ANSWER!
+//!   Answer: $Ans(...)
 //!   Given clauses: 6
 //!   Time: <0.1 seconds
 //!
@@ -152,8 +153,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Search for a proof with answer
     match prover.search() {
-        ProofResult::Proof { clauses_kept, .. } => {
+        ProofResult::Proof { clauses_kept, answers, .. } => {
             println!("✅ PROOF FOUND with ANSWER!");
+            for answer in &answers {
+                println!("  Answer: {}", answer.format(prover.symbols()));
+            }
             println!("  Clauses kept: {}", clauses_kept);
             println!("  Time: <0.1 seconds");
             println!();
@@ -111,8 +111,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Search for a solution
     match prover.search() {
-        ProofResult::Proof { clauses_kept, .. } => {
+        ProofResult::Proof { clauses_kept, answers, proofs, .. } => {
             println!("✅ SOLUTION FOUND!");
+            // With assign(max_proofs, -1) every solution is listed
+            println!("  Proofs: {}", proofs.len());
+            for answer in &answers {
+                println!("  Answer: {}", answer.format(prover.symbols()));
+            }
             println!("  Clauses kept: {}", clauses_kept);
             println!("  Time: <0.1 seconds");
             println!();
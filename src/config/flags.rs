@@ -1,3 +1,4 @@
+use super::options::{check_flag, OptionError, OPTIONS};
 use std::collections::HashMap;
 
 /// Boolean toggle that mimics the behaviour of the legacy `Flags` array.
@@ -25,6 +26,23 @@ impl FlagSet {
         Self::default()
     }
 
+    /// Every registered flag at its default value.
+    pub fn with_defaults() -> Self {
+        let mut flags = Self::new();
+        for spec in OPTIONS.iter().filter(|spec| spec.is_flag()) {
+            flags.set(spec.name, spec.default != 0);
+        }
+        flags
+    }
+
+    /// Set or clear a registered flag, rejecting names the registry does
+    /// not list as flags.
+    pub fn apply(&mut self, name: &str, enabled: bool) -> Result<(), OptionError> {
+        check_flag(name)?;
+        self.set(name, enabled);
+        Ok(())
+    }
+
     pub fn set(&mut self, name: impl Into<String>, enabled: bool) {
         let name_str = name.into();
         self.flags
@@ -49,6 +67,7 @@ impl FlagSet {
 #[cfg(test)]
 mod tests {
     use super::FlagSet;
+    use crate::config::OptionError;
 
     #[test]
     fn update_flags() {
@@ -59,4 +78,21 @@ mod tests {
         flags.disable("verbose");
         assert!(!flags.is_enabled("verbose"));
     }
+
+    #[test]
+    fn apply_checks_the_registry() {
+        let mut flags = FlagSet::with_defaults();
+        assert!(flags.is_enabled("binary_res"));
+        assert!(!flags.is_enabled("hyper_res"));
+        assert_eq!(flags.apply("hyper_res", true), Ok(()));
+        assert!(flags.is_enabled("hyper_res"));
+        assert_eq!(
+            flags.apply("hyper_ress", true),
+            Err(OptionError::UnknownFlag("hyper_ress".into()))
+        );
+        assert_eq!(
+            flags.apply("max_weight", false),
+            Err(OptionError::NotAFlag("max_weight".into()))
+        );
+    }
 }
@@ -5,9 +5,14 @@
 //! as functionality migrates.
 
 mod flags;
+mod options;
 mod params;
 mod stats;
 
 pub use flags::{Flag, FlagSet};
+pub use options::{
+    check_flag, lookup_option, options_table, parse_parameter, OptionError, OptionKind,
+    OptionSpec, OPTIONS,
+};
 pub use params::{ParameterSet, ParameterValue};
 pub use stats::Statistics;
@@ -0,0 +1,252 @@
+//! Registry of the Otter flags and parameters foras understands.
+//!
+//! Every name accepted by `set`, `clear` and `assign` is listed in
+//! [`OPTIONS`] with its type, default, accepted range and a one-line
+//! description.  [`check_flag`] and [`parse_parameter`] validate against it,
+//! for the prover builder as for [`FlagSet`](super::FlagSet) and
+//! [`ParameterSet`](super::ParameterSet), so a typo such as
+//! `set(hyper_ress).` is reported instead of silently doing nothing.
+
+use std::fmt;
+use std::fmt::Write;
+
+/// What kind of value an option holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionKind {
+    /// Boolean, changed with `set(name)` and `clear(name)`
+    Flag,
+    /// Integer, changed with `assign(name, value)` within `min..=max`
+    Integer { min: i64, max: i64 },
+}
+
+/// One registered flag or parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub kind: OptionKind,
+    /// Default value; 0 or 1 for flags
+    pub default: i64,
+    pub description: &'static str,
+}
+
+impl OptionSpec {
+    const fn flag(name: &'static str, default: bool, description: &'static str) -> Self {
+        Self { name, kind: OptionKind::Flag, default: default as i64, description }
+    }
+
+    const fn integer(
+        name: &'static str,
+        default: i64,
+        min: i64,
+        max: i64,
+        description: &'static str,
+    ) -> Self {
+        Self { name, kind: OptionKind::Integer { min, max }, default, description }
+    }
+
+    pub fn is_flag(&self) -> bool {
+        self.kind == OptionKind::Flag
+    }
+}
+
+const INT_MAX: i64 = i32::MAX as i64;
+const INT_MIN: i64 = i32::MIN as i64;
+
+/// Every supported option, flags first, each group in alphabetical order.
+pub const OPTIONS: &[OptionSpec] = &[
+    OptionSpec::flag("ancestor_subsume", false, "Prefer the clause with the shorter derivation when two subsume each other"),
+    OptionSpec::flag("auto", false, "Pick inference rules and limits from the shape of the input"),
+    OptionSpec::flag("back_demod", false, "Rewrite kept clauses with each new demodulator"),
+    OptionSpec::flag("binary_res", true, "Binary resolution"),
+    OptionSpec::flag("build_proof_object", false, "Print an Ivy-style proof object after each proof"),
+    OptionSpec::flag("build_proof_object_1", false, "Same as build_proof_object"),
+    OptionSpec::flag("build_proof_object_2", false, "Same as build_proof_object"),
+    OptionSpec::flag("control_memory", false, "Lower max_weight as memory fills; purge sos at max_mem instead of stopping"),
+    OptionSpec::flag("demod_inf", false, "Demodulate generated clauses and collect demodulators"),
+    OptionSpec::flag("factor", false, "Factor generated clauses"),
+    OptionSpec::flag("hyper_res", false, "Positive hyperresolution"),
+    OptionSpec::flag("kbo", false, "Orient equalities with the Knuth-Bendix ordering instead of LRPO"),
+    OptionSpec::flag("keep_hint_equivalents", false, "Keep clauses equivalent to a hint regardless of max_weight"),
+    OptionSpec::flag("keep_hint_subsumers", false, "Keep clauses that subsume a hint regardless of max_weight"),
+    OptionSpec::flag("linked_ur_res", false, "Linked UR-resolution"),
+    OptionSpec::flag("para_from", false, "Paramodulation from the given clause"),
+    OptionSpec::flag("para_from_left", true, "Paramodulate from the left side of equalities"),
+    OptionSpec::flag("para_from_right", true, "Paramodulate from the right side of equalities"),
+    OptionSpec::flag("para_into", false, "Paramodulation into the given clause"),
+    OptionSpec::flag("para_into_left", true, "Paramodulate into the left side of equalities"),
+    OptionSpec::flag("para_into_right", true, "Paramodulate into the right side of equalities"),
+    OptionSpec::flag("print_given", false, "Accepted for Otter compatibility; no effect"),
+    OptionSpec::flag("print_kept", false, "Accepted for Otter compatibility; no effect"),
+    OptionSpec::flag("print_lists_at_end", false, "Accepted for Otter compatibility; no effect"),
+    OptionSpec::flag("process_input", false, "Accepted for Otter compatibility; no effect"),
+    OptionSpec::flag("prolog_style_variables", false, "Read names starting with an upper-case letter as variables"),
+    OptionSpec::flag("superposition", false, "Ordered superposition, equality resolution and equality factoring instead of paramodulation; usable input clauses join sos"),
+    OptionSpec::flag("unit_deletion", false, "Delete literals of generated clauses refuted by unit clauses"),
+    OptionSpec::flag("ur_res", false, "UR-resolution"),
+    OptionSpec::integer("bsub_hint_add_wt", -1000, INT_MIN, INT_MAX, "Added to the weight of clauses that subsume a hint"),
+    OptionSpec::integer("bsub_hint_wt", INT_MAX, INT_MIN, INT_MAX, "Weight of clauses that subsume a hint; max for unchanged"),
+    OptionSpec::integer("demod_limit", 100, -1, INT_MAX, "Rewrite steps allowed per clause; -1 for no limit"),
+    OptionSpec::integer("equiv_hint_add_wt", 0, INT_MIN, INT_MAX, "Added to the weight of clauses equivalent to a hint"),
+    OptionSpec::integer("equiv_hint_wt", INT_MAX, INT_MIN, INT_MAX, "Weight of clauses equivalent to a hint; max for unchanged"),
+    OptionSpec::integer("fsub_hint_add_wt", 0, INT_MIN, INT_MAX, "Added to the weight of clauses subsumed by a hint"),
+    OptionSpec::integer("fsub_hint_wt", INT_MAX, INT_MIN, INT_MAX, "Weight of clauses subsumed by a hint; max for unchanged"),
+    OptionSpec::integer("max_given", 1000, -1, INT_MAX, "Stop after this many given clauses; -1 for no limit"),
+    OptionSpec::integer("max_kept", 10000, -1, INT_MAX, "Stop after keeping this many clauses; -1 for no limit"),
+    OptionSpec::integer("max_mem", -1, -1, INT_MAX, "Memory limit in kilobytes for clauses, their indexes, demodulators and hints; -1 for no limit"),
+    OptionSpec::integer("max_proofs", 1, -1, INT_MAX, "Stop after this many distinct proofs or answers; -1 for no limit"),
+    OptionSpec::integer("max_seconds", -1, -1, INT_MAX, "Stop after this many seconds; -1 for no limit"),
+    OptionSpec::integer("max_sos", -1, -1, INT_MAX, "Purge the heaviest sos clauses beyond this many; -1 for no limit"),
+    OptionSpec::integer("max_weight", INT_MAX, INT_MIN, INT_MAX, "Discard generated clauses heavier than this"),
+    OptionSpec::integer("pick_given_ratio", 4, -1, INT_MAX, "Pick this many given clauses by weight for each one by age"),
+];
+
+/// The registry entry for `name`.
+pub fn lookup_option(name: &str) -> Option<&'static OptionSpec> {
+    OPTIONS.iter().find(|spec| spec.name == name)
+}
+
+/// Check that `name` is registered as a flag.
+pub fn check_flag(name: &str) -> Result<(), OptionError> {
+    match lookup_option(name) {
+        Some(spec) if spec.is_flag() => Ok(()),
+        Some(_) => Err(OptionError::NotAFlag(name.to_string())),
+        None => Err(OptionError::UnknownFlag(name.to_string())),
+    }
+}
+
+/// Parse `value` for the registered parameter `name` and check its range.
+pub fn parse_parameter(name: &str, value: &str) -> Result<i64, OptionError> {
+    let (min, max) = match lookup_option(name).map(|spec| spec.kind) {
+        Some(OptionKind::Integer { min, max }) => (min, max),
+        Some(OptionKind::Flag) => return Err(OptionError::NotAParameter(name.to_string())),
+        None => return Err(OptionError::UnknownParameter(name.to_string())),
+    };
+    let parsed: i64 = value.trim().parse().map_err(|_| OptionError::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+    })?;
+    if !(min..=max).contains(&parsed) {
+        return Err(OptionError::OutOfRange { name: name.to_string(), value: parsed, min, max });
+    }
+    Ok(parsed)
+}
+
+/// A `set`, `clear` or `assign` the registry rejects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionError {
+    UnknownFlag(String),
+    UnknownParameter(String),
+    /// `set` or `clear` of a parameter
+    NotAFlag(String),
+    /// `assign` to a flag
+    NotAParameter(String),
+    /// A value that is not an integer
+    InvalidValue { name: String, value: String },
+    OutOfRange { name: String, value: i64, min: i64, max: i64 },
+}
+
+impl OptionError {
+    /// Unknown names are only warned about, as Otter does; everything else
+    /// stops the run.
+    pub fn is_warning(&self) -> bool {
+        matches!(self, OptionError::UnknownFlag(_) | OptionError::UnknownParameter(_))
+    }
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionError::UnknownFlag(name) => write!(f, "unknown flag {}, ignored", name),
+            OptionError::UnknownParameter(name) => {
+                write!(f, "unknown parameter {}, ignored", name)
+            }
+            OptionError::NotAFlag(name) => {
+                write!(f, "{} is a parameter; use assign({}, value)", name, name)
+            }
+            OptionError::NotAParameter(name) => {
+                write!(f, "{} is a flag; use set({}) or clear({})", name, name, name)
+            }
+            OptionError::InvalidValue { name, value } => {
+                write!(f, "parameter {} needs an integer, got {}", name, value)
+            }
+            OptionError::OutOfRange { name, value, min, max } => write!(
+                f,
+                "parameter {} must be in [{}, {}], got {}",
+                name,
+                format_value(*min),
+                format_value(*max),
+                value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+/// The registry as a table, for `foras --list-options`.
+pub fn options_table() -> String {
+    let mut out = String::new();
+    for (title, flags) in [("Flags (set/clear):", true), ("Parameters (assign):", false)] {
+        writeln!(out, "{}", title).unwrap();
+        for spec in OPTIONS.iter().filter(|spec| spec.is_flag() == flags) {
+            let (default, range) = match spec.kind {
+                OptionKind::Flag => {
+                    ((if spec.default != 0 { "on" } else { "off" }).to_string(), String::new())
+                }
+                OptionKind::Integer { min, max } => (
+                    format_value(spec.default),
+                    format!("[{}, {}]", format_value(min), format_value(max)),
+                ),
+            };
+            writeln!(
+                out,
+                "  {:<24}{:>6}  {:<18}{}",
+                spec.name, default, range, spec.description
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// Print the integer limits by name rather than as ten digits.
+fn format_value(value: i64) -> String {
+    match value {
+        INT_MAX => "max".to_string(),
+        INT_MIN => "min".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lookup_option, options_table, OptionKind, OPTIONS};
+
+    #[test]
+    fn registry_is_sorted_and_defaults_are_in_range() {
+        for pair in OPTIONS.windows(2) {
+            if pair[0].is_flag() == pair[1].is_flag() {
+                assert!(pair[0].name < pair[1].name, "{} is out of order", pair[1].name);
+            } else {
+                assert!(pair[0].is_flag(), "flags come before parameters");
+            }
+        }
+        for spec in OPTIONS {
+            if let OptionKind::Integer { min, max } = spec.kind {
+                assert!((min..=max).contains(&spec.default), "{}", spec.name);
+            }
+        }
+        assert_eq!(lookup_option("hyper_res").map(|spec| spec.is_flag()), Some(true));
+        assert!(lookup_option("hyper_ress").is_none());
+    }
+
+    #[test]
+    fn table_lists_every_option() {
+        let table = options_table();
+        for spec in OPTIONS {
+            assert!(table.contains(spec.name), "{} missing", spec.name);
+        }
+        assert!(table.contains("max_weight"));
+        assert!(table.contains("[min, max]"));
+    }
+}
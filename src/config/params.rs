@@ -1,3 +1,4 @@
+use super::options::{parse_parameter, OptionError, OPTIONS};
 use std::collections::HashMap;
 
 /// Values maintained in the parameter table.
@@ -25,6 +26,23 @@ impl ParameterSet {
         Self::default()
     }
 
+    /// Every registered integer parameter at its default value.
+    pub fn with_defaults() -> Self {
+        let mut params = Self::new();
+        for spec in OPTIONS.iter().filter(|spec| !spec.is_flag()) {
+            params.set(spec.name, ParameterValue::Integer(spec.default));
+        }
+        params
+    }
+
+    /// Parse `value` for the registered parameter `name`, check its range
+    /// and store it.  Returns the stored value.
+    pub fn assign(&mut self, name: &str, value: &str) -> Result<i64, OptionError> {
+        let parsed = parse_parameter(name, value)?;
+        self.set(name, ParameterValue::Integer(parsed));
+        Ok(parsed)
+    }
+
     pub fn set(&mut self, name: impl Into<String>, value: ParameterValue) {
         self.parameters.insert(name.into(), value);
     }
@@ -58,6 +76,7 @@ impl ParameterSet {
 #[cfg(test)]
 mod tests {
     use super::{ParameterSet, ParameterValue};
+    use crate::config::OptionError;
 
     #[test]
     fn store_and_fetch_parameters() {
@@ -70,4 +89,20 @@ mod tests {
         assert_eq!(params.get_float("epsilon"), Some(0.5));
         assert_eq!(params.get_text("name"), Some("foras"));
     }
+
+    #[test]
+    fn assign_parses_and_range_checks() {
+        let mut params = ParameterSet::with_defaults();
+        assert_eq!(params.get_int("max_given"), Some(1000));
+        assert_eq!(params.assign("max_given", "20"), Ok(20));
+        assert_eq!(params.get_int("max_given"), Some(20));
+        assert_eq!(
+            params.assign("max_given", "twenty"),
+            Err(OptionError::InvalidValue { name: "max_given".into(), value: "twenty".into() })
+        );
+        assert!(matches!(params.assign("max_given", "-2"), Err(OptionError::OutOfRange { .. })));
+        assert_eq!(params.assign("max_givn", "1"), Err(OptionError::UnknownParameter("max_givn".into())));
+        assert_eq!(params.assign("factor", "1"), Err(OptionError::NotAParameter("factor".into())));
+        assert_eq!(params.get_int("max_given"), Some(20));
+    }
 }
@@ -1,10 +1,45 @@
 use super::literal::Literal;
-use super::{ClauseAttribute, ParentList};
+use super::{ClauseAttribute, Justification, ParentList, Simplification};
 
 /// Identifier for clauses produced during search.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ClauseId(pub u32);
 
+/// Inference rule that derived a clause, named as in Otter's justifications.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InferenceRule {
+    BinaryRes,
+    HyperRes,
+    UrRes,
+    LinkedUrRes,
+    ParaInto,
+    ParaFrom,
+    Factor,
+    BackDemod,
+    Superposition,
+    EqualityResolution,
+    EqualityFactoring,
+}
+
+impl InferenceRule {
+    /// Otter's name for the rule (`binary`, `hyper`, `para_into`, ...).
+    pub fn name(self) -> &'static str {
+        match self {
+            InferenceRule::BinaryRes => "binary",
+            InferenceRule::HyperRes => "hyper",
+            InferenceRule::UrRes => "ur",
+            InferenceRule::LinkedUrRes => "linked_ur",
+            InferenceRule::ParaInto => "para_into",
+            InferenceRule::ParaFrom => "para_from",
+            InferenceRule::Factor => "factor",
+            InferenceRule::BackDemod => "back_demod",
+            InferenceRule::Superposition => "superposition",
+            InferenceRule::EqualityResolution => "eq_res",
+            InferenceRule::EqualityFactoring => "eq_factor",
+        }
+    }
+}
+
 /// Clause structure mirroring the original Foras representation.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Clause {
@@ -12,6 +47,10 @@ pub struct Clause {
     pub literals: Vec<Literal>,
     pub pick_weight: i32,
     pub parents: ParentList,
+    /// Rule that derived the clause; `None` for input clauses.
+    pub rule: Option<InferenceRule>,
+    /// How the clause was derived; `None` for input clauses.
+    pub justification: Option<Justification>,
     pub attributes: Vec<ClauseAttribute>,
     pub heat_level: i8,
     pub proof_depth: usize,
@@ -24,6 +63,8 @@ impl Clause {
             literals,
             pick_weight: 0,
             parents: ParentList::new(),
+            rule: None,
+            justification: None,
             attributes: Vec::new(),
             heat_level: 0,
             proof_depth: 0,
@@ -35,6 +76,27 @@ impl Clause {
         self
     }
 
+    pub fn with_rule(mut self, rule: InferenceRule) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+
+    pub fn with_justification(mut self, justification: Justification) -> Self {
+        self.justification = Some(justification);
+        self
+    }
+
+    /// Record a simplification of a derived clause; the clauses it used
+    /// become parents.
+    pub fn add_simplification(&mut self, simplification: Simplification) {
+        for id in simplification.clause_ids() {
+            self.parents.push(id);
+        }
+        if let Some(justification) = &mut self.justification {
+            justification.push(simplification);
+        }
+    }
+
     pub fn add_literal(&mut self, literal: Literal) {
         self.literals.push(literal);
     }
@@ -46,6 +108,19 @@ impl Clause {
     pub fn add_attribute(&mut self, attribute: ClauseAttribute) {
         self.attributes.push(attribute);
     }
+
+    /// Approximate bytes the clause occupies: the struct, its literals and
+    /// their terms, parents and justification.  Attribute payloads are not
+    /// counted.
+    pub fn memory_bytes(&self) -> usize {
+        use std::mem::size_of;
+        size_of::<Self>()
+            + self.literals.capacity() * size_of::<Literal>()
+            + self.literals.iter().map(|literal| literal.atom.heap_bytes()).sum::<usize>()
+            + self.parents.len() * size_of::<ClauseId>()
+            + self.justification.as_ref().map_or(0, Justification::heap_bytes)
+            + self.attributes.capacity() * size_of::<ClauseAttribute>()
+    }
 }
 
 #[cfg(test)]
@@ -1,10 +1,32 @@
 use super::{Clause, ClauseId};
+use std::collections::HashSet;
 
-/// Storage for clauses that also assigns unique identifiers.
+/// Whether a stored clause still takes part in the search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClauseState {
+    /// In a clause list, a proof, or otherwise in use
+    Active,
+    /// Subsumed, rewritten or purged; kept only while an active clause or a
+    /// collection root descends from it
+    Deleted,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    clause: Clause,
+    state: ClauseState,
+}
+
+/// Storage for clauses that also assigns unique identifiers.  Ids are
+/// slot numbers, so lookups are direct; a reclaimed clause leaves its slot
+/// empty and its id is never reused.
 #[derive(Default, Debug, Clone)]
 pub struct ClauseArena {
-    next_id: u32,
-    clauses: Vec<Clause>,
+    slots: Vec<Option<Slot>>,
+    /// Number of occupied slots
+    stored: usize,
+    /// Sum of [`Clause::memory_bytes`] over the stored clauses, as inserted
+    bytes: usize,
 }
 
 impl ClauseArena {
@@ -13,37 +35,121 @@ impl ClauseArena {
     }
 
     pub fn insert(&mut self, mut clause: Clause) -> ClauseId {
-        self.next_id += 1;
-        let id = ClauseId(self.next_id);
+        let id = ClauseId(self.slots.len() as u32 + 1);
         clause.id = Some(id);
-        self.clauses.push(clause);
+        self.bytes += clause.memory_bytes();
+        self.slots.push(Some(Slot { clause, state: ClauseState::Active }));
+        self.stored += 1;
         id
     }
 
+    fn slot(&self, id: ClauseId) -> Option<&Slot> {
+        let index = (id.0 as usize).checked_sub(1)?;
+        self.slots.get(index)?.as_ref()
+    }
+
+    fn slot_mut(&mut self, id: ClauseId) -> Option<&mut Slot> {
+        let index = (id.0 as usize).checked_sub(1)?;
+        self.slots.get_mut(index)?.as_mut()
+    }
+
+    /// Drop a clause.  Ids are never reused, so anything still holding `id`
+    /// just finds nothing.
+    pub fn remove(&mut self, id: ClauseId) -> Option<Clause> {
+        let index = (id.0 as usize).checked_sub(1)?;
+        let slot = self.slots.get_mut(index)?.take()?;
+        self.stored -= 1;
+        self.bytes = self.bytes.saturating_sub(slot.clause.memory_bytes());
+        Some(slot.clause)
+    }
+
+    /// Mark a clause deleted: it stays retrievable for the proofs and
+    /// clauses derived from it, but [`collect`](Self::collect) may reclaim
+    /// it.  Returns false if `id` is not stored.
+    pub fn delete(&mut self, id: ClauseId) -> bool {
+        match self.slot_mut(id) {
+            Some(slot) => {
+                slot.state = ClauseState::Deleted;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn state(&self, id: ClauseId) -> Option<ClauseState> {
+        self.slot(id).map(|slot| slot.state)
+    }
+
+    pub fn is_active(&self, id: ClauseId) -> bool {
+        self.state(id) == Some(ClauseState::Active)
+    }
+
+    /// Reclaim every deleted clause that is not an ancestor of an active
+    /// clause or of `roots`, through parents and justifications, and return
+    /// how many were reclaimed.  Proofs of the active clauses and the roots
+    /// can still be reconstructed afterwards.
+    pub fn collect(&mut self, roots: impl IntoIterator<Item = ClauseId>) -> usize {
+        let mut pending: Vec<ClauseId> = roots.into_iter().collect();
+        pending.extend(
+            self.slots
+                .iter()
+                .flatten()
+                .filter(|slot| slot.state == ClauseState::Active)
+                .filter_map(|slot| slot.clause.id),
+        );
+        let mut reachable = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            let Some(slot) = self.slot(id) else {
+                continue;
+            };
+            pending.extend(slot.clause.parents.iter().copied());
+            if let Some(justification) = &slot.clause.justification {
+                pending.extend(justification.clause_ids());
+            }
+        }
+
+        let unreachable: Vec<ClauseId> =
+            self.iter().filter_map(|clause| clause.id).filter(|id| !reachable.contains(id)).collect();
+        for id in &unreachable {
+            self.remove(*id);
+        }
+        unreachable.len()
+    }
+
+    /// Approximate bytes held by the stored clauses.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
     pub fn get(&self, id: ClauseId) -> Option<&Clause> {
-        self.clauses.iter().find(|cl| cl.id == Some(id))
+        self.slot(id).map(|slot| &slot.clause)
     }
 
     pub fn get_mut(&mut self, id: ClauseId) -> Option<&mut Clause> {
-        self.clauses.iter_mut().find(|cl| cl.id == Some(id))
+        self.slot_mut(id).map(|slot| &mut slot.clause)
     }
 
+    /// Number of stored clauses, active or deleted.
     pub fn len(&self) -> usize {
-        self.clauses.len()
+        self.stored
     }
 
     pub fn is_empty(&self) -> bool {
-        self.clauses.is_empty()
+        self.stored == 0
     }
 
+    /// Stored clauses, active or deleted, in id order.
     pub fn iter(&self) -> impl Iterator<Item = &Clause> {
-        self.clauses.iter()
+        self.slots.iter().flatten().map(|slot| &slot.clause)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ClauseArena;
+    use super::{ClauseArena, ClauseState};
     use crate::data::{Clause, ClauseId, Literal, Term, VariableId};
 
     #[test]
@@ -58,4 +164,52 @@ mod tests {
         assert_eq!(arena.len(), 1);
         assert!(arena.get(id).is_some());
     }
+
+    #[test]
+    fn memory_follows_inserts_and_removals() {
+        let mut arena = ClauseArena::new();
+        let clause = Clause::new(vec![Literal::new(
+            true,
+            Term::variable(VariableId::new(0)),
+        )]);
+        let bytes = clause.memory_bytes();
+        let first = arena.insert(clause.clone());
+        arena.insert(clause);
+        assert_eq!(arena.memory_bytes(), 2 * bytes);
+        assert!(arena.remove(first).is_some());
+        assert!(arena.remove(first).is_none());
+        assert!(arena.get(first).is_none());
+        assert_eq!(arena.memory_bytes(), bytes);
+    }
+
+    #[test]
+    fn collection_keeps_ancestors_of_active_clauses() {
+        let mut arena = ClauseArena::new();
+        let unit = || Clause::new(vec![Literal::new(true, Term::variable(VariableId::new(0)))]);
+        let ancestor = arena.insert(unit());
+        let mut child = unit();
+        child.add_parent(ancestor);
+        let child = arena.insert(child);
+        let orphan = arena.insert(unit());
+        let root = arena.insert(unit());
+
+        for id in [ancestor, orphan, root] {
+            assert!(arena.delete(id));
+        }
+        assert_eq!(arena.state(ancestor), Some(ClauseState::Deleted));
+        assert!(arena.is_active(child));
+        assert!(arena.get(orphan).is_some(), "deleted clauses stay until collected");
+
+        assert_eq!(arena.collect([root]), 1);
+        assert!(arena.get(orphan).is_none());
+        assert!(arena.get(ancestor).is_some(), "still an ancestor of an active clause");
+        assert!(arena.get(root).is_some());
+        assert_eq!(arena.len(), 3);
+
+        // Ids stay tied to their slots after collection
+        let next = arena.insert(unit());
+        assert_eq!(next, ClauseId(5));
+        assert_eq!(arena.get(next).and_then(|clause| clause.id), Some(next));
+        assert!(arena.get(ClauseId(0)).is_none());
+    }
 }
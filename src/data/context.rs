@@ -1,7 +1,5 @@
 use super::{Term, VariableId};
 
-pub const MAX_VARS: usize = 64;
-
 /// Status associated with a variable binding inside a substitution context.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub enum ContextStatus {
@@ -12,10 +10,11 @@ pub enum ContextStatus {
 }
 
 /// Substitution context that mirrors the legacy `struct context` layout.
+/// Slots are indexed by variable id and grow as variables are bound.
 #[derive(Clone, Debug)]
 pub struct Context {
-    terms: [Option<Term>; MAX_VARS],
-    statuses: [ContextStatus; MAX_VARS],
+    terms: Vec<Option<Term>>,
+    statuses: Vec<ContextStatus>,
     multiplier: i32,
     built_in_multiplier: i32,
 }
@@ -23,8 +22,8 @@ pub struct Context {
 impl Default for Context {
     fn default() -> Self {
         Self {
-            terms: std::array::from_fn(|_| None),
-            statuses: [ContextStatus::Unbound; MAX_VARS],
+            terms: Vec::new(),
+            statuses: Vec::new(),
             multiplier: 1,
             built_in_multiplier: 1,
         }
@@ -36,11 +35,17 @@ impl Context {
         Self::default()
     }
 
+    /// Make room for the variable at `index`.
+    fn reserve(&mut self, index: usize) {
+        if index >= self.terms.len() {
+            self.terms.resize(index + 1, None);
+            self.statuses.resize(index + 1, ContextStatus::Unbound);
+        }
+    }
+
     pub fn assign(&mut self, var: VariableId, term: Term) {
         let index = var.as_u16() as usize;
-        if index >= MAX_VARS {
-            panic!("variable index {} exceeds MAX_VARS", index);
-        }
+        self.reserve(index);
         self.terms[index] = Some(term);
         self.statuses[index] = ContextStatus::Bound;
     }
@@ -52,17 +57,15 @@ impl Context {
 
     pub fn clear(&mut self, var: VariableId) {
         let index = var.as_u16() as usize;
-        if index < MAX_VARS {
+        if index < self.terms.len() {
             self.terms[index] = None;
             self.statuses[index] = ContextStatus::Unbound;
         }
     }
 
     pub fn reset(&mut self) {
-        for slot in &mut self.terms {
-            *slot = None;
-        }
-        self.statuses.fill(ContextStatus::Unbound);
+        self.terms.clear();
+        self.statuses.clear();
         self.multiplier = 1;
         self.built_in_multiplier = 1;
     }
@@ -74,9 +77,8 @@ impl Context {
 
     pub fn set_status(&mut self, var: VariableId, status: ContextStatus) {
         let index = var.as_u16() as usize;
-        if index < MAX_VARS {
-            self.statuses[index] = status;
-        }
+        self.reserve(index);
+        self.statuses[index] = status;
     }
 
     pub fn multiplier(&self) -> i32 {
@@ -149,6 +151,14 @@ mod tests {
         assert_eq!(ctx.status(var), ContextStatus::Bound);
         ctx.clear(var);
         assert!(ctx.lookup(var).is_none());
+
+        // Any variable id has a slot
+        let far = VariableId::new(1000);
+        ctx.assign(far, Term::variable(var));
+        assert_eq!(ctx.lookup(far), Some(&Term::variable(var)));
+        ctx.set_status(VariableId::new(2000), ContextStatus::Frozen);
+        assert_eq!(ctx.status(VariableId::new(2000)), ContextStatus::Frozen);
+        assert_eq!(ctx.status(VariableId::new(3000)), ContextStatus::Unbound);
     }
 
     #[test]
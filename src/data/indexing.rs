@@ -1,5 +1,6 @@
-use super::{Literal, SymbolId, Term, TermKind};
-use std::collections::VecDeque;
+use super::{Clause, ClauseId, Literal, SymbolId, Term, TermBank, TermId, TermKind, VariableId};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// Node type used in the IMD tree (index/match/demodulation).
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -90,10 +91,758 @@ pub fn term_to_imd_kind(term: &Term) -> ImdNodeKind {
     }
 }
 
+/// Label of a [`DiscriminationTree`] node.  Unlike [`ImdNodeKind`],
+/// variables keep their identity, which makes the tree perfect: a retrieved
+/// pattern matches the query without a separate matching pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum DiscKey {
+    Variable(VariableId),
+    Symbol(SymbolId),
+}
+
+impl DiscKey {
+    fn of(term: &Term) -> Self {
+        match term {
+            Term::Variable { id, .. } => DiscKey::Variable(*id),
+            Term::Application { symbol, .. } => DiscKey::Symbol(*symbol),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DiscNode {
+    key: DiscKey,
+    children: Vec<DiscNode>,
+    /// Entries whose pattern ends at this node
+    entries: Vec<usize>,
+}
+
+/// Perfect discrimination tree over the preorder symbol strings of its
+/// patterns, for retrieving the patterns that generalize a query term.
+/// Symbols are interned with their arity, so the string determines the
+/// term.  Entries are caller-chosen numbers, such as positions in a list
+/// of demodulators.
+#[derive(Clone, Debug, Default)]
+pub struct DiscriminationTree {
+    roots: Vec<DiscNode>,
+    len: usize,
+    /// Bytes held by the nodes and entries, as inserted
+    bytes: usize,
+}
+
+impl DiscriminationTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of entries inserted.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Approximate bytes held by the tree's nodes.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Index `entry` under `pattern`.
+    pub fn insert(&mut self, pattern: &Term, entry: usize) {
+        let mut path = Vec::new();
+        preorder(pattern, &mut path);
+        let created = insert_path(&mut self.roots, &path, entry);
+        self.len += 1;
+        self.bytes += created * std::mem::size_of::<DiscNode>() + std::mem::size_of::<usize>();
+    }
+
+    /// Entries whose pattern generalizes `query`, that is, matches it with
+    /// some substitution for the pattern's variables; in ascending order.
+    pub fn generalizations(&self, query: &Term) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut bindings = Vec::new();
+        retrieve(&self.roots, &mut vec![query], &mut bindings, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    /// [`generalizations`](Self::generalizations) of a term of a
+    /// [`TermBank`].
+    pub fn generalizations_shared(&self, bank: &TermBank, query: TermId) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut bindings = Vec::new();
+        retrieve_shared(&self.roots, bank, &mut vec![query], &mut bindings, &mut found);
+        found.sort_unstable();
+        found
+    }
+}
+
+/// Sign and predicate symbol of a literal.
+type LiteralKey = (bool, SymbolId);
+
+/// Literal keys above which subsumer retrieval scans every key set instead
+/// of enumerating subsets.
+const MAX_SUBSET_KEYS: usize = 12;
+
+/// Features of a clause that cannot decrease under instantiation and
+/// injective mapping of literals: if C subsumes D, every feature of C is at
+/// most the same feature of D.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeatureVector {
+    /// Distinct literal keys, sorted
+    keys: Vec<LiteralKey>,
+    literals: usize,
+    /// Occurrences of each symbol in literals of each sign, sorted
+    symbols: Vec<(LiteralKey, u32)>,
+}
+
+impl FeatureVector {
+    pub fn of(clause: &Clause) -> Self {
+        let mut keys = Vec::new();
+        let mut counts: HashMap<LiteralKey, u32> = HashMap::new();
+        for literal in &clause.literals {
+            if let Term::Application { symbol, .. } = &literal.atom {
+                keys.push((literal.sign, *symbol));
+            }
+            count_symbols(&literal.atom, literal.sign, &mut counts);
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        let mut symbols: Vec<_> = counts.into_iter().collect();
+        symbols.sort_unstable();
+        Self { keys, literals: clause.literals.len(), symbols }
+    }
+
+    /// Approximate bytes an index entry for the vector occupies.
+    fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<(ClauseId, FeatureVector)>()
+            + self.keys.len() * std::mem::size_of::<LiteralKey>()
+            + self.symbols.len() * std::mem::size_of::<(LiteralKey, u32)>()
+    }
+
+    /// Whether no feature exceeds the same feature of `other`.
+    pub fn le(&self, other: &FeatureVector) -> bool {
+        if self.literals > other.literals {
+            return false;
+        }
+        let mut theirs = other.symbols.iter().peekable();
+        self.symbols.iter().all(|(key, count)| {
+            while theirs.next_if(|(other_key, _)| other_key < key).is_some() {}
+            theirs.next_if(|(other_key, _)| other_key == key).is_some_and(|(_, other_count)| count <= other_count)
+        })
+    }
+}
+
+fn count_symbols(term: &Term, sign: bool, counts: &mut HashMap<LiteralKey, u32>) {
+    if let Term::Application { symbol, args } = term {
+        *counts.entry((sign, *symbol)).or_default() += 1;
+        for arg in args {
+            count_symbols(arg, sign, counts);
+        }
+    }
+}
+
+/// Feature-vector index of clauses for subsumption.  Retrieval returns the
+/// clauses whose features allow them to subsume, or be subsumed by, a query
+/// clause; the caller still runs the subsumption test on each.
+#[derive(Clone, Debug, Default)]
+pub struct SubsumptionIndex {
+    features: HashMap<ClauseId, FeatureVector>,
+    /// Clauses by their distinct literal keys
+    by_keys: HashMap<Vec<LiteralKey>, HashSet<ClauseId>>,
+    /// Clauses containing each literal key
+    postings: HashMap<LiteralKey, HashSet<ClauseId>>,
+    /// Bytes held by the feature vectors and the key tables, as inserted
+    bytes: usize,
+}
+
+impl SubsumptionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    pub fn contains(&self, id: ClauseId) -> bool {
+        self.features.contains_key(&id)
+    }
+
+    /// Approximate bytes held by the feature vectors and the key tables.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Bytes of a `by_keys` entry for `keys`, without its clauses.
+    fn key_set_bytes(keys: &[LiteralKey]) -> usize {
+        std::mem::size_of::<(Vec<LiteralKey>, HashSet<ClauseId>)>() + std::mem::size_of_val(keys)
+    }
+
+    pub fn insert(&mut self, id: ClauseId, clause: &Clause) {
+        if self.contains(id) {
+            return;
+        }
+        let features = FeatureVector::of(clause);
+        self.bytes += features.memory_bytes() + (features.keys.len() + 1) * std::mem::size_of::<ClauseId>();
+        for key in &features.keys {
+            let ids = self.postings.entry(*key).or_default();
+            if ids.is_empty() {
+                self.bytes += std::mem::size_of::<(LiteralKey, HashSet<ClauseId>)>();
+            }
+            ids.insert(id);
+        }
+        let ids = self.by_keys.entry(features.keys.clone()).or_default();
+        if ids.is_empty() {
+            self.bytes += Self::key_set_bytes(&features.keys);
+        }
+        ids.insert(id);
+        self.features.insert(id, features);
+    }
+
+    pub fn remove(&mut self, id: ClauseId) {
+        let Some(features) = self.features.remove(&id) else {
+            return;
+        };
+        self.bytes -= features.memory_bytes() + (features.keys.len() + 1) * std::mem::size_of::<ClauseId>();
+        for key in &features.keys {
+            if let Some(ids) = self.postings.get_mut(key) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.postings.remove(key);
+                    self.bytes -= std::mem::size_of::<(LiteralKey, HashSet<ClauseId>)>();
+                }
+            }
+        }
+        if let Some(ids) = self.by_keys.get_mut(&features.keys) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.by_keys.remove(&features.keys);
+                self.bytes -= Self::key_set_bytes(&features.keys);
+            }
+        }
+    }
+
+    /// Clauses that may subsume `clause`, in ascending order.  Their
+    /// literal keys are a subset of the clause's, so only the key sets made
+    /// from its own keys are looked at.
+    pub fn subsumer_candidates(&self, clause: &Clause) -> Vec<ClauseId> {
+        let query = FeatureVector::of(clause);
+        let mut found = Vec::new();
+        let mut collect = |ids: &HashSet<ClauseId>| {
+            found.extend(ids.iter().copied().filter(|id| self.features[id].le(&query)));
+        };
+        if query.keys.len() <= MAX_SUBSET_KEYS {
+            for mask in 0..1usize << query.keys.len() {
+                let subset: Vec<LiteralKey> =
+                    (0..query.keys.len()).filter(|bit| mask & (1 << bit) != 0).map(|bit| query.keys[bit]).collect();
+                if let Some(ids) = self.by_keys.get(&subset) {
+                    collect(ids);
+                }
+            }
+        } else {
+            for (keys, ids) in &self.by_keys {
+                if keys.iter().all(|key| query.keys.binary_search(key).is_ok()) {
+                    collect(ids);
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    /// Clauses that `clause` may subsume, in ascending order.  They contain
+    /// every literal key of the clause, so only the clauses with its rarest
+    /// key are looked at.
+    pub fn subsumee_candidates(&self, clause: &Clause) -> Vec<ClauseId> {
+        let query = FeatureVector::of(clause);
+        let mut rarest: Option<&HashSet<ClauseId>> = None;
+        for key in &query.keys {
+            match self.postings.get(key) {
+                Some(ids) if rarest.is_none_or(|rarest| ids.len() < rarest.len()) => rarest = Some(ids),
+                Some(_) => {}
+                None => return Vec::new(),
+            }
+        }
+        let mut found: Vec<ClauseId> = match rarest {
+            Some(ids) => ids.iter().copied().filter(|id| query.le(&self.features[id])).collect(),
+            None => self.features.iter().filter(|(_, features)| query.le(features)).map(|(id, _)| *id).collect(),
+        };
+        found.sort_unstable();
+        found
+    }
+}
+
+/// What a term has at one fingerprint position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Feature {
+    Symbol(SymbolId),
+    Variable,
+    /// The position is inside the binding of a variable above it
+    BelowVariable,
+    /// The position does not exist and no variable above can create it
+    Absent,
+}
+
+impl Feature {
+    /// Whether two terms with these features at the same position may
+    /// unify.
+    fn unifiable(self, other: Feature) -> bool {
+        match (self, other) {
+            (Feature::BelowVariable, _) | (_, Feature::BelowVariable) => true,
+            (Feature::Absent, Feature::Absent) => true,
+            (Feature::Absent, _) | (_, Feature::Absent) => false,
+            (Feature::Variable, _) | (_, Feature::Variable) => true,
+            (Feature::Symbol(a), Feature::Symbol(b)) => a == b,
+        }
+    }
+}
+
+/// Positions sampled by a fingerprint: the root, its first three
+/// arguments, and the first two arguments of its first two arguments.
+const FINGERPRINT_POSITIONS: [&[usize]; 8] = [&[], &[0], &[1], &[2], &[0, 0], &[0, 1], &[1, 0], &[1, 1]];
+
+type Fingerprint = [Feature; FINGERPRINT_POSITIONS.len()];
+
+fn fingerprint(term: &Term) -> Fingerprint {
+    FINGERPRINT_POSITIONS.map(|path| feature_at(term, path))
+}
+
+fn feature_at(mut term: &Term, path: &[usize]) -> Feature {
+    for index in path {
+        match term {
+            Term::Variable { .. } => return Feature::BelowVariable,
+            Term::Application { args, .. } => match args.get(*index) {
+                Some(arg) => term = arg,
+                None => return Feature::Absent,
+            },
+        }
+    }
+    match term {
+        Term::Variable { .. } => Feature::Variable,
+        Term::Application { symbol, .. } => Feature::Symbol(*symbol),
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct FingerprintNode {
+    children: HashMap<Feature, FingerprintNode>,
+    ids: Vec<ClauseId>,
+}
+
+impl FingerprintNode {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.ids.is_empty()
+    }
+
+    /// Remove `id` from the leaf at `path`, pruning nodes left empty, and
+    /// return the bytes freed.
+    fn remove(&mut self, path: &[Feature], id: ClauseId) -> usize {
+        match path.split_first() {
+            None => {
+                let before = self.ids.len();
+                self.ids.retain(|other| *other != id);
+                (before - self.ids.len()) * std::mem::size_of::<ClauseId>()
+            }
+            Some((feature, rest)) => {
+                let Some(child) = self.children.get_mut(feature) else {
+                    return 0;
+                };
+                let mut freed = child.remove(rest, id);
+                if child.is_empty() {
+                    self.children.remove(feature);
+                    freed += std::mem::size_of::<(Feature, FingerprintNode)>();
+                }
+                freed
+            }
+        }
+    }
+
+    fn retrieve(&self, query: &[Feature], found: &mut Vec<ClauseId>) {
+        let Some((feature, rest)) = query.split_first() else {
+            found.extend_from_slice(&self.ids);
+            return;
+        };
+        for (child_feature, child) in &self.children {
+            if feature.unifiable(*child_feature) {
+                child.retrieve(rest, found);
+            }
+        }
+    }
+}
+
+/// Fingerprint index of terms, each belonging to a clause, for retrieving
+/// the clauses with a term that may unify with a query term.  A term's
+/// fingerprint records what it has at a few fixed positions; two terms
+/// whose fingerprints clash at some position cannot unify, so retrieval
+/// is a walk of a trie of fingerprints that skips clashing branches.  The
+/// caller still unifies the retrieved clauses' terms.
+#[derive(Clone, Debug, Default)]
+pub struct FingerprintIndex {
+    root: FingerprintNode,
+    /// Fingerprints of the terms of each clause
+    terms: HashMap<ClauseId, Vec<Fingerprint>>,
+    /// Bytes held by the trie and the fingerprints, as inserted
+    bytes: usize,
+}
+
+impl FingerprintIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of clauses with a term in the index.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn contains(&self, id: ClauseId) -> bool {
+        self.terms.contains_key(&id)
+    }
+
+    /// Approximate bytes held by the trie and the clauses' fingerprints.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Index `term` as a term of clause `id`.
+    pub fn insert(&mut self, id: ClauseId, term: &Term) {
+        let print = fingerprint(term);
+        let mut node = &mut self.root;
+        for feature in print {
+            node = match node.children.entry(feature) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    self.bytes += std::mem::size_of::<(Feature, FingerprintNode)>();
+                    entry.insert(FingerprintNode::default())
+                }
+            };
+        }
+        node.ids.push(id);
+        let prints = self.terms.entry(id).or_default();
+        if prints.is_empty() {
+            self.bytes += std::mem::size_of::<(ClauseId, Vec<Fingerprint>)>();
+        }
+        prints.push(print);
+        self.bytes += std::mem::size_of::<ClauseId>() + std::mem::size_of::<Fingerprint>();
+    }
+
+    /// Remove every term of clause `id`.
+    pub fn remove(&mut self, id: ClauseId) {
+        let Some(prints) = self.terms.remove(&id) else {
+            return;
+        };
+        self.bytes -= std::mem::size_of::<(ClauseId, Vec<Fingerprint>)>() + prints.len() * std::mem::size_of::<Fingerprint>();
+        for print in prints {
+            self.bytes -= self.root.remove(&print, id);
+        }
+    }
+
+    /// Clauses with a term that may unify with `query`, in ascending order.
+    pub fn unifiable(&self, query: &Term) -> Vec<ClauseId> {
+        let mut found = Vec::new();
+        self.root.retrieve(&fingerprint(query), &mut found);
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+/// Index of clauses by the terms inference rules unify, for finding the
+/// partners of a clause without trying every clause.  Retrieval may
+/// return clauses that turn out to have no inference with the query, and
+/// returns them in the order they were inserted, a
+/// [`replace`](Self::replace)d clause keeping the place of the one it
+/// replaces.
+#[derive(Clone, Debug, Default)]
+pub struct PartnerIndex {
+    /// Place of each clause in insertion order
+    clauses: HashMap<ClauseId, u64>,
+    next_place: u64,
+    /// Atoms of positive literals, for resolving with negative ones
+    positive: FingerprintIndex,
+    negative: FingerprintIndex,
+    /// Every subterm of every atom, the atoms and variables included:
+    /// where paramodulation may rewrite
+    subterms: FingerprintIndex,
+    /// Both sides of positive two-argument literals, by predicate: what
+    /// paramodulation may rewrite with
+    sides: HashMap<SymbolId, FingerprintIndex>,
+}
+
+impl PartnerIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clauses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn contains(&self, id: ClauseId) -> bool {
+        self.clauses.contains_key(&id)
+    }
+
+    /// Approximate bytes held by the term indexes.
+    pub fn memory_bytes(&self) -> usize {
+        self.clauses.len() * std::mem::size_of::<(ClauseId, u64)>()
+            + self.positive.memory_bytes()
+            + self.negative.memory_bytes()
+            + self.subterms.memory_bytes()
+            + self
+                .sides
+                .values()
+                .map(|sides| std::mem::size_of::<(SymbolId, FingerprintIndex)>() + sides.memory_bytes())
+                .sum::<usize>()
+    }
+
+    pub fn insert(&mut self, id: ClauseId, clause: &Clause) {
+        let place = self.next_place;
+        self.next_place += 1;
+        self.insert_at(id, clause, place);
+    }
+
+    /// Put `id` in the place of `old`, which is removed.
+    pub fn replace(&mut self, old: ClauseId, id: ClauseId, clause: &Clause) {
+        match self.clauses.get(&old).copied() {
+            Some(place) => {
+                self.remove(old);
+                self.insert_at(id, clause, place);
+            }
+            None => self.insert(id, clause),
+        }
+    }
+
+    fn insert_at(&mut self, id: ClauseId, clause: &Clause, place: u64) {
+        if self.clauses.contains_key(&id) {
+            return;
+        }
+        self.clauses.insert(id, place);
+        for literal in &clause.literals {
+            let atoms = if literal.sign { &mut self.positive } else { &mut self.negative };
+            atoms.insert(id, &literal.atom);
+            for_each_subterm(&literal.atom, &mut |term| self.subterms.insert(id, term));
+            if let Term::Application { symbol, args } = &literal.atom
+                && literal.sign
+                && args.len() == 2
+            {
+                let sides = self.sides.entry(*symbol).or_default();
+                sides.insert(id, &args[0]);
+                sides.insert(id, &args[1]);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: ClauseId) {
+        if self.clauses.remove(&id).is_none() {
+            return;
+        }
+        self.positive.remove(id);
+        self.negative.remove(id);
+        self.subterms.remove(id);
+        for sides in self.sides.values_mut() {
+            sides.remove(id);
+        }
+    }
+
+    /// Sort `ids` of indexed clauses into insertion order, without repeats.
+    pub fn sort_by_place(&self, ids: &mut Vec<ClauseId>) {
+        ids.sort_unstable_by_key(|id| self.clauses.get(id).copied());
+        ids.dedup();
+    }
+
+    /// Clauses with a literal that may resolve with a literal of `clause`.
+    pub fn resolution_partners(&self, clause: &Clause) -> Vec<ClauseId> {
+        let mut found: Vec<ClauseId> = clause
+            .literals
+            .iter()
+            .flat_map(|literal| {
+                let atoms = if literal.sign { &self.negative } else { &self.positive };
+                atoms.unifiable(&literal.atom)
+            })
+            .collect();
+        self.sort_by_place(&mut found);
+        found
+    }
+
+    /// Clauses that equalities of `from` may paramodulate into.
+    pub fn para_into_partners(&self, from: &Clause, eq_symbol: SymbolId) -> Vec<ClauseId> {
+        let mut found = Vec::new();
+        for literal in &from.literals {
+            if let Term::Application { symbol, args } = &literal.atom
+                && literal.sign
+                && *symbol == eq_symbol
+                && args.len() == 2
+            {
+                found.extend(self.subterms.unifiable(&args[0]));
+                found.extend(self.subterms.unifiable(&args[1]));
+            }
+        }
+        self.sort_by_place(&mut found);
+        found
+    }
+
+    /// Clauses with an equality that may paramodulate into `into`.
+    pub fn para_from_partners(&self, into: &Clause, eq_symbol: SymbolId) -> Vec<ClauseId> {
+        let Some(sides) = self.sides.get(&eq_symbol) else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        for literal in &into.literals {
+            for_each_subterm(&literal.atom, &mut |term| found.extend(sides.unifiable(term)));
+        }
+        self.sort_by_place(&mut found);
+        found
+    }
+}
+
+fn for_each_subterm(term: &Term, visit: &mut impl FnMut(&Term)) {
+    visit(term);
+    if let Term::Application { args, .. } = term {
+        for arg in args {
+            for_each_subterm(arg, visit);
+        }
+    }
+}
+
+fn preorder(term: &Term, path: &mut Vec<DiscKey>) {
+    path.push(DiscKey::of(term));
+    if let Term::Application { args, .. } = term {
+        for arg in args {
+            preorder(arg, path);
+        }
+    }
+}
+
+/// Insert `entry` at the end of `path`, returning how many nodes were
+/// created.
+fn insert_path(nodes: &mut Vec<DiscNode>, path: &[DiscKey], entry: usize) -> usize {
+    let Some((key, rest)) = path.split_first() else {
+        return 0;
+    };
+    let (index, created) = match nodes.iter().position(|node| node.key == *key) {
+        Some(index) => (index, 0),
+        None => {
+            nodes.push(DiscNode { key: key.clone(), children: Vec::new(), entries: Vec::new() });
+            (nodes.len() - 1, 1)
+        }
+    };
+    if rest.is_empty() {
+        nodes[index].entries.push(entry);
+        created
+    } else {
+        created + insert_path(&mut nodes[index].children, rest, entry)
+    }
+}
+
+/// Walk `nodes` against the query subterms still to be matched, which are
+/// on `pending` with the next one last.
+fn retrieve<'a>(
+    nodes: &[DiscNode],
+    pending: &mut Vec<&'a Term>,
+    bindings: &mut Vec<(VariableId, &'a Term)>,
+    found: &mut Vec<usize>,
+) {
+    let Some(term) = pending.pop() else {
+        return;
+    };
+    for node in nodes {
+        let (pushed, bound) = match &node.key {
+            DiscKey::Variable(var) => match bindings.iter().find(|(bound, _)| bound == var) {
+                Some((_, value)) if *value != term => continue,
+                Some(_) => (0, false),
+                None => {
+                    bindings.push((*var, term));
+                    (0, true)
+                }
+            },
+            DiscKey::Symbol(symbol) => match term {
+                Term::Application { symbol: query_symbol, args } if query_symbol == symbol => {
+                    pending.extend(args.iter().rev());
+                    (args.len(), false)
+                }
+                _ => continue,
+            },
+        };
+
+        if pending.is_empty() {
+            found.extend_from_slice(&node.entries);
+        } else {
+            retrieve(&node.children, pending, bindings, found);
+        }
+
+        pending.truncate(pending.len() - pushed);
+        if bound {
+            bindings.pop();
+        }
+    }
+    pending.push(term);
+}
+
+/// [`retrieve`] over shared terms.
+fn retrieve_shared(
+    nodes: &[DiscNode],
+    bank: &TermBank,
+    pending: &mut Vec<TermId>,
+    bindings: &mut Vec<(VariableId, TermId)>,
+    found: &mut Vec<usize>,
+) {
+    let Some(term) = pending.pop() else {
+        return;
+    };
+    for node in nodes {
+        let (pushed, bound) = match &node.key {
+            DiscKey::Variable(var) => match bindings.iter().find(|(bound, _)| bound == var) {
+                Some((_, value)) if *value != term => continue,
+                Some(_) => (0, false),
+                None => {
+                    bindings.push((*var, term));
+                    (0, true)
+                }
+            },
+            DiscKey::Symbol(symbol) if bank.symbol(term) == Some(*symbol) => {
+                let args = bank.args(term);
+                pending.extend(args.iter().rev());
+                (args.len(), false)
+            }
+            DiscKey::Symbol(_) => continue,
+        };
+
+        if pending.is_empty() {
+            found.extend_from_slice(&node.entries);
+        } else {
+            retrieve_shared(&node.children, bank, pending, bindings, found);
+        }
+
+        pending.truncate(pending.len() - pushed);
+        if bound {
+            bindings.pop();
+        }
+    }
+    pending.push(term);
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ImdBfs, ImdNode, ImdNodeKind, IsNode, term_to_imd_kind};
-    use crate::data::{Literal, SymbolKind, SymbolTable, Term, VariableId};
+    use super::{
+        DiscriminationTree, FingerprintIndex, ImdBfs, ImdNode, ImdNodeKind, IsNode, PartnerIndex, SubsumptionIndex,
+        term_to_imd_kind,
+    };
+    use crate::data::{Clause, ClauseId, Literal, SymbolKind, SymbolTable, Term, TermBank, VariableId};
 
     #[test]
     fn term_kind_conversion() {
@@ -132,4 +881,157 @@ mod tests {
         node.attach_literal(literal.clone());
         assert_eq!(node.literals.len(), 1);
     }
+
+    #[test]
+    fn discrimination_tree_retrieves_generalizations() {
+        let table = SymbolTable::new();
+        let a = Term::application(table.intern("a", 0, SymbolKind::Constant), vec![]);
+        let b = Term::application(table.intern("b", 0, SymbolKind::Constant), vec![]);
+        let f = |x: Term, y: Term| Term::application(table.intern("f", 2, SymbolKind::Function), vec![x, y]);
+        let x = Term::variable(VariableId::new(0));
+        let y = Term::variable(VariableId::new(1));
+
+        let mut tree = DiscriminationTree::new();
+        tree.insert(&f(x.clone(), y.clone()), 0);
+        tree.insert(&f(x.clone(), x.clone()), 1);
+        tree.insert(&f(a.clone(), y.clone()), 2);
+        tree.insert(&a, 3);
+        tree.insert(&x, 4);
+        assert_eq!(tree.len(), 5);
+
+        assert_eq!(tree.generalizations(&f(a.clone(), a.clone())), vec![0, 1, 2, 4]);
+        // f(x, x) needs equal arguments
+        assert_eq!(tree.generalizations(&f(b.clone(), a.clone())), vec![0, 4]);
+        assert_eq!(tree.generalizations(&f(f(a.clone(), b.clone()), f(a.clone(), b.clone()))), vec![0, 1, 4]);
+        assert_eq!(tree.generalizations(&a), vec![3, 4]);
+        // Query variables only match pattern variables
+        assert_eq!(tree.generalizations(&f(y.clone(), a.clone())), vec![0, 4]);
+        assert!(tree.memory_bytes() > DiscriminationTree::new().memory_bytes());
+
+        let mut bank = TermBank::new();
+        for query in [f(a.clone(), a.clone()), f(b.clone(), a.clone()), f(y, a.clone()), a] {
+            let id = bank.intern(&query);
+            assert_eq!(tree.generalizations_shared(&bank, id), tree.generalizations(&query));
+        }
+    }
+
+    #[test]
+    fn subsumption_index_filters_by_features() {
+        let table = SymbolTable::new();
+        let a = Term::application(table.intern("a", 0, SymbolKind::Constant), vec![]);
+        let f = |arg: Term| Term::application(table.intern("f", 1, SymbolKind::Function), vec![arg]);
+        let p = |arg: Term| Term::application(table.intern("P", 1, SymbolKind::Predicate), vec![arg]);
+        let q = |arg: Term| Term::application(table.intern("Q", 1, SymbolKind::Predicate), vec![arg]);
+        let x = Term::variable(VariableId::new(0));
+        let clause = |literals: Vec<(bool, Term)>| {
+            Clause::new(literals.into_iter().map(|(sign, atom)| Literal::new(sign, atom)).collect())
+        };
+
+        let mut index = SubsumptionIndex::new();
+        index.insert(ClauseId(1), &clause(vec![(true, p(x.clone()))]));
+        index.insert(ClauseId(2), &clause(vec![(true, p(f(x.clone())))]));
+        index.insert(ClauseId(3), &clause(vec![(true, p(a.clone())), (false, q(a.clone()))]));
+        index.insert(ClauseId(4), &clause(vec![(false, q(x.clone()))]));
+        assert_eq!(index.len(), 4);
+
+        // P(a) | -Q(a) can only be subsumed by clauses without f
+        let query = clause(vec![(true, p(a.clone())), (false, q(a.clone()))]);
+        assert_eq!(index.subsumer_candidates(&query), vec![ClauseId(1), ClauseId(3), ClauseId(4)]);
+        // P(x) may subsume everything with a positive P literal
+        assert_eq!(index.subsumee_candidates(&clause(vec![(true, p(x.clone()))])), vec![ClauseId(1), ClauseId(2), ClauseId(3)]);
+        assert_eq!(index.subsumee_candidates(&clause(vec![(true, p(f(f(x.clone()))))])), vec![]);
+        assert!(index.subsumee_candidates(&clause(vec![(false, p(x.clone()))])).is_empty());
+
+        let bytes = index.memory_bytes();
+        index.remove(ClauseId(1));
+        assert!(!index.contains(ClauseId(1)));
+        assert!(index.memory_bytes() < bytes);
+        assert_eq!(index.subsumer_candidates(&query), vec![ClauseId(3), ClauseId(4)]);
+        assert_eq!(index.subsumee_candidates(&Clause::new(vec![])).len(), 3);
+
+        // The byte count is kept as clauses come and go
+        for id in [2, 3, 4] {
+            index.remove(ClauseId(id));
+        }
+        assert_eq!(index.memory_bytes(), 0);
+    }
+
+    #[test]
+    fn fingerprint_index_skips_clashing_terms() {
+        let table = SymbolTable::new();
+        let a = Term::application(table.intern("a", 0, SymbolKind::Constant), vec![]);
+        let b = Term::application(table.intern("b", 0, SymbolKind::Constant), vec![]);
+        let f = |x: Term, y: Term| Term::application(table.intern("f", 2, SymbolKind::Function), vec![x, y]);
+        let g = |x: Term| Term::application(table.intern("g", 1, SymbolKind::Function), vec![x]);
+        let x = Term::variable(VariableId::new(0));
+
+        let mut index = FingerprintIndex::new();
+        index.insert(ClauseId(1), &f(a.clone(), b.clone()));
+        index.insert(ClauseId(2), &f(x.clone(), g(a.clone())));
+        index.insert(ClauseId(3), &g(x.clone()));
+        index.insert(ClauseId(4), &x);
+        index.insert(ClauseId(4), &a);
+
+        assert_eq!(index.unifiable(&f(a.clone(), x.clone())), vec![ClauseId(1), ClauseId(2), ClauseId(4)]);
+        // g(a) clashes with b below the root of clause 1's term
+        assert_eq!(index.unifiable(&f(b.clone(), g(x.clone()))), vec![ClauseId(2), ClauseId(4)]);
+        assert_eq!(index.unifiable(&g(f(a.clone(), a.clone()))), vec![ClauseId(3), ClauseId(4)]);
+        assert_eq!(index.unifiable(&x).len(), 4);
+
+        index.remove(ClauseId(4));
+        assert!(!index.contains(ClauseId(4)));
+        assert_eq!(index.unifiable(&b), vec![]);
+        assert_eq!(index.len(), 3);
+
+        let bytes = index.memory_bytes();
+        index.remove(ClauseId(3));
+        assert!(index.memory_bytes() < bytes);
+        index.remove(ClauseId(1));
+        index.remove(ClauseId(2));
+        assert_eq!(index.memory_bytes(), 0);
+    }
+
+    #[test]
+    fn partner_index_finds_resolution_and_paramodulation_partners() {
+        let table = SymbolTable::new();
+        let a = Term::application(table.intern("a", 0, SymbolKind::Constant), vec![]);
+        let b = Term::application(table.intern("b", 0, SymbolKind::Constant), vec![]);
+        let eq_symbol = table.intern("=", 2, SymbolKind::Predicate);
+        let eq = |x: Term, y: Term| Term::application(eq_symbol, vec![x, y]);
+        let f = |arg: Term| Term::application(table.intern("f", 1, SymbolKind::Function), vec![arg]);
+        let p = |arg: Term| Term::application(table.intern("P", 1, SymbolKind::Predicate), vec![arg]);
+        let x = Term::variable(VariableId::new(0));
+        let clause = |literals: Vec<(bool, Term)>| {
+            Clause::new(literals.into_iter().map(|(sign, atom)| Literal::new(sign, atom)).collect())
+        };
+
+        let mut index = PartnerIndex::new();
+        index.insert(ClauseId(1), &clause(vec![(true, p(a.clone()))]));
+        index.insert(ClauseId(2), &clause(vec![(false, p(f(x.clone())))]));
+        index.insert(ClauseId(3), &clause(vec![(true, eq(f(a.clone()), b.clone()))]));
+        index.insert(ClauseId(4), &clause(vec![(false, p(b.clone())), (true, p(f(b.clone())))]));
+        assert_eq!(index.len(), 4);
+
+        assert_eq!(index.resolution_partners(&clause(vec![(false, p(x.clone()))])), vec![ClauseId(1), ClauseId(4)]);
+        assert_eq!(index.resolution_partners(&clause(vec![(true, p(f(a.clone())))])), vec![ClauseId(2)]);
+
+        // f(a) = b rewrites the f(...) of clause 2 and the f(a) of clause 3;
+        // f(b) only unifies with f(x) and the variable x
+        let from = clause(vec![(true, eq(f(a.clone()), b.clone()))]);
+        assert_eq!(index.para_into_partners(&from, eq_symbol), vec![ClauseId(2), ClauseId(3), ClauseId(4)]);
+        let into = clause(vec![(true, p(f(f(a.clone()))))]);
+        assert_eq!(index.para_from_partners(&into, eq_symbol), vec![ClauseId(3)]);
+        assert!(index.para_from_partners(&clause(vec![(true, p(a.clone()))]), eq_symbol).is_empty());
+
+        let bytes = index.memory_bytes();
+        index.remove(ClauseId(3));
+        assert!(!index.contains(ClauseId(3)));
+        assert!(index.memory_bytes() < bytes);
+        assert!(index.para_from_partners(&into, eq_symbol).is_empty());
+
+        // A replacement keeps the place of the clause it replaces
+        index.replace(ClauseId(1), ClauseId(5), &clause(vec![(true, p(b.clone()))]));
+        assert!(!index.contains(ClauseId(1)));
+        assert_eq!(index.resolution_partners(&clause(vec![(false, p(x.clone()))])), vec![ClauseId(5), ClauseId(4)]);
+    }
 }
@@ -0,0 +1,344 @@
+//! Structured justifications for derived clauses.
+//!
+//! `Clause::parents` only says which clauses a clause came from.  A
+//! [`Justification`] also says how: which literals were resolved, which
+//! position a paramodulation rewrote, and the simplifications applied to the
+//! result, in the order they ran.  Literal indices are 0-based positions in
+//! the parent clause as stored in the arena.
+
+use super::{ClauseId, InferenceRule};
+
+/// A literal of one of the parents of a [`Inference::Resolution`] step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LiteralPos {
+    /// Index into the step's `parents`
+    pub parent: usize,
+    /// Index of the literal in that parent
+    pub literal: usize,
+}
+
+impl LiteralPos {
+    pub fn new(parent: usize, literal: usize) -> Self {
+        Self { parent, literal }
+    }
+}
+
+/// Two complementary literals that were unified and removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Clash {
+    pub left: LiteralPos,
+    pub right: LiteralPos,
+}
+
+impl Clash {
+    pub fn new(left: LiteralPos, right: LiteralPos) -> Self {
+        Self { left, right }
+    }
+}
+
+/// The inference that produced a clause, before any simplification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inference {
+    /// Resolution between renamed-apart copies of `parents` on the given
+    /// clashes.  Binary, hyper, UR and linked UR resolution all have this
+    /// shape; a clause used twice occurs twice in `parents`.
+    Resolution {
+        parents: Vec<ClauseId>,
+        clashes: Vec<Clash>,
+    },
+    /// Paramodulation from one side of the equality `from.from_literal` into
+    /// the subterm of `into.into_literal` at `position`, a path of 0-based
+    /// argument indices starting at the atom.
+    Paramodulation {
+        from: ClauseId,
+        from_literal: usize,
+        /// The right side was unified with the subterm and the left side
+        /// put in its place
+        from_right: bool,
+        into: ClauseId,
+        into_literal: usize,
+        position: Vec<usize>,
+    },
+    /// Equality resolution: the sides of the disequality `literal` of
+    /// `parent` are unified and the literal dropped.
+    EqualityResolution { parent: ClauseId, literal: usize },
+    /// Equality factoring: side `right` of the equality `literal` of
+    /// `parent` is unified with side `other_right` of the equality `other`,
+    /// and `literal` becomes the disequality of their remaining sides.
+    EqualityFactoring {
+        parent: ClauseId,
+        literal: usize,
+        right: bool,
+        other: usize,
+        other_right: bool,
+    },
+    /// An existing clause, rewritten by the simplifications that follow
+    /// (back demodulation).
+    Copy { parent: ClauseId },
+}
+
+/// A simplification applied after the inference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Simplification {
+    /// Unify literal `merged` with literal `kept` and drop `merged`.
+    Factor { kept: usize, merged: usize },
+    /// Rewrite to normal form with these unit equalities, tried in this
+    /// order; `true` marks an equation used right to left.
+    Demod(Vec<(ClauseId, bool)>),
+    /// Resolve away one literal against each of these unit clauses, in
+    /// order.
+    UnitDel(Vec<ClauseId>),
+    /// Drop `t!=t` literals.
+    XxRes,
+    /// Evaluate built-ins while demodulating and drop the literals that
+    /// evaluate to false.
+    Eval,
+}
+
+impl Simplification {
+    /// Clauses the simplification used.
+    pub fn clause_ids(&self) -> Vec<ClauseId> {
+        match self {
+            Simplification::Demod(steps) => {
+                steps.iter().map(|(id, _)| *id).collect()
+            }
+            Simplification::UnitDel(units) => units.clone(),
+            Simplification::Factor { .. } | Simplification::XxRes | Simplification::Eval => {
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// How a derived clause was obtained.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Justification {
+    pub inference: Inference,
+    pub simplifications: Vec<Simplification>,
+}
+
+impl Justification {
+    pub fn new(inference: Inference) -> Self {
+        Self { inference, simplifications: Vec::new() }
+    }
+
+    /// Binary resolution of `left.literal` with `right.literal`.
+    pub fn binary(
+        left: ClauseId,
+        left_literal: usize,
+        right: ClauseId,
+        right_literal: usize,
+    ) -> Self {
+        Self::new(Inference::Resolution {
+            parents: vec![left, right],
+            clashes: vec![Clash::new(
+                LiteralPos::new(0, left_literal),
+                LiteralPos::new(1, right_literal),
+            )],
+        })
+    }
+
+    /// Resolution of `nucleus` with one satellite per clash, given as
+    /// `(nucleus literal, satellite, satellite literal)`.
+    pub fn nucleus(
+        nucleus: ClauseId,
+        clashes: impl IntoIterator<Item = (usize, ClauseId, usize)>,
+    ) -> Self {
+        let mut parents = vec![nucleus];
+        let mut pairs = Vec::new();
+        for (nucleus_literal, satellite, satellite_literal) in clashes {
+            pairs.push(Clash::new(
+                LiteralPos::new(0, nucleus_literal),
+                LiteralPos::new(parents.len(), satellite_literal),
+            ));
+            parents.push(satellite);
+        }
+        Self::new(Inference::Resolution { parents, clashes: pairs })
+    }
+
+    /// Bytes owned by the justification's vectors.
+    pub fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+        let inference = match &self.inference {
+            Inference::Resolution { parents, clashes } => {
+                parents.capacity() * size_of::<ClauseId>() + clashes.capacity() * size_of::<Clash>()
+            }
+            Inference::Paramodulation { position, .. } => position.capacity() * size_of::<usize>(),
+            Inference::EqualityResolution { .. } | Inference::EqualityFactoring { .. } | Inference::Copy { .. } => 0,
+        };
+        let simplifications = self
+            .simplifications
+            .iter()
+            .map(|simplification| match simplification {
+                Simplification::Demod(steps) => steps.capacity() * size_of::<(ClauseId, bool)>(),
+                Simplification::UnitDel(units) => units.capacity() * size_of::<ClauseId>(),
+                Simplification::Factor { .. } | Simplification::XxRes | Simplification::Eval => 0,
+            })
+            .sum::<usize>();
+        inference + self.simplifications.capacity() * size_of::<Simplification>() + simplifications
+    }
+
+    pub fn push(&mut self, simplification: Simplification) {
+        self.simplifications.push(simplification);
+    }
+
+    /// Every clause the justification refers to, inference parents first.
+    pub fn clause_ids(&self) -> Vec<ClauseId> {
+        let mut ids = match &self.inference {
+            Inference::Resolution { parents, .. } => parents.clone(),
+            Inference::Paramodulation { from, into, .. } => vec![*from, *into],
+            Inference::EqualityResolution { parent, .. }
+            | Inference::EqualityFactoring { parent, .. }
+            | Inference::Copy { parent } => vec![*parent],
+        };
+        for simplification in &self.simplifications {
+            ids.extend(simplification.clause_ids());
+        }
+        ids
+    }
+
+    /// Otter's justification list, e.g. `binary,3.1,5.2,demod,7,unit_del,4`.
+    /// Literal numbers and term positions are 1-based, as in Otter.
+    pub fn otter_string(&self, rule: Option<InferenceRule>) -> String {
+        let mut parts = Vec::new();
+        let name = rule.map_or("unknown", InferenceRule::name).to_string();
+        match &self.inference {
+            Inference::Resolution { parents, clashes }
+                if rule == Some(InferenceRule::BinaryRes) && clashes.len() == 1 =>
+            {
+                parts.push(name);
+                for pos in [clashes[0].left, clashes[0].right] {
+                    parts.push(format!(
+                        "{}.{}",
+                        parents[pos.parent].0,
+                        pos.literal + 1
+                    ));
+                }
+            }
+            Inference::Resolution { parents, .. } => {
+                parts.push(name);
+                parts.extend(parents.iter().map(|id| id.0.to_string()));
+            }
+            Inference::Paramodulation {
+                from,
+                from_literal,
+                from_right,
+                into,
+                into_literal,
+                position,
+            } => {
+                let from_pos = format!(
+                    "{}.{}.{}",
+                    from.0,
+                    from_literal + 1,
+                    if *from_right { 2 } else { 1 }
+                );
+                let mut into_pos = format!("{}.{}", into.0, into_literal + 1);
+                for arg in position {
+                    into_pos.push_str(&format!(".{}", arg + 1));
+                }
+                parts.push(name);
+                if rule == Some(InferenceRule::ParaInto) {
+                    parts.push(into_pos);
+                    parts.push(from_pos);
+                } else {
+                    parts.push(from_pos);
+                    parts.push(into_pos);
+                }
+            }
+            Inference::EqualityResolution { parent, literal } => {
+                parts.push(name);
+                parts.push(format!("{}.{}", parent.0, literal + 1));
+            }
+            Inference::EqualityFactoring { parent, literal, right, other, other_right } => {
+                parts.push(name);
+                for (index, side) in [(literal, right), (other, other_right)] {
+                    parts.push(format!("{}.{}.{}", parent.0, index + 1, if *side { 2 } else { 1 }));
+                }
+            }
+            Inference::Copy { parent } => {
+                parts.push(name);
+                parts.push(parent.0.to_string());
+            }
+        }
+        for simplification in &self.simplifications {
+            match simplification {
+                Simplification::Factor { .. } => {
+                    parts.push("factor_simp".to_string());
+                }
+                Simplification::Demod(steps) => {
+                    parts.push("demod".to_string());
+                    parts.extend(steps.iter().map(|(id, _)| id.0.to_string()));
+                }
+                Simplification::UnitDel(units) => {
+                    parts.push("unit_del".to_string());
+                    parts.extend(units.iter().map(|id| id.0.to_string()));
+                }
+                Simplification::XxRes => parts.push("xx_res".to_string()),
+                Simplification::Eval => parts.push("eval".to_string()),
+            }
+        }
+        parts.join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Inference, Justification, Simplification};
+    use crate::data::{ClauseId, InferenceRule};
+
+    #[test]
+    fn otter_string_numbers_literals_from_one() {
+        let mut justification =
+            Justification::binary(ClauseId(3), 0, ClauseId(5), 1);
+        justification.push(Simplification::Demod(vec![(ClauseId(7), false)]));
+        justification.push(Simplification::UnitDel(vec![ClauseId(4)]));
+        assert_eq!(
+            justification.otter_string(Some(InferenceRule::BinaryRes)),
+            "binary,3.1,5.2,demod,7,unit_del,4"
+        );
+        assert_eq!(
+            justification.clause_ids(),
+            vec![ClauseId(3), ClauseId(5), ClauseId(7), ClauseId(4)]
+        );
+    }
+
+    #[test]
+    fn paramodulation_positions_follow_the_rule() {
+        let justification = Justification::new(Inference::Paramodulation {
+            from: ClauseId(2),
+            from_literal: 0,
+            from_right: false,
+            into: ClauseId(5),
+            into_literal: 0,
+            position: vec![1, 0],
+        });
+        assert_eq!(
+            justification.otter_string(Some(InferenceRule::ParaInto)),
+            "para_into,5.1.2.1,2.1.1"
+        );
+        assert_eq!(
+            justification.otter_string(Some(InferenceRule::ParaFrom)),
+            "para_from,2.1.1,5.1.2.1"
+        );
+        assert_eq!(
+            justification.otter_string(Some(InferenceRule::Superposition)),
+            "superposition,2.1.1,5.1.2.1"
+        );
+    }
+
+    #[test]
+    fn equality_rules_name_literals_and_sides() {
+        let resolution = Justification::new(Inference::EqualityResolution { parent: ClauseId(4), literal: 1 });
+        assert_eq!(resolution.otter_string(Some(InferenceRule::EqualityResolution)), "eq_res,4.2");
+        let factoring = Justification::new(Inference::EqualityFactoring {
+            parent: ClauseId(4),
+            literal: 0,
+            right: false,
+            other: 2,
+            other_right: true,
+        });
+        assert_eq!(factoring.otter_string(Some(InferenceRule::EqualityFactoring)), "eq_factor,4.1.1,4.3.2");
+        assert_eq!(factoring.clause_ids(), vec![ClauseId(4)]);
+    }
+}
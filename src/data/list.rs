@@ -32,6 +32,18 @@ impl ClauseList {
         }
     }
 
+    /// Put `id` in place of the member at `index`, returning the old member.
+    pub fn replace(&mut self, index: usize, id: ClauseId) -> Option<ClauseId> {
+        self.members
+            .get_mut(index)
+            .map(|member| std::mem::replace(member, id))
+    }
+
+    /// Keep only the members for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(ClauseId) -> bool) {
+        self.members.retain(|id| keep(*id));
+    }
+
     pub fn len(&self) -> usize {
         self.members.len()
     }
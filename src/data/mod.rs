@@ -9,23 +9,32 @@ pub mod clause;
 pub mod clause_store;
 pub mod context;
 pub mod indexing;
+pub mod justification;
 pub mod list;
 pub mod literal;
 pub mod ordering;
 pub mod parent;
+pub mod print;
 pub mod symbol;
 pub mod term;
+pub mod term_bank;
 pub mod weight;
 
 pub use attribute::{ClauseAttribute, ClauseAttributeValue};
-pub use clause::{Clause, ClauseId};
-pub use clause_store::ClauseArena;
-pub use context::{Context, ContextStatus, MAX_VARS, Trail};
-pub use indexing::{ImdBfs, ImdNode, ImdNodeKind, IsNode, term_to_imd_kind};
+pub use clause::{Clause, ClauseId, InferenceRule};
+pub use clause_store::{ClauseArena, ClauseState};
+pub use context::{Context, ContextStatus, Trail};
+pub use indexing::{
+    DiscriminationTree, FeatureVector, FingerprintIndex, ImdBfs, ImdNode, ImdNodeKind, IsNode, PartnerIndex,
+    SubsumptionIndex, term_to_imd_kind,
+};
+pub use justification::{Clash, Inference, Justification, LiteralPos, Simplification};
 pub use list::ClauseList;
 pub use literal::Literal;
-pub use ordering::LRPO;
+pub use ordering::{KBO, LRPO, SymbolPrecedence, TermOrdering};
 pub use parent::ParentList;
+pub use print::{format_clause, format_literal, format_term, variable_name};
 pub use symbol::{Symbol, SymbolId, SymbolKind, SymbolTable};
 pub use term::{Term, TermKind, VariableId};
-pub use weight::WeightTable;
+pub use term_bank::{SharedTerm, TermBank, TermId};
+pub use weight::{WeightPattern, WeightTable, WeightTemplate};
@@ -1,17 +1,20 @@
-//! Term ordering for orienting equalities.
+//! Term orderings for orienting equalities.
 //!
-//! Implements LRPO (Lexicographic Recursive Path Ordering), which is used to:
+//! A [`TermOrdering`] is used to:
 //! - Orient equalities (determine if l=r should be rewritten as l→r or r→l)
 //! - Prevent infinite loops in paramodulation and demodulation
 //! - Guide the search by choosing "simpler" terms
 //!
-//! This implementation follows C Foras's lrpo.c closely to ensure compatibility.
+//! Two are provided: LRPO, which follows C Foras's lrpo.c closely to ensure
+//! compatibility, and the Knuth-Bendix ordering, selected by `set(kbo)`.
 
-use crate::data::{SymbolId, Term};
+use crate::data::{SharedTerm, SymbolId, Term, TermBank, TermId, VariableId, WeightTable};
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
 
-/// Maximum recursion depth for LRPO to prevent stack overflow
-const MAX_LRPO_DEPTH: usize = 100;
+/// Maximum recursion depth for the orderings to prevent stack overflow
+const MAX_ORDERING_DEPTH: usize = 100;
 
 /// Precedence comparison result
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,56 +25,101 @@ enum Precedence {
     NotComparable,
 }
 
-/// Lexicographic Recursive Path Ordering (LRPO) for terms.
+/// A simplification ordering on terms.
 ///
-/// LRPO is a simplification ordering that satisfies:
-/// - Well-founded: No infinite descending chains
-/// - Total on ground terms: Any two ground terms can be compared
-/// - Compatible with term structure: If s > t then f(...s...) > f(...t...)
+/// Only [`greater`](Self::greater) must be provided; the shared-term
+/// methods fall back to converting their arguments to owned terms.
+pub trait TermOrdering: fmt::Debug {
+    /// Check if s > t in the ordering.
+    fn greater(&self, s: &Term, t: &Term) -> bool;
+
+    /// Compare two terms.
+    ///
+    /// Returns:
+    /// - `Ordering::Greater` if `s > t`
+    /// - `Ordering::Less` if `s < t`
+    /// - `Ordering::Equal` if `s = t` syntactically or if incomparable
+    ///
+    /// Note: When terms are incomparable, we return Equal rather than having
+    /// a separate Incomparable variant. Callers should use `greater()` for
+    /// definitive ordering checks.
+    fn compare(&self, s: &Term, t: &Term) -> Ordering {
+        if terms_identical(s, t) {
+            Ordering::Equal
+        } else if self.greater(s, t) {
+            Ordering::Greater
+        } else if self.greater(t, s) {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Check if s >= t in the ordering.
+    fn greater_or_equal(&self, s: &Term, t: &Term) -> bool {
+        terms_identical(s, t) || self.greater(s, t)
+    }
+
+    /// [`greater`](Self::greater) for terms of a [`TermBank`].
+    fn greater_shared(&self, bank: &TermBank, s: TermId, t: TermId) -> bool {
+        self.greater(&bank.to_term(s), &bank.to_term(t))
+    }
+
+    /// [`compare`](Self::compare) for terms of a [`TermBank`].
+    fn compare_shared(&self, bank: &TermBank, s: TermId, t: TermId) -> Ordering {
+        if s == t {
+            Ordering::Equal
+        } else if self.greater_shared(bank, s, t) {
+            Ordering::Greater
+        } else if self.greater_shared(bank, t, s) {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// Symbol precedence set by the `lex` command.
 ///
-/// The ordering is based on:
-/// 1. Symbol precedence (set via lex() directive, lower value = higher precedence)
-/// 2. Lexicographic comparison of arguments for same function symbol
-/// 3. Variables are smaller than non-variable terms that contain them
-#[derive(Debug, Clone)]
-pub struct LRPO {
-    /// Symbol precedence (lower value = higher precedence)
-    /// If not specified, symbols are NOT_COMPARABLE (like C Foras)
-    precedence: Vec<(SymbolId, u32)>,
+/// A lower value is a higher precedence (like C Foras's lex_val); symbols
+/// without a value are not comparable with any other symbol.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolPrecedence {
+    values: Vec<(SymbolId, u32)>,
 }
 
-impl LRPO {
-    /// Create a new LRPO ordering with default precedence.
+impl SymbolPrecedence {
     pub fn new() -> Self {
-        Self {
-            precedence: Vec::new(),
-        }
+        Self::default()
     }
 
     /// Set explicit precedence for a symbol (lower value = higher precedence).
-    pub fn set_precedence(&mut self, symbol: SymbolId, prec: u32) {
+    pub fn set(&mut self, symbol: SymbolId, prec: u32) {
         // Remove old precedence if exists
-        self.precedence.retain(|(s, _)| *s != symbol);
-        self.precedence.push((symbol, prec));
+        self.values.retain(|(s, _)| *s != symbol);
+        self.values.push((symbol, prec));
     }
 
     /// Get precedence for a symbol.
     /// Returns None if the symbol has no explicit precedence (incomparable).
-    fn get_precedence(&self, symbol: SymbolId) -> Option<u32> {
-        self.precedence
-            .iter()
-            .find(|(s, _)| *s == symbol)
-            .map(|(_, p)| *p)
+    pub fn get(&self, symbol: SymbolId) -> Option<u32> {
+        self.values.iter().find(|(s, _)| *s == symbol).map(|(_, p)| *p)
+    }
+
+    /// Whether `symbol` alone has the highest precedence.
+    pub fn is_greatest(&self, symbol: SymbolId) -> bool {
+        self.get(symbol)
+            .is_some_and(|prec| self.values.iter().all(|(s, p)| *s == symbol || *p > prec))
     }
 
     /// Compare symbol precedence (following C Foras's sym_precedence).
     /// In C Foras, LOWER lex_val means HIGHER precedence.
-    fn sym_precedence(&self, sym1: SymbolId, sym2: SymbolId) -> Precedence {
+    fn compare(&self, sym1: SymbolId, sym2: SymbolId) -> Precedence {
         if sym1 == sym2 {
             return Precedence::SameAs;
         }
 
-        match (self.get_precedence(sym1), self.get_precedence(sym2)) {
+        match (self.get(sym1), self.get(sym2)) {
             (Some(p1), Some(p2)) => {
                 // Lower value = higher precedence (like C Foras)
                 if p1 < p2 {
@@ -87,6 +135,65 @@ impl LRPO {
             _ => Precedence::NotComparable,
         }
     }
+}
+
+/// Check if two terms are structurally identical, ignoring variable names.
+pub(crate) fn terms_identical(t1: &Term, t2: &Term) -> bool {
+    match (t1, t2) {
+        (Term::Variable { id: x, .. }, Term::Variable { id: y, .. }) => x == y,
+        (
+            Term::Application {
+                symbol: s1,
+                args: args1,
+            },
+            Term::Application {
+                symbol: s2,
+                args: args2,
+            },
+        ) => {
+            s1 == s2
+                && args1.len() == args2.len()
+                && args1
+                    .iter()
+                    .zip(args2.iter())
+                    .all(|(a, b)| terms_identical(a, b))
+        }
+        _ => false,
+    }
+}
+
+/// Lexicographic Recursive Path Ordering (LRPO) for terms.
+///
+/// LRPO is a simplification ordering that satisfies:
+/// - Well-founded: No infinite descending chains
+/// - Total on ground terms: Any two ground terms can be compared
+/// - Compatible with term structure: If s > t then f(...s...) > f(...t...)
+///
+/// The ordering is based on:
+/// 1. Symbol precedence (set via lex() directive, lower value = higher precedence)
+/// 2. Lexicographic comparison of arguments for same function symbol
+/// 3. Variables are smaller than non-variable terms that contain them
+#[derive(Debug, Clone, Default)]
+pub struct LRPO {
+    /// Symbol precedence; unlisted symbols are NOT_COMPARABLE (like C Foras)
+    precedence: SymbolPrecedence,
+}
+
+impl LRPO {
+    /// Create a new LRPO ordering with default precedence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an LRPO ordering with the given precedence.
+    pub fn with_precedence(precedence: SymbolPrecedence) -> Self {
+        Self { precedence }
+    }
+
+    /// Set explicit precedence for a symbol (lower value = higher precedence).
+    pub fn set_precedence(&mut self, symbol: SymbolId, prec: u32) {
+        self.precedence.set(symbol, prec);
+    }
 
     /// Check if t1 > t2 in LRPO (matches C Foras's lrpo function).
     ///
@@ -94,7 +201,7 @@ impl LRPO {
     /// Returns true if t1 is strictly greater than t2.
     fn lrpo_gt(&self, t1: &Term, t2: &Term, depth: usize) -> bool {
         // Prevent stack overflow
-        if depth > MAX_LRPO_DEPTH {
+        if depth > MAX_ORDERING_DEPTH {
             return false;
         }
 
@@ -122,7 +229,7 @@ impl LRPO {
                 }
 
                 // Different symbols: compare precedence (C Foras line 238-255)
-                let prec = self.sym_precedence(*f, *g);
+                let prec = self.precedence.compare(*f, *g);
 
                 match prec {
                     Precedence::SameAs => {
@@ -138,7 +245,7 @@ impl LRPO {
                     Precedence::LessThan | Precedence::NotComparable => {
                         // f < g or incomparable: return (some arg of t1 >= t2) (C Foras line 247-254)
                         s_args.iter().any(|s_arg| {
-                            self.terms_identical(s_arg, t2) || self.lrpo_gt(s_arg, t2, depth + 1)
+                            terms_identical(s_arg, t2) || self.lrpo_gt(s_arg, t2, depth + 1)
                         })
                     }
                 }
@@ -157,7 +264,7 @@ impl LRPO {
     ) -> bool {
         // Skip over identical arguments (C Foras line 77-79)
         let mut i = 0;
-        while i < s_args.len() && self.terms_identical(&s_args[i], &t_args[i]) {
+        while i < s_args.len() && terms_identical(&s_args[i], &t_args[i]) {
             i += 1;
         }
 
@@ -175,36 +282,11 @@ impl LRPO {
             // First differing arg: s_args[i] <= t_args[i]
             // Return (some remaining arg of t1 >= t2) (C Foras line 90-96)
             s_args.iter().skip(i + 1).any(|s_arg| {
-                self.terms_identical(s_arg, t2) || self.lrpo_gt(s_arg, t2, depth + 1)
+                terms_identical(s_arg, t2) || self.lrpo_gt(s_arg, t2, depth + 1)
             })
         }
     }
 
-    /// Check if two terms are structurally identical.
-    fn terms_identical(&self, t1: &Term, t2: &Term) -> bool {
-        match (t1, t2) {
-            (Term::Variable { id: x, .. }, Term::Variable { id: y, .. }) => x == y,
-            (
-                Term::Application {
-                    symbol: s1,
-                    args: args1,
-                },
-                Term::Application {
-                    symbol: s2,
-                    args: args2,
-                },
-            ) => {
-                s1 == s2
-                    && args1.len() == args2.len()
-                    && args1
-                        .iter()
-                        .zip(args2.iter())
-                        .all(|(a, b)| self.terms_identical(a, b))
-            }
-            _ => false,
-        }
-    }
-
     /// Check if variable v occurs in term t.
     fn occurs_in(&self, v: &Term, t: &Term) -> bool {
         match v {
@@ -214,7 +296,7 @@ impl LRPO {
     }
 
     fn occurs_in_impl(&self, v: &Term, t: &Term) -> bool {
-        if self.terms_identical(v, t) {
+        if terms_identical(v, t) {
             return true;
         }
         match t {
@@ -223,44 +305,199 @@ impl LRPO {
         }
     }
 
-    /// Compare two terms using LRPO.
-    ///
-    /// Returns:
-    /// - `Ordering::Greater` if `s > t`
-    /// - `Ordering::Less` if `s < t`
-    /// - `Ordering::Equal` if `s = t` syntactically or if incomparable
-    ///
-    /// Note: When terms are incomparable, we return Equal rather than having
-    /// a separate Incomparable variant. Callers should use `greater()` for
-    /// definitive ordering checks.
-    pub fn compare(&self, s: &Term, t: &Term) -> Ordering {
-        if self.terms_identical(s, t) {
-            Ordering::Equal
-        } else if self.lrpo_gt(s, t, 0) {
-            Ordering::Greater
-        } else if self.lrpo_gt(t, s, 0) {
-            Ordering::Less
-        } else {
-            // Incomparable - return Equal as a neutral choice
-            // The demod code uses weight as primary criterion anyway
-            Ordering::Equal
+    /// [`lrpo_gt`](Self::lrpo_gt) over shared terms.
+    fn lrpo_gt_shared(&self, bank: &TermBank, t1: TermId, t2: TermId, depth: usize) -> bool {
+        if depth > MAX_ORDERING_DEPTH || t1 == t2 {
+            return false;
+        }
+        let Some(f) = bank.symbol(t1) else {
+            return false;
+        };
+        let Some(g) = bank.symbol(t2) else {
+            return bank.variable_id(t2).is_some_and(|var| bank.occurs(var, t1));
+        };
+        let (s_args, t_args) = (bank.args(t1), bank.args(t2));
+        if f == g && s_args.len() == t_args.len() {
+            let Some(i) = (0..s_args.len()).find(|i| s_args[*i] != t_args[*i]) else {
+                return false;
+            };
+            return if self.lrpo_gt_shared(bank, s_args[i], t_args[i], depth + 1) {
+                t_args[i + 1..].iter().all(|t_arg| self.lrpo_gt_shared(bank, t1, *t_arg, depth + 1))
+            } else {
+                s_args[i + 1..].iter().any(|s_arg| {
+                    *s_arg == t2 || self.lrpo_gt_shared(bank, *s_arg, t2, depth + 1)
+                })
+            };
+        }
+        match self.precedence.compare(f, g) {
+            Precedence::SameAs => false,
+            Precedence::GreaterThan => t_args.iter().all(|t_arg| self.lrpo_gt_shared(bank, t1, *t_arg, depth + 1)),
+            Precedence::LessThan | Precedence::NotComparable => s_args.iter().any(|s_arg| {
+                *s_arg == t2 || self.lrpo_gt_shared(bank, *s_arg, t2, depth + 1)
+            }),
         }
     }
+}
 
-    /// Check if s > t in LRPO ordering.
-    pub fn greater(&self, s: &Term, t: &Term) -> bool {
+impl TermOrdering for LRPO {
+    fn greater(&self, s: &Term, t: &Term) -> bool {
         self.lrpo_gt(s, t, 0)
     }
 
-    /// Check if s >= t in LRPO ordering.
-    pub fn greater_or_equal(&self, s: &Term, t: &Term) -> bool {
-        self.terms_identical(s, t) || self.lrpo_gt(s, t, 0)
+    fn greater_shared(&self, bank: &TermBank, s: TermId, t: TermId) -> bool {
+        self.lrpo_gt_shared(bank, s, t, 0)
     }
 }
 
-impl Default for LRPO {
-    fn default() -> Self {
-        Self::new()
+/// Knuth-Bendix ordering (KBO) for terms.
+///
+/// Terms are compared by weight first: each variable weighs 1 and each
+/// symbol what [`WeightTable::symbol_weight`] gives it, and `s > t` also
+/// needs every variable to occur in `s` at least as often as in `t`.  Terms
+/// of equal weight are compared by the precedence of their root symbols,
+/// then lexicographically by their arguments.  Unlike LRPO, KBO orients
+/// equations such as `g(x) * x = e` and associativity without any `lex`.
+///
+/// For the ordering to be well-founded, constants weigh at least 1 and a
+/// unary symbol may only weigh 0 if it alone has the highest precedence;
+/// other weights below that are raised to 1.
+#[derive(Debug, Clone)]
+pub struct KBO {
+    precedence: SymbolPrecedence,
+    weights: WeightTable,
+}
+
+impl KBO {
+    /// Create a KBO ordering from a precedence and the symbol weights of
+    /// `weights`.
+    pub fn new(precedence: SymbolPrecedence, weights: WeightTable) -> Self {
+        Self { precedence, weights }
+    }
+
+    fn symbol_weight(&self, symbol: SymbolId, arity: usize) -> i64 {
+        let weight = i64::from(self.weights.symbol_weight(symbol));
+        match arity {
+            0 => weight.max(1),
+            1 if weight <= 0 && !self.precedence.is_greatest(symbol) => 1,
+            _ => weight.max(0),
+        }
+    }
+
+    /// Add the weight of `term` times `sign` to `weight`, and its variable
+    /// occurrences times `sign` to `vars`.
+    fn weigh(&self, term: &Term, sign: i64, weight: &mut i64, vars: &mut HashMap<VariableId, i64>) {
+        match term {
+            Term::Variable { id, .. } => {
+                *weight += sign;
+                *vars.entry(*id).or_default() += sign;
+            }
+            Term::Application { symbol, args } => {
+                *weight += sign * self.symbol_weight(*symbol, args.len());
+                for arg in args {
+                    self.weigh(arg, sign, weight, vars);
+                }
+            }
+        }
+    }
+
+    /// Check if t1 > t2 in KBO.
+    fn kbo_gt(&self, t1: &Term, t2: &Term, depth: usize) -> bool {
+        if depth > MAX_ORDERING_DEPTH {
+            return false;
+        }
+        let mut balance = 0;
+        let mut vars = HashMap::new();
+        self.weigh(t1, 1, &mut balance, &mut vars);
+        self.weigh(t2, -1, &mut balance, &mut vars);
+        if vars.values().any(|count| *count < 0) {
+            return false;
+        }
+        if balance != 0 {
+            return balance > 0;
+        }
+
+        match (t1, t2) {
+            (Term::Variable { .. }, _) => false,
+            // Of equal weight and containing the variable, t1 can only be
+            // f(...f(x)...) for a unary f of weight 0
+            (Term::Application { .. }, Term::Variable { .. }) => true,
+            (
+                Term::Application { symbol: f, args: s_args },
+                Term::Application { symbol: g, args: t_args },
+            ) => {
+                if f == g && s_args.len() == t_args.len() {
+                    s_args
+                        .iter()
+                        .zip(t_args)
+                        .find(|(s_arg, t_arg)| !terms_identical(s_arg, t_arg))
+                        .is_some_and(|(s_arg, t_arg)| self.kbo_gt(s_arg, t_arg, depth + 1))
+                } else {
+                    self.precedence.compare(*f, *g) == Precedence::GreaterThan
+                }
+            }
+        }
+    }
+
+    /// [`weigh`](Self::weigh) for a term of a [`TermBank`].
+    fn weigh_shared(&self, bank: &TermBank, term: TermId, sign: i64, weight: &mut i64, vars: &mut HashMap<VariableId, i64>) {
+        match bank.get(term) {
+            SharedTerm::Variable { id, .. } => {
+                *weight += sign;
+                *vars.entry(*id).or_default() += sign;
+            }
+            SharedTerm::Application { symbol, args } => {
+                *weight += sign * self.symbol_weight(*symbol, args.len());
+                for arg in args.iter() {
+                    self.weigh_shared(bank, *arg, sign, weight, vars);
+                }
+            }
+        }
+    }
+
+    /// [`kbo_gt`](Self::kbo_gt) for terms of a [`TermBank`].
+    fn kbo_gt_shared(&self, bank: &TermBank, t1: TermId, t2: TermId, depth: usize) -> bool {
+        if depth > MAX_ORDERING_DEPTH || t1 == t2 {
+            return false;
+        }
+        let mut balance = 0;
+        let mut vars = HashMap::new();
+        self.weigh_shared(bank, t1, 1, &mut balance, &mut vars);
+        self.weigh_shared(bank, t2, -1, &mut balance, &mut vars);
+        if vars.values().any(|count| *count < 0) {
+            return false;
+        }
+        if balance != 0 {
+            return balance > 0;
+        }
+
+        match (bank.get(t1), bank.get(t2)) {
+            (SharedTerm::Variable { .. }, _) => false,
+            (SharedTerm::Application { .. }, SharedTerm::Variable { .. }) => true,
+            (
+                SharedTerm::Application { symbol: f, args: s_args },
+                SharedTerm::Application { symbol: g, args: t_args },
+            ) => {
+                if f == g && s_args.len() == t_args.len() {
+                    s_args
+                        .iter()
+                        .zip(t_args.iter())
+                        .find(|(s_arg, t_arg)| s_arg != t_arg)
+                        .is_some_and(|(s_arg, t_arg)| self.kbo_gt_shared(bank, *s_arg, *t_arg, depth + 1))
+                } else {
+                    self.precedence.compare(*f, *g) == Precedence::GreaterThan
+                }
+            }
+        }
+    }
+}
+
+impl TermOrdering for KBO {
+    fn greater(&self, s: &Term, t: &Term) -> bool {
+        self.kbo_gt(s, t, 0)
+    }
+
+    fn greater_shared(&self, bank: &TermBank, s: TermId, t: TermId) -> bool {
+        self.kbo_gt_shared(bank, s, t, 0)
     }
 }
 
@@ -408,4 +645,107 @@ mod tests {
         assert!(!lrpo.greater(&f_x, &g_x));
         assert!(!lrpo.greater(&g_x, &f_x));
     }
+
+    #[test]
+    fn lrpo_on_shared_terms_agrees_with_owned_terms() {
+        let table = SymbolTable::new();
+        let mut lrpo = LRPO::new();
+        let x = make_var(0);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let f = |args: Vec<Term>| make_fun(&table, "f", args);
+        let g = |args: Vec<Term>| make_fun(&table, "g", args);
+        for (prec, (name, arity)) in [("f", 2), ("g", 2), ("a", 0), ("b", 0)].into_iter().enumerate() {
+            lrpo.set_precedence(table.intern(name, arity, SymbolKind::Function), prec as u32);
+        }
+
+        let terms = [
+            x.clone(),
+            a.clone(),
+            f(vec![x.clone(), a.clone()]),
+            f(vec![a.clone(), b.clone()]),
+            f(vec![b.clone(), a.clone()]),
+            g(vec![f(vec![x.clone(), x.clone()]), x.clone()]),
+            g(vec![a, b]),
+        ];
+        let mut bank = TermBank::new();
+        let ids: Vec<TermId> = terms.iter().map(|term| bank.intern(term)).collect();
+        for (s, s_id) in terms.iter().zip(&ids) {
+            for (t, t_id) in terms.iter().zip(&ids) {
+                assert_eq!(lrpo.greater_shared(&bank, *s_id, *t_id), lrpo.greater(s, t));
+                assert_eq!(lrpo.compare_shared(&bank, *s_id, *t_id), lrpo.compare(s, t));
+            }
+        }
+        assert!(lrpo.greater_shared(&bank, ids[3], ids[4]) || lrpo.greater_shared(&bank, ids[4], ids[3]));
+    }
+
+    #[test]
+    fn kbo_orients_group_axioms_without_precedence() {
+        let table = SymbolTable::new();
+        let kbo = KBO::new(SymbolPrecedence::new(), WeightTable::new());
+        let lrpo = LRPO::new();
+        let x = make_var(0);
+        let y = make_var(1);
+        let z = make_var(2);
+        let e = make_const(&table, "e");
+        let mul = |s: Term, t: Term| make_fun(&table, "*", vec![s, t]);
+        let g = |t: Term| make_fun(&table, "g", vec![t]);
+
+        // g(x) * x = e and associativity, which LRPO leaves unoriented
+        let inverse = mul(g(x.clone()), x.clone());
+        assert!(kbo.greater(&inverse, &e));
+        assert_eq!(lrpo.compare(&inverse, &e), Ordering::Equal);
+        let left = mul(mul(x.clone(), y.clone()), z.clone());
+        let right = mul(x.clone(), mul(y.clone(), z.clone()));
+        assert_eq!(kbo.compare(&left, &right), Ordering::Greater);
+        assert!(kbo.greater(&mul(e.clone(), x.clone()), &x));
+
+        // Lighter, or missing a variable of the other side
+        assert!(!kbo.greater(&e, &inverse));
+        assert!(!kbo.greater(&mul(x.clone(), x.clone()), &g(y.clone())));
+        // Commutativity stays unoriented
+        assert_eq!(kbo.compare(&mul(x.clone(), y.clone()), &mul(y.clone(), x.clone())), Ordering::Equal);
+    }
+
+    #[test]
+    fn kbo_breaks_weight_ties_by_precedence() {
+        let table = SymbolTable::new();
+        let x = make_var(0);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let f = |t: Term| make_fun(&table, "f", vec![t]);
+        let h = |t: Term| make_fun(&table, "h", vec![t]);
+        let symbol = |term: &Term| term.symbol().unwrap();
+
+        let mut precedence = SymbolPrecedence::new();
+        precedence.set(symbol(&f(x.clone())), 0);
+        precedence.set(symbol(&a), 1);
+        precedence.set(symbol(&b), 2);
+        let mut weights = WeightTable::new();
+        weights.set_weight(symbol(&f(x.clone())), 0);
+        weights.set_weight(symbol(&h(x.clone())), 0);
+        let kbo = KBO::new(precedence, weights);
+
+        assert!(kbo.greater(&a, &b));
+        assert!(kbo.greater(&h(a.clone()), &h(b.clone())));
+        assert!(!kbo.greater(&h(b.clone()), &h(a.clone())));
+        // f has the highest precedence, so it may weigh 0: f(x) > x by
+        // precedence alone.  h does not, so it weighs 1 after all.
+        assert!(kbo.greater(&f(x.clone()), &x));
+        assert!(kbo.greater(&f(a.clone()), &a));
+        assert!(kbo.greater(&h(x.clone()), &x));
+        assert!(kbo.greater(&h(h(a.clone())), &f(f(a.clone()))));
+
+        let mut bank = TermBank::new();
+        let (fx, xid) = (bank.intern(&f(x.clone())), bank.intern(&x));
+        assert!(kbo.greater_shared(&bank, fx, xid));
+        assert_eq!(kbo.compare_shared(&bank, xid, fx), Ordering::Less);
+        let terms = [a.clone(), b.clone(), h(a.clone()), h(b), f(f(a.clone())), h(h(a)), f(x.clone()), x];
+        let ids: Vec<TermId> = terms.iter().map(|term| bank.intern(term)).collect();
+        for (s, s_id) in terms.iter().zip(&ids) {
+            for (t, t_id) in terms.iter().zip(&ids) {
+                assert_eq!(kbo.greater_shared(&bank, *s_id, *t_id), kbo.greater(s, t), "{:?} > {:?}", s, t);
+            }
+        }
+    }
 }
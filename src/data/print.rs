@@ -0,0 +1,186 @@
+//! Otter-style printing of terms, literals and clauses.
+//!
+//! Terms only carry symbol ids, so printing goes through the
+//! [`SymbolTable`].  Variables keep the names they were written with as long
+//! as every variable of what is printed has a name of its own; otherwise
+//! they are named the way Otter prints them: `x`, `y`, `z`, `u`, `v`, `w` for
+//! the first six ids and `v<N>` beyond that.
+
+use super::{Clause, Literal, SymbolTable, Term, VariableId};
+use std::collections::HashMap;
+
+/// Otter's name for a variable id.
+pub fn variable_name(id: VariableId) -> String {
+    const NAMES: [&str; 6] = ["x", "y", "z", "u", "v", "w"];
+    match NAMES.get(id.0 as usize) {
+        Some(name) => (*name).to_string(),
+        None => format!("v{}", id.0),
+    }
+}
+
+/// Names to print variables by: the ones they were written with, when
+/// every variable has one and no two variables share one.
+struct VariableNames(HashMap<VariableId, String>);
+
+impl VariableNames {
+    fn of<'a>(terms: impl IntoIterator<Item = &'a Term>, symbols: &SymbolTable) -> Self {
+        let mut names = HashMap::new();
+        let complete = terms.into_iter().all(|term| collect_names(term, symbols, &mut names));
+        let mut distinct: Vec<&String> = names.values().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if !complete || distinct.len() != names.len() {
+            names.clear();
+        }
+        Self(names)
+    }
+
+    fn name(&self, id: VariableId) -> String {
+        self.0.get(&id).cloned().unwrap_or_else(|| variable_name(id))
+    }
+}
+
+/// Record the name of each variable of `term`; false once a variable has
+/// no name or two names.
+fn collect_names(term: &Term, symbols: &SymbolTable, names: &mut HashMap<VariableId, String>) -> bool {
+    match term {
+        Term::Variable { id, symbol } => {
+            let Some(name) = symbol.and_then(|symbol| symbols.get(symbol)).map(|symbol| symbol.name) else {
+                return false;
+            };
+            names.entry(*id).or_insert_with(|| name.clone()) == &name
+        }
+        Term::Application { args, .. } => args.iter().all(|arg| collect_names(arg, symbols, names)),
+    }
+}
+
+/// Print a term; binary operator symbols such as `*` or `+` print infix.
+pub fn format_term(term: &Term, symbols: &SymbolTable) -> String {
+    write_term(term, symbols, &VariableNames::of([term], symbols), false)
+}
+
+/// Print a literal, with negated equalities written as `l!=r`.
+pub fn format_literal(literal: &Literal, symbols: &SymbolTable) -> String {
+    write_literal(literal, symbols, &VariableNames::of([&literal.atom], symbols))
+}
+
+/// Print a clause as `l1|l2|...`; the empty clause prints as `$F`.
+pub fn format_clause(clause: &Clause, symbols: &SymbolTable) -> String {
+    if clause.literals.is_empty() {
+        return "$F".to_string();
+    }
+    let names = VariableNames::of(clause.literals.iter().map(|literal| &literal.atom), symbols);
+    clause
+        .literals
+        .iter()
+        .map(|literal| write_literal(literal, symbols, &names))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn write_literal(literal: &Literal, symbols: &SymbolTable, names: &VariableNames) -> String {
+    if literal.sign {
+        return write_term(&literal.atom, symbols, names, false);
+    }
+    if let Term::Application { symbol, args } = &literal.atom
+        && args.len() == 2
+        && symbol_name(*symbol, symbols) == "="
+    {
+        return format!(
+            "{}!={}",
+            write_term(&args[0], symbols, names, true),
+            write_term(&args[1], symbols, names, true)
+        );
+    }
+    format!("-{}", write_term(&literal.atom, symbols, names, true))
+}
+
+fn symbol_name(symbol: crate::data::SymbolId, symbols: &SymbolTable) -> String {
+    symbols
+        .get(symbol)
+        .map_or_else(|| format!("s{}", symbol.as_raw()), |sym| sym.name)
+}
+
+/// Operator symbols are names made only of punctuation, like `=` or `*`.
+fn is_operator(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('$')
+        && name.chars().all(|c| !c.is_alphanumeric() && c != '_')
+}
+
+/// `nested` infix terms are parenthesized so the output reads back the same.
+fn write_term(term: &Term, symbols: &SymbolTable, names: &VariableNames, nested: bool) -> String {
+    match term {
+        Term::Variable { id, .. } => names.name(*id),
+        Term::Application { symbol, args } => {
+            let name = symbol_name(*symbol, symbols);
+            if args.is_empty() {
+                return name;
+            }
+            if args.len() == 2 && is_operator(&name) {
+                let infix = format!(
+                    "{}{}{}",
+                    write_term(&args[0], symbols, names, true),
+                    name,
+                    write_term(&args[1], symbols, names, true)
+                );
+                return if nested { format!("({})", infix) } else { infix };
+            }
+            let args = args
+                .iter()
+                .map(|arg| write_term(arg, symbols, names, false))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{}({})", name, args)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_clause, format_literal, format_term, variable_name};
+    use crate::data::{
+        Clause, Literal, SymbolKind, SymbolTable, Term, VariableId,
+    };
+
+    #[test]
+    fn variables_use_otter_names() {
+        assert_eq!(variable_name(VariableId::new(0)), "x");
+        assert_eq!(variable_name(VariableId::new(5)), "w");
+        assert_eq!(variable_name(VariableId::new(6)), "v6");
+    }
+
+    #[test]
+    fn prints_terms_literals_and_clauses() {
+        let symbols = SymbolTable::new();
+        let eq = symbols.intern("=", 2, SymbolKind::Predicate);
+        let mult = symbols.intern("*", 2, SymbolKind::Function);
+        let f = symbols.intern("f", 2, SymbolKind::Function);
+        let p = symbols.intern("P", 1, SymbolKind::Predicate);
+        let e = symbols.intern("e", 0, SymbolKind::Constant);
+        let x = Term::variable(VariableId::new(0));
+        let y = Term::variable(VariableId::new(1));
+        let e = Term::application(e, vec![]);
+
+        let product = Term::application(mult, vec![x.clone(), y.clone()]);
+        assert_eq!(format_term(&product, &symbols), "x*y");
+        let nested = Term::application(f, vec![product.clone(), e.clone()]);
+        assert_eq!(format_term(&nested, &symbols), "f(x*y,e)");
+        let equation = Term::application(eq, vec![product, e.clone()]);
+        assert_eq!(
+            format_literal(&Literal::new(true, equation.clone()), &symbols),
+            "(x*y)=e"
+        );
+        assert_eq!(
+            format_literal(&Literal::new(false, equation), &symbols),
+            "(x*y)!=e"
+        );
+
+        let clause = Clause::new(vec![
+            Literal::new(false, Term::application(p, vec![x])),
+            Literal::new(true, Term::application(p, vec![e])),
+        ]);
+        assert_eq!(format_clause(&clause, &symbols), "-P(x)|P(e)");
+        assert_eq!(format_clause(&Clause::new(vec![]), &symbols), "$F");
+    }
+}
@@ -72,6 +72,18 @@ impl Term {
             Term::Application { args, .. } => args.iter(),
         }
     }
+
+    /// Bytes owned by the term's argument vectors, recursively; the term
+    /// node itself is counted by whatever holds it.
+    pub fn heap_bytes(&self) -> usize {
+        match self {
+            Term::Variable { .. } => 0,
+            Term::Application { args, .. } => {
+                args.capacity() * std::mem::size_of::<Term>()
+                    + args.iter().map(Term::heap_bytes).sum::<usize>()
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -94,4 +106,17 @@ mod tests {
         assert_eq!(t_fun.arity(), 2);
         assert_eq!(t_var.arity(), 0);
     }
+
+    #[test]
+    fn heap_bytes_count_nested_arguments() {
+        let table = SymbolTable::new();
+        let f = table.intern("f", 1, SymbolKind::Function);
+        let x = Term::variable(VariableId::new(0));
+        let fx = Term::application(f, vec![x.clone()]);
+        let ffx = Term::application(f, vec![fx.clone()]);
+        let node = std::mem::size_of::<Term>();
+        assert_eq!(x.heap_bytes(), 0);
+        assert_eq!(fx.heap_bytes(), node);
+        assert_eq!(ffx.heap_bytes(), 2 * node);
+    }
 }
@@ -0,0 +1,242 @@
+//! Hash-consed terms for the forward demodulation cache.
+//!
+//! A [`TermBank`] stores every distinct term once and names it by a
+//! [`TermId`]; the arguments of a stored term are the ids of its (equally
+//! shared) subterms.  Two terms in the same bank are equal exactly when
+//! their ids are, and each stored term carries its hash, size, depth and
+//! groundness, computed once when it is first interned.
+//!
+//! Clauses still own their terms.  Forward demodulation interns the atoms
+//! it rewrites, matches and orders them here, remembers their normal forms
+//! in a [`DemodCache`](crate::inference::DemodCache), and converts the
+//! results back with [`TermBank::to_term_named`].
+//!
+//! Variables are stored by id alone; their names stay with the clause
+//! they come from and are put back when a term is converted.
+
+use super::{SymbolId, Term, VariableId};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A term stored in a [`TermBank`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TermId(pub u32);
+
+/// The root of a shared term, with its arguments as ids in the same bank.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SharedTerm {
+    Variable { id: VariableId },
+    Application { symbol: SymbolId, args: Box<[TermId]> },
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    term: SharedTerm,
+    hash: u64,
+    /// Symbol and variable occurrences
+    size: u32,
+    depth: u32,
+    ground: bool,
+}
+
+/// Store of hash-consed terms.
+#[derive(Clone, Debug, Default)]
+pub struct TermBank {
+    nodes: Vec<Node>,
+    /// Ids of the stored terms by hash
+    by_hash: HashMap<u64, Vec<TermId>>,
+    /// Bytes held by the nodes and the hash table, as inserted
+    bytes: usize,
+}
+
+impl TermBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct terms stored.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Approximate bytes held by the stored terms.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The id of `term`, storing it and its subterms if they are new.
+    pub fn intern(&mut self, term: &Term) -> TermId {
+        match term {
+            Term::Variable { id, .. } => self.variable(*id),
+            Term::Application { symbol, args } => {
+                let args = args.iter().map(|arg| self.intern(arg)).collect();
+                self.application(*symbol, args)
+            }
+        }
+    }
+
+    pub fn variable(&mut self, id: VariableId) -> TermId {
+        let mut hasher = DefaultHasher::new();
+        (0u8, id).hash(&mut hasher);
+        self.insert(SharedTerm::Variable { id }, hasher.finish(), 1, 0, false)
+    }
+
+    /// The id of `symbol` applied to the stored terms `args`.
+    pub fn application(&mut self, symbol: SymbolId, args: Vec<TermId>) -> TermId {
+        let mut hasher = DefaultHasher::new();
+        (1u8, symbol).hash(&mut hasher);
+        let mut size = 1u32;
+        let mut depth = 0;
+        let mut ground = true;
+        for arg in &args {
+            let node = &self.nodes[arg.0 as usize];
+            node.hash.hash(&mut hasher);
+            size = size.saturating_add(node.size);
+            depth = depth.max(node.depth + 1);
+            ground &= node.ground;
+        }
+        let term = SharedTerm::Application { symbol, args: args.into_boxed_slice() };
+        self.insert(term, hasher.finish(), size, depth, ground)
+    }
+
+    fn insert(&mut self, term: SharedTerm, hash: u64, size: u32, depth: u32, ground: bool) -> TermId {
+        let ids = self.by_hash.entry(hash).or_default();
+        if let Some(id) = ids.iter().find(|id| self.nodes[id.0 as usize].term == term) {
+            return *id;
+        }
+        if ids.is_empty() {
+            self.bytes += std::mem::size_of::<u64>() + std::mem::size_of::<Vec<TermId>>();
+        }
+        let id = TermId(self.nodes.len() as u32);
+        ids.push(id);
+        self.bytes += std::mem::size_of::<Node>()
+            + match &term {
+                SharedTerm::Variable { .. } => 0,
+                SharedTerm::Application { args, .. } => std::mem::size_of_val::<[TermId]>(args),
+            };
+        self.nodes.push(Node { term, hash, size, depth, ground });
+        id
+    }
+
+    pub fn get(&self, id: TermId) -> &SharedTerm {
+        &self.nodes[id.0 as usize].term
+    }
+
+    /// Arguments of `id`; none for variables.
+    pub fn args(&self, id: TermId) -> &[TermId] {
+        match self.get(id) {
+            SharedTerm::Variable { .. } => &[],
+            SharedTerm::Application { args, .. } => args,
+        }
+    }
+
+    /// Root symbol of an application.
+    pub fn symbol(&self, id: TermId) -> Option<SymbolId> {
+        match self.get(id) {
+            SharedTerm::Variable { .. } => None,
+            SharedTerm::Application { symbol, .. } => Some(*symbol),
+        }
+    }
+
+    /// The variable `id` is, if it is one.
+    pub fn variable_id(&self, id: TermId) -> Option<VariableId> {
+        match self.get(id) {
+            SharedTerm::Variable { id, .. } => Some(*id),
+            SharedTerm::Application { .. } => None,
+        }
+    }
+
+    pub fn hash(&self, id: TermId) -> u64 {
+        self.nodes[id.0 as usize].hash
+    }
+
+    /// Number of symbol and variable occurrences.
+    pub fn size(&self, id: TermId) -> usize {
+        self.nodes[id.0 as usize].size as usize
+    }
+
+    /// Length of the longest path from the root to a leaf; 0 for variables
+    /// and constants.
+    pub fn depth(&self, id: TermId) -> usize {
+        self.nodes[id.0 as usize].depth as usize
+    }
+
+    pub fn is_ground(&self, id: TermId) -> bool {
+        self.nodes[id.0 as usize].ground
+    }
+
+    /// Whether the variable `var` occurs in `id`.
+    pub fn occurs(&self, var: VariableId, id: TermId) -> bool {
+        match self.get(id) {
+            _ if self.is_ground(id) => false,
+            SharedTerm::Variable { id, .. } => *id == var,
+            SharedTerm::Application { args, .. } => args.iter().any(|arg| self.occurs(var, *arg)),
+        }
+    }
+
+    /// The owned term `id` stands for, with unnamed variables.
+    pub fn to_term(&self, id: TermId) -> Term {
+        self.to_term_named(id, &HashMap::new())
+    }
+
+    /// The owned term `id` stands for, naming its variables from `names`.
+    pub fn to_term_named(&self, id: TermId, names: &HashMap<VariableId, SymbolId>) -> Term {
+        match self.get(id) {
+            SharedTerm::Variable { id } => Term::Variable { id: *id, symbol: names.get(id).copied() },
+            SharedTerm::Application { symbol, args } => {
+                Term::application(*symbol, args.iter().map(|arg| self.to_term_named(*arg, names)).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SharedTerm, TermBank};
+    use crate::data::{SymbolKind, SymbolTable, Term, VariableId};
+    use std::collections::HashMap;
+
+    #[test]
+    fn equal_terms_share_one_id() {
+        let table = SymbolTable::new();
+        let a = Term::application(table.intern("a", 0, SymbolKind::Constant), vec![]);
+        let f = |x: Term, y: Term| Term::application(table.intern("f", 2, SymbolKind::Function), vec![x, y]);
+        let x = Term::variable(VariableId::new(0));
+
+        let mut bank = TermBank::new();
+        let first = bank.intern(&f(f(a.clone(), x.clone()), f(a.clone(), x.clone())));
+        // a, x, f(a, x) and the whole term
+        assert_eq!(bank.len(), 4);
+        let second = bank.intern(&f(f(a.clone(), x.clone()), f(a.clone(), x.clone())));
+        assert_eq!(first, second);
+        assert_eq!(bank.len(), 4);
+
+        let args = bank.args(first).to_vec();
+        assert_eq!(args[0], args[1]);
+        assert!(matches!(bank.get(args[0]), SharedTerm::Application { .. }));
+        assert_eq!(bank.to_term(first), f(f(a.clone(), x.clone()), f(a.clone(), x.clone())));
+
+        assert_eq!(bank.size(first), 7);
+        assert_eq!(bank.depth(first), 2);
+        assert!(!bank.is_ground(first));
+        assert!(bank.occurs(VariableId::new(0), first));
+        let ground = bank.intern(&f(a.clone(), a.clone()));
+        assert!(bank.is_ground(ground));
+        assert_ne!(bank.hash(ground), bank.hash(args[0]));
+        assert_ne!(bank.intern(&f(x.clone(), a.clone())), args[0]);
+
+        // A variable is one term whatever its name, and gets the name back
+        let name = table.intern("u", 0, SymbolKind::Variable);
+        let named = Term::named_variable(VariableId::new(0), name);
+        let id = bank.intern(&f(named.clone(), a.clone()));
+        assert_eq!(id, bank.intern(&f(x.clone(), a.clone())));
+        assert_eq!(bank.to_term(id), f(x, a.clone()));
+        let names = HashMap::from([(VariableId::new(0), name)]);
+        assert_eq!(bank.to_term_named(id, &names), f(named, a));
+    }
+}
@@ -3,10 +3,65 @@
 //! This module implements the weighting scheme used by Foras for prioritizing
 //! clauses during the given-clause loop. Each symbol can be assigned a weight,
 //! and the weight of a clause is the sum of the weights of all its symbols.
+//!
+//! Otter's `weight_list` templates refine this: a term that matches a
+//! [`WeightTemplate`] gets the template's weight instead, plus `N` times the
+//! weight of each subterm matched by a `$(N)` position.
 
-use super::{Clause, Literal, SymbolId, Term};
+use super::{Clause, Literal, SymbolId, SymbolTable, Term, VariableId};
 use std::collections::HashMap;
 
+/// Pattern side of a weight template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeightPattern {
+    /// Matches a variable, consistently with the pattern's other variables
+    Variable(VariableId),
+    /// `$(N)`: matches any term and adds `N` times its weight
+    Multiplier(i32),
+    Application { symbol: SymbolId, args: Vec<WeightPattern> },
+}
+
+impl WeightPattern {
+    /// Convert a parsed template term, reading `$(N)`, an application of
+    /// `$` to an integer, as a multiplier.
+    pub fn from_term(term: &Term, symbols: &SymbolTable) -> Result<Self, String> {
+        match term {
+            Term::Variable { id, .. } => Ok(WeightPattern::Variable(*id)),
+            Term::Application { symbol, args } => {
+                let name = |id: SymbolId| symbols.get(id).map(|sym| sym.name).unwrap_or_default();
+                if args.len() == 1 && name(*symbol) == "$" {
+                    let factor = match &args[0] {
+                        Term::Application { symbol, args } if args.is_empty() => name(*symbol),
+                        _ => String::new(),
+                    };
+                    return factor
+                        .parse()
+                        .map(WeightPattern::Multiplier)
+                        .map_err(|_| format!("invalid weight multiplier $({})", factor));
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| Self::from_term(arg, symbols))
+                    .collect::<Result<_, _>>()?;
+                Ok(WeightPattern::Application { symbol: *symbol, args })
+            }
+        }
+    }
+}
+
+/// One `weight(pattern, weight)` entry of a `weight_list`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightTemplate {
+    pub pattern: WeightPattern,
+    pub weight: i32,
+}
+
+impl WeightTemplate {
+    pub fn new(pattern: WeightPattern, weight: i32) -> Self {
+        Self { pattern, weight }
+    }
+}
+
 /// Table mapping symbols to their weights.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct WeightTable {
@@ -14,6 +69,8 @@ pub struct WeightTable {
     weights: HashMap<SymbolId, i32>,
     /// Default weight for symbols not in the table
     default_weight: i32,
+    /// Templates tried in order before falling back to symbol weights
+    templates: Vec<WeightTemplate>,
 }
 
 impl WeightTable {
@@ -22,6 +79,7 @@ impl WeightTable {
         Self {
             weights: HashMap::new(),
             default_weight: 1,
+            templates: Vec::new(),
         }
     }
 
@@ -30,6 +88,7 @@ impl WeightTable {
         Self {
             weights: HashMap::new(),
             default_weight,
+            templates: Vec::new(),
         }
     }
 
@@ -48,8 +107,45 @@ impl WeightTable {
         self.default_weight = weight;
     }
 
+    /// Append a template; earlier templates take priority.
+    pub fn add_template(&mut self, template: WeightTemplate) {
+        self.templates.push(template);
+    }
+
+    /// Weight of `symbol` on its own, for orderings that weigh a term by
+    /// adding up its symbols: the weight of the first template
+    /// `symbol($(1), ..., $(1))`, or just `symbol` for a constant, otherwise
+    /// the symbol weight.
+    pub fn symbol_weight(&self, symbol: SymbolId) -> i32 {
+        self.templates
+            .iter()
+            .find_map(|template| match &template.pattern {
+                WeightPattern::Application { symbol: s, args }
+                    if *s == symbol && args.iter().all(|arg| *arg == WeightPattern::Multiplier(1)) =>
+                {
+                    Some(template.weight)
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| self.get_weight(symbol))
+    }
+
+    /// The templates, in priority order.
+    pub fn templates(&self) -> &[WeightTemplate] {
+        &self.templates
+    }
+
     /// Calculate the weight of a term.
+    ///
+    /// The first template matching the term decides its weight; otherwise
+    /// it is the symbol weight plus the weights of the arguments.
     pub fn weight_term(&self, term: &Term) -> i32 {
+        for template in &self.templates {
+            let mut weight = template.weight;
+            if self.match_template(&template.pattern, term, &mut Vec::new(), &mut weight) {
+                return weight;
+            }
+        }
         match term {
             Term::Variable { .. } => {
                 // Variables typically have weight 1
@@ -66,6 +162,44 @@ impl WeightTable {
         }
     }
 
+    /// Match `term` against `pattern` modulo variable renaming, adding the
+    /// weight of `$(N)` subterms to `weight`.
+    fn match_template(
+        &self,
+        pattern: &WeightPattern,
+        term: &Term,
+        renaming: &mut Vec<(VariableId, VariableId)>,
+        weight: &mut i32,
+    ) -> bool {
+        match (pattern, term) {
+            (WeightPattern::Multiplier(factor), _) => {
+                *weight += factor * self.weight_term(term);
+                true
+            }
+            (WeightPattern::Variable(from), Term::Variable { id, .. }) => {
+                match renaming.iter().find(|(p, t)| p == from || t == id) {
+                    Some(pair) => *pair == (*from, *id),
+                    None => {
+                        renaming.push((*from, *id));
+                        true
+                    }
+                }
+            }
+            (
+                WeightPattern::Application { symbol: p_symbol, args: p_args },
+                Term::Application { symbol, args },
+            ) => {
+                p_symbol == symbol
+                    && p_args.len() == args.len()
+                    && p_args
+                        .iter()
+                        .zip(args)
+                        .all(|(p_arg, arg)| self.match_template(p_arg, arg, renaming, weight))
+            }
+            _ => false,
+        }
+    }
+
     /// Calculate the weight of a literal.
     pub fn weight_literal(&self, literal: &Literal) -> i32 {
         self.weight_term(&literal.atom)
@@ -175,4 +309,64 @@ mod tests {
         // P(X) = 2 + 1 = 3, Q(Y) = 3 + 1 = 4, total = 7
         assert_eq!(table.weight_clause(&clause), 7);
     }
+
+    #[test]
+    fn templates_override_symbol_counts() {
+        let mut table = WeightTable::new();
+        let f = SymbolId::from_raw(1);
+        let g = SymbolId::from_raw(2);
+        let a = Term::Application { symbol: SymbolId::from_raw(3), args: vec![] };
+        let x = || Term::variable(VariableId::new(0));
+        let y = || Term::variable(VariableId::new(1));
+
+        // weight(f($(2), u), 10).  weight(g(u, u), 0).  weight(w, 3).
+        table.add_template(WeightTemplate::new(
+            WeightPattern::Application {
+                symbol: f,
+                args: vec![WeightPattern::Multiplier(2), WeightPattern::Variable(VariableId::new(7))],
+            },
+            10,
+        ));
+        let u = || WeightPattern::Variable(VariableId::new(7));
+        table.add_template(WeightTemplate::new(
+            WeightPattern::Application { symbol: g, args: vec![u(), u()] },
+            0,
+        ));
+        table.add_template(WeightTemplate::new(WeightPattern::Variable(VariableId::new(9)), 3));
+
+        // f(g(a, a), x) = 10 + 2 * (1 + 1 + 1)
+        let g_a_a = Term::Application { symbol: g, args: vec![a.clone(), a.clone()] };
+        assert_eq!(table.weight_term(&Term::Application { symbol: f, args: vec![g_a_a, x()] }), 16);
+        // f(a, a) does not match: the second argument must be a variable
+        let f_a_a = Term::Application { symbol: f, args: vec![a.clone(), a.clone()] };
+        assert_eq!(table.weight_term(&f_a_a), 3);
+        // g(y, y) is a renaming of g(u, u); g(x, y) is not, so it weighs 1 + 3 + 3
+        assert_eq!(table.weight_term(&Term::Application { symbol: g, args: vec![y(), y()] }), 0);
+        assert_eq!(table.weight_term(&Term::Application { symbol: g, args: vec![x(), y()] }), 7);
+    }
+
+    #[test]
+    fn symbol_weights_come_from_additive_templates() {
+        let mut table = WeightTable::new();
+        let (f, g, a) = (SymbolId::from_raw(1), SymbolId::from_raw(2), SymbolId::from_raw(3));
+        table.set_weight(g, 4);
+        // weight(f($(1), $(1)), 0).  weight(g($(2)), 9).  weight(a, 5).
+        table.add_template(WeightTemplate::new(
+            WeightPattern::Application {
+                symbol: f,
+                args: vec![WeightPattern::Multiplier(1), WeightPattern::Multiplier(1)],
+            },
+            0,
+        ));
+        table.add_template(WeightTemplate::new(
+            WeightPattern::Application { symbol: g, args: vec![WeightPattern::Multiplier(2)] },
+            9,
+        ));
+        table.add_template(WeightTemplate::new(WeightPattern::Application { symbol: a, args: vec![] }, 5));
+
+        assert_eq!(table.symbol_weight(f), 0);
+        assert_eq!(table.symbol_weight(g), 4, "g($(2)) does not weigh g on its own");
+        assert_eq!(table.symbol_weight(a), 5);
+        assert_eq!(table.symbol_weight(SymbolId::from_raw(4)), 1);
+    }
 }
@@ -1,8 +1,9 @@
 //! Builder to construct a prover from parsed Foras input.
 
+use crate::config::{check_flag, parse_parameter, OptionError};
 use crate::data::{Clause, ClauseArena, SymbolId, SymbolTable, Term};
 use crate::data::symbol::SymbolKind;
-use crate::inference::{Prover, ProverConfig};
+use crate::inference::{Evaluator, Prover, ProverConfig};
 use crate::parser::{ForasCommand, ForasFile};
 
 /// Problem type characteristics detected from input clauses.
@@ -55,6 +56,10 @@ pub struct ProverBuilder {
     lex_symbols: Vec<String>,
     /// Special unary symbols
     special_unary_symbols: Vec<String>,
+    /// Options the registry rejected
+    diagnostics: Vec<OptionError>,
+    /// `make_evaluable(operator, evaluator)` pairs
+    evaluables: Vec<(String, String)>,
 }
 
 impl ProverBuilder {
@@ -65,6 +70,8 @@ impl ProverBuilder {
             symbols: SymbolTable::new(),
             lex_symbols: Vec::new(),
             special_unary_symbols: Vec::new(),
+            diagnostics: Vec::new(),
+            evaluables: Vec::new(),
         }
     }
 
@@ -79,10 +86,10 @@ impl ProverBuilder {
         for command in commands {
             match command {
                 ForasCommand::Set(flag) => {
-                    self.apply_set_flag(flag);
+                    self.apply_flag(flag, true);
                 }
                 ForasCommand::Clear(flag) => {
-                    self.apply_clear_flag(flag);
+                    self.apply_flag(flag, false);
                 }
                 ForasCommand::Assign { name, value } => {
                     self.apply_assign(name, value);
@@ -91,149 +98,121 @@ impl ProverBuilder {
                     // Store lex symbols for later processing after symbols are interned
                     self.lex_symbols = symbols.clone();
                 }
+                ForasCommand::ProofObject(_) => {
+                    // A proof object in the input comes from a run with
+                    // build_proof_object set; answer in kind
+                    self.apply_flag("build_proof_object", true);
+                }
+                ForasCommand::MakeEvaluable { operator, evaluator } => {
+                    self.evaluables.push((operator.clone(), evaluator.clone()));
+                }
                 _ => {}
             }
         }
     }
 
-    fn apply_set_flag(&mut self, flag: &str) {
-        match flag {
-            "auto" => {
-                // Auto mode sets various defaults
-                self.config.pick_given_ratio = 4;
-                self.config.max_seconds = 10800; // 3 hours
-                self.config.auto_mode = true;
-                // In original Foras, auto mode enables these inference rules by default
-                self.config.use_hyper_res = true;
-                self.config.use_ur_res = true;
-                self.config.use_binary_res = true;
-                // RE-ENABLED: Fixed infinite loop/recursion issue (made backward() iterative)
-                self.config.use_linked_ur_res = true;
-                // Enable subsumption (always on in C Foras)
-                self.config.use_subsumption = true;
-            }
-            "hyper_res" => {
-                self.config.use_hyper_res = true;
-            }
-            "binary_res" => {
-                self.config.use_binary_res = true;
-            }
-            "para_into" => {
-                self.config.use_para_into = true;
-            }
-            "para_from" => {
-                self.config.use_para_from = true;
-            }
-            "demod_inf" => {
-                self.config.use_demod = true;
-            }
-            "back_demod" => {
-                self.config.use_back_demod = true;
-            }
-            "factor" => {
-                self.config.use_factor = true;
-            }
-            "ur_res" => {
-                self.config.use_ur_res = true;
-            }
-            "linked_ur_res" => {
-                self.config.use_linked_ur_res = true;
-            }
-            "unit_deletion" => {
-                self.config.use_unit_deletion = true;
-            }
-            "keep_hint_subsumers" => {
-                self.config.keep_hint_subsumers = true;
-            }
-            "keep_hint_equivalents" => {
-                self.config.keep_hint_equivalents = true;
-            }
-            "ancestor_subsume" => {
-                self.config.use_ancestor_subsume = true;
-            }
-            _ => {}
+    /// Record a rejected option: warnings go to stderr, errors fail
+    /// [`build`](Self::build).
+    fn report(&mut self, error: OptionError) {
+        if error.is_warning() {
+            eprintln!("WARNING: {}", error);
         }
+        self.diagnostics.push(error);
     }
 
-    fn apply_clear_flag(&mut self, flag: &str) {
+    /// `set(flag)` or `clear(flag)`.
+    fn apply_flag(&mut self, flag: &str, enabled: bool) {
+        if let Err(error) = check_flag(flag) {
+            self.report(error);
+            return;
+        }
         match flag {
-            "back_demod" => {
-                self.config.use_back_demod = false;
-            }
-            "demod_inf" => {
-                self.config.use_demod = false;
+            "auto" => {
+                self.config.auto_mode = enabled;
+                if enabled {
+                    // In original Foras, auto mode enables these inference rules by default
+                    for rule in ["hyper_res", "ur_res", "binary_res", "linked_ur_res"] {
+                        self.apply_flag(rule, true);
+                    }
+                    self.apply_assign("pick_given_ratio", "4");
+                    self.apply_assign("max_seconds", "10800"); // 3 hours
+                    // Enable subsumption (always on in C Foras)
+                    self.config.use_subsumption = true;
+                }
             }
+            "ancestor_subsume" => self.config.use_ancestor_subsume = enabled,
+            "back_demod" => self.config.use_back_demod = enabled,
+            "binary_res" => self.config.use_binary_res = enabled,
+            "build_proof_object" | "build_proof_object_1" | "build_proof_object_2" => {
+                self.config.build_proof_object = enabled;
+            }
+            "control_memory" => self.config.control_memory = enabled,
+            "demod_inf" => self.config.use_demod = enabled,
+            "factor" => self.config.use_factor = enabled,
+            "hyper_res" => self.config.use_hyper_res = enabled,
+            "kbo" => self.config.use_kbo = enabled,
+            "keep_hint_equivalents" => self.config.keep_hint_equivalents = enabled,
+            "keep_hint_subsumers" => self.config.keep_hint_subsumers = enabled,
+            "linked_ur_res" => self.config.use_linked_ur_res = enabled,
+            "para_from" => self.config.use_para_from = enabled,
+            "para_from_left" => self.config.para_from_left = enabled,
+            "para_from_right" => self.config.para_from_right = enabled,
+            "para_into" => self.config.use_para_into = enabled,
+            "para_into_left" => self.config.para_into_left = enabled,
+            "para_into_right" => self.config.para_into_right = enabled,
+            "superposition" => self.config.use_superposition = enabled,
+            "unit_deletion" => self.config.use_unit_deletion = enabled,
+            "ur_res" => self.config.use_ur_res = enabled,
+            // Registered for Otter compatibility only
             _ => {}
         }
     }
 
+    /// `assign(name, value)`.
     fn apply_assign(&mut self, name: &str, value: &str) {
+        let value = match parse_parameter(name, value) {
+            Ok(value) => value,
+            Err(error) => {
+                self.report(error);
+                return;
+            }
+        };
+        // The registry keeps values within i32 and -1 means "no limit"
+        let limit = |value: i64| usize::try_from(value).unwrap_or(usize::MAX);
         match name {
-            "max_seconds" => {
-                if let Ok(secs) = value.parse::<u64>() {
-                    self.config.max_seconds = secs;
-                }
-            }
-            "max_given" => {
-                if let Ok(n) = value.parse::<usize>() {
-                    self.config.max_given = n;
-                }
-            }
-            "pick_given_ratio" => {
-                if let Ok(n) = value.parse::<usize>() {
-                    self.config.pick_given_ratio = n;
-                }
-            }
-            "max_mem" => {
-                // Memory limit - convert KB to clause limit approximation
-                if let Ok(kb) = value.parse::<usize>() {
-                    // Rough estimate: 100 bytes per clause
-                    self.config.max_clauses = (kb * 1024) / 100;
-                }
-            }
-            "max_weight" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.max_weight = w;
-                }
-            }
-            "fsub_hint_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.fsub_hint_wt = w;
-                }
-            }
-            "bsub_hint_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.bsub_hint_wt = w;
-                }
-            }
-            "equiv_hint_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.equiv_hint_wt = w;
-                }
-            }
-            "fsub_hint_add_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.fsub_hint_add_wt = w;
-                }
-            }
-            "bsub_hint_add_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.bsub_hint_add_wt = w;
-                }
-            }
-            "equiv_hint_add_wt" => {
-                if let Ok(w) = value.parse::<i32>() {
-                    self.config.equiv_hint_add_wt = w;
-                }
-            }
+            "bsub_hint_add_wt" => self.config.bsub_hint_add_wt = value as i32,
+            "bsub_hint_wt" => self.config.bsub_hint_wt = value as i32,
+            "demod_limit" => self.config.max_demod_iterations = limit(value),
+            "equiv_hint_add_wt" => self.config.equiv_hint_add_wt = value as i32,
+            "equiv_hint_wt" => self.config.equiv_hint_wt = value as i32,
+            "fsub_hint_add_wt" => self.config.fsub_hint_add_wt = value as i32,
+            "fsub_hint_wt" => self.config.fsub_hint_wt = value as i32,
+            "max_given" => self.config.max_given = limit(value),
+            "max_kept" => self.config.max_clauses = limit(value),
+            // Kilobytes; 0 is the config's "no limit"
+            "max_mem" => self.config.max_memory_bytes = value.max(0) as usize * 1024,
+            "max_proofs" => self.config.max_proofs = limit(value),
+            // 0 is the config's "no limit"
+            "max_seconds" => self.config.max_seconds = value.max(0) as u64,
+            "max_sos" => self.config.max_sos = value.max(0) as usize,
+            "max_weight" => self.config.max_weight = value as i32,
+            "pick_given_ratio" => self.config.pick_given_ratio = limit(value),
             _ => {}
         }
     }
 
+    /// Options rejected so far, warnings and errors alike.
+    pub fn diagnostics(&self) -> &[OptionError] {
+        &self.diagnostics
+    }
+
     /// Build a prover from parsed Foras file.
     pub fn build(mut self, file: &ForasFile) -> Result<Prover, String> {
         // Apply commands first
         self.apply_commands(&file.commands);
+        if let Some(error) = self.diagnostics.iter().find(|error| !error.is_warning()) {
+            return Err(error.to_string());
+        }
 
         // Removed unused early prover creation
         let mut arena = ClauseArena::new();
@@ -242,9 +221,28 @@ impl ProverBuilder {
         let mut usable_clauses = Vec::new();
         let mut sos_clauses = Vec::new();
         let mut hint_clauses = Vec::new();
+        let mut passive_clauses = Vec::new();
+        let mut demodulator_clauses = Vec::new();
+        let mut pick_templates = Vec::new();
+        let mut purge_templates = Vec::new();
 
         // Process each list section
         for list in &file.lists {
+            if list.kind == crate::parser::ListKind::Weight {
+                let templates = list
+                    .weight_templates(&self.symbols, &file.operators)
+                    .map_err(|e| e.to_string())?;
+                match list.name.as_str() {
+                    "pick_given" => pick_templates.extend(templates),
+                    "purge_gen" => purge_templates.extend(templates),
+                    "pick_and_purge" => {
+                        pick_templates.extend(templates.iter().cloned());
+                        purge_templates.extend(templates);
+                    }
+                    name => eprintln!("WARNING: unknown weight list {}, ignored", name),
+                }
+                continue;
+            }
             match list.name.as_str() {
                 "usable" => {
                     // Handle both clause and formula lists
@@ -300,8 +298,33 @@ impl ProverBuilder {
                         }
                     }
                 }
-                "passive" | "demodulators" => {
-                    // These lists are recognized but not yet implemented
+                "passive" => {
+                    // Passive clauses only serve subsumption and unit conflict
+                    let clause_list = match list.kind {
+                        crate::parser::ListKind::Formula => {
+                            list.to_clause_list_from_formulas(&mut arena, &self.symbols)
+                                .map_err(|e| e.to_string())?
+                        }
+                        _ => {
+                            list.to_clause_list(&mut arena, &self.symbols, &file.operators)
+                                .map_err(|e| e.to_string())?
+                        }
+                    };
+                    for id in clause_list.iter() {
+                        if let Some(clause) = arena.get(*id) {
+                            passive_clauses.push(clause.clone());
+                        }
+                    }
+                }
+                "demodulators" => {
+                    let clause_list = list
+                        .to_clause_list(&mut arena, &self.symbols, &file.operators)
+                        .map_err(|e| e.to_string())?;
+                    for id in clause_list.iter() {
+                        if let Some(clause) = arena.get(*id) {
+                            demodulator_clauses.push(clause.clone());
+                        }
+                    }
                 }
                 _ => {
                     // Unknown list type - could warn or ignore
@@ -429,14 +452,21 @@ impl ProverBuilder {
             }
         }
 
+        // Built-ins are found by name, so every clause must be parsed first
+        let evaluator = Evaluator::new(&self.symbols, &self.evaluables)?;
+
         // Build prover with configuration and symbols
         let mut prover = Prover::with_config(self.config.clone(), self.symbols);
+        prover.set_evaluator(evaluator);
 
         // Set symbol precedence from lex ordering
         for (sym_id, prec) in precedence_updates {
             prover.set_symbol_precedence(sym_id, prec);
         }
 
+        // Weight templates must be in place before clauses are weighed
+        prover.set_weight_templates(pick_templates, purge_templates);
+
         // Add clauses to prover
         for clause in usable_clauses {
             prover.add_usable(clause);
@@ -444,6 +474,9 @@ impl ProverBuilder {
         for clause in sos_clauses {
             prover.add_sos(clause);
         }
+        for clause in passive_clauses {
+            prover.add_passive(clause);
+        }
 
         // Add hints to prover
         for clause in hint_clauses {
@@ -453,6 +486,10 @@ impl ProverBuilder {
         // Set equality symbol for paramodulation
         prover.set_eq_symbol(eq_symbol);
 
+        for clause in demodulator_clauses {
+            prover.add_demodulator(clause)?;
+        }
+
         Ok(prover)
     }
 
@@ -471,8 +508,34 @@ impl Default for ProverBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::InferenceRule;
     use crate::parser::Parser;
 
+    /// Input with `commands` followed by each `(name, clauses)` as a
+    /// `list(name)`.
+    fn problem(commands: &str, lists: &[(&str, &str)]) -> String {
+        let mut input = commands.to_string();
+        for (name, clauses) in lists {
+            input.push_str(&format!("list({}).\n{}\nend_of_list.\n", name, clauses));
+        }
+        input
+    }
+
+    fn build(input: &str) -> Prover {
+        let file = Parser::new().parse_str(input).expect("parse");
+        ProverBuilder::new().build(&file).expect("build prover")
+    }
+
+    /// The id of the stored clause printed as `text`.
+    fn clause_id(prover: &Prover, text: &str) -> u32 {
+        let clause = prover
+            .arena()
+            .iter()
+            .find(|clause| crate::data::format_clause(clause, prover.symbols()) == text)
+            .unwrap_or_else(|| panic!("no clause {}", text));
+        clause.id.expect("stored clauses have ids").0
+    }
+
     #[test]
     fn build_from_simple_input() {
         let input = r#"
@@ -534,4 +597,299 @@ end_of_list.
         let (_, kept, _) = prover.stats();
         assert_eq!(kept, 0);
     }
+
+    #[test]
+    fn set_build_proof_object() {
+        let parser = Parser::new();
+        let file = parser.parse_str("set(build_proof_object).\n").expect("parse");
+
+        let prover = ProverBuilder::new()
+            .build(&file)
+            .expect("build prover");
+
+        assert!(prover.config().build_proof_object);
+    }
+
+    #[test]
+    fn unknown_options_warn_and_bad_values_fail() {
+        let parser = Parser::new();
+        let file = parser
+            .parse_str("set(hyper_ress).\nclear(binary_res).\nassign(max_givn, 3).\n")
+            .expect("parse");
+        let mut builder = ProverBuilder::new();
+        builder.apply_commands(&file.commands);
+        assert_eq!(
+            builder.diagnostics(),
+            &[
+                OptionError::UnknownFlag("hyper_ress".into()),
+                OptionError::UnknownParameter("max_givn".into()),
+            ]
+        );
+        assert!(!builder.config.use_binary_res);
+        assert!(builder.build(&file).is_ok());
+
+        let file = parser.parse_str("assign(max_weight, heavy).\n").expect("parse");
+        let error = ProverBuilder::new().build(&file).err().expect("malformed value");
+        assert!(error.contains("max_weight"), "{}", error);
+    }
+
+    #[test]
+    fn registry_defaults_match_the_default_config() {
+        let mut builder = ProverBuilder::new();
+        for spec in crate::config::OPTIONS {
+            match spec.name {
+                // These switch other options on rather than hold a default
+                "auto" | "build_proof_object_1" | "build_proof_object_2" => {}
+                // Read by the parser; setting it must neither warn nor change the search
+                "prolog_style_variables" => builder.apply_flag(spec.name, true),
+                _ if spec.is_flag() => builder.apply_flag(spec.name, spec.default != 0),
+                _ => builder.apply_assign(spec.name, &spec.default.to_string()),
+            }
+        }
+        assert!(builder.diagnostics().is_empty());
+        assert_eq!(
+            serde_json::to_value(&builder.config).unwrap(),
+            serde_json::to_value(ProverConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn weight_lists_set_pick_and_purge_weights() {
+        let input = r#"
+set(binary_res).
+assign(max_weight, 5).
+weight_list(pick_given).
+weight(P($(2)), 1).
+end_of_list.
+weight_list(purge_gen).
+weight(Q($(1)), 100).
+end_of_list.
+list(usable).
+-P(x) | Q(x).
+end_of_list.
+list(sos).
+P(f(a)).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        let weights: Vec<i32> = prover.arena().iter().map(|clause| clause.pick_weight).collect();
+        // -P(x) | Q(x) is (1 + 2 * 1) + 2; P(f(a)) is 1 + 2 * 2
+        assert_eq!(weights, vec![5, 5]);
+
+        // Q(f(a)) would weigh 3 for pick_given, but 102 for purge_gen
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Saturated { .. }), "{:?}", result);
+        assert_eq!(prover.arena().iter().count(), 2);
+    }
+
+    #[test]
+    fn negative_pick_given_ratio_always_picks_by_weight() {
+        let input = "assign(pick_given_ratio, -1).\nlist(usable).\n-P(x) | Q(x).\n-Q(x) | R(x).\n\
+                     end_of_list.\nlist(sos).\nP(a).\n-R(a).\nend_of_list.\n";
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        assert_eq!(prover.config().pick_given_ratio, usize::MAX);
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+    }
+
+    #[test]
+    fn passive_clauses_only_subsume_and_conflict() {
+        let passive = |clauses| {
+            problem(
+                "set(binary_res).\n",
+                &[("usable", "-P(x) | Q(x).\n-Q(x) | R(x)."), ("sos", "P(a)."), ("passive", clauses)],
+            )
+        };
+
+        let mut prover = build(&passive("-R(a)."));
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+        let proof = prover.proof().expect("proof");
+        let conflict = proof.conclusion().expect("conclusion");
+        assert_eq!(conflict.parents.len(), 2);
+        assert_eq!(conflict.parents[1].0, clause_id(&prover, "-R(a)"), "the passive clause closes the proof");
+
+        // Q(a) is subsumed by the passive Q(x), which itself resolves with nothing
+        let mut prover = build(&passive("Q(x)."));
+        prover.config_mut().use_subsumption = true;
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Saturated { .. }), "{:?}", result);
+        assert_eq!(prover.statistics().clauses_forward_subsumed, 1);
+        assert_eq!(prover.arena().iter().count(), 4);
+    }
+
+    #[test]
+    fn demodulators_list_rewrites_generated_clauses() {
+        let rules = |clauses| {
+            problem(
+                "set(binary_res).\n",
+                &[("demodulators", clauses), ("usable", "-Q(x) | P(f(x)).\n-P(b)."), ("sos", "Q(a).")],
+            )
+        };
+
+        // f(x) = g(x) cannot be oriented without a symbol precedence
+        let mut prover = build(&rules("f(x) = g(x).\ng(a) = b."));
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+        let proof = prover.proof().expect("proof");
+        let demod = format!("demod,{},{}", clause_id(&prover, "f(x)=g(x)"), clause_id(&prover, "g(a)=b"));
+        assert!(proof.steps().iter().any(|step| step.otter_justification().contains(&demod)));
+
+        let file = Parser::new().parse_str(&rules("f(x) = g(y).")).expect("parse");
+        let error = ProverBuilder::new().build(&file).err().expect("rhs variable not in lhs");
+        assert!(error.contains("right side"), "{}", error);
+    }
+
+    #[test]
+    fn evaluable_builtins_run_counters() {
+        let input = r#"
+make_evaluable(_+_, $SUM(_,_)).
+make_evaluable(_<_, $LT(_,_)).
+list(usable).
+-P(x) | -(x < 3) | P(x + 1).
+-P(y) | -$GE(y, 3) | Done($PROD(y, 2)).
+-Done(6).
+end_of_list.
+list(sos).
+P(0).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+
+        let clauses: Vec<String> =
+            prover.arena().iter().map(|clause| crate::data::format_clause(clause, prover.symbols())).collect();
+        assert!(clauses.contains(&"P(3)".to_string()));
+        assert!(clauses.contains(&"Done(6)".to_string()));
+        // From P(3), 3 < 3 evaluates to false, so -(3 < 3) makes the clause true
+        assert!(!clauses.contains(&"P(4)".to_string()), "{:?}", clauses);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn conditional_demodulators_guard_rewrites() {
+        let input = r#"
+make_evaluable(_+_, $SUM(_,_)).
+make_evaluable(_<_, $LT(_,_)).
+list(demodulators).
+x < 3 -> (next(x) = x + 1).
+end_of_list.
+list(usable).
+-P(x) | P(next(x)).
+-P(next(3)).
+end_of_list.
+list(sos).
+P(0).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+
+        let clauses: Vec<String> =
+            prover.arena().iter().map(|clause| crate::data::format_clause(clause, prover.symbols())).collect();
+        assert!(clauses.contains(&"P(3)".to_string()), "{:?}", clauses);
+        // 3 < 3 is false, so next(3) is left alone
+        assert!(!clauses.contains(&"P(4)".to_string()), "{:?}", clauses);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn max_proofs_enumerates_distinct_answers() {
+        let input = |max_proofs: i32| {
+            format!(
+                r#"
+assign(max_proofs, {}).
+list(usable).
+-Color(x) | $Ans(x).
+end_of_list.
+list(sos).
+Color(red).
+Color(green).
+Color(red).
+Color(blue).
+end_of_list.
+"#,
+                max_proofs
+            )
+        };
+        let search = |max_proofs: i32| {
+            let file = Parser::new().parse_str(&input(max_proofs)).expect("parse");
+            let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+            let result = prover.search();
+            let crate::inference::ProofResult::Proof { answers, proofs, .. } = result else {
+                panic!("expected proofs, got {:?}", result);
+            };
+            let mut answers: Vec<String> = answers.iter().map(|answer| answer.format(prover.symbols())).collect();
+            answers.sort();
+            assert_eq!(prover.proofs().len(), proofs.len());
+            answers
+        };
+
+        // The second Color(red) repeats an answer and is not counted
+        assert_eq!(search(-1), vec!["$Ans(blue)", "$Ans(green)", "$Ans(red)"]);
+        assert_eq!(search(2).len(), 2);
+        assert_eq!(search(1).len(), 1);
+    }
+
+    #[test]
+    fn kbo_orients_what_lrpo_leaves_unoriented() {
+        let with_flags = |flags: &str| {
+            problem(
+                &format!("{}set(binary_res).\nset(demod_inf).\n", flags),
+                &[("usable", "f(x, x) = g(x).\n-P(x) | Q(x).\n-Q(g(a))."), ("sos", "P(f(a, a)).")],
+            )
+        };
+
+        // Without a precedence LRPO cannot compare f(x, x) and g(x), so
+        // Q(f(a, a)) is never rewritten
+        let mut prover = build(&with_flags(""));
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Saturated { .. }), "{:?}", result);
+
+        // KBO finds f(x, x) heavier
+        let mut prover = build(&with_flags("set(kbo).\n"));
+        assert!(prover.config().use_kbo);
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn superposition_refutes_non_horn_equality() {
+        let input = "set(superposition).\nlist(sos).\na = b | a = c.\nf(a) != f(b).\nf(a) != f(c).\nend_of_list.\n";
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        assert!(prover.config().use_superposition);
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+
+        let proof = prover.proof().expect("proof");
+        let rules: Vec<_> = proof.steps().iter().filter_map(|step| step.rule).collect();
+        assert!(rules.contains(&InferenceRule::Superposition), "{:?}", rules);
+        assert!(rules.contains(&InferenceRule::EqualityResolution), "{:?}", rules);
+    }
+
+    #[test]
+    fn superposition_infers_between_usable_input_clauses() {
+        // The goal needs the group axioms superposed with each other
+        let input = problem(
+            "set(superposition).\nset(kbo).\nset(demod_inf).\nset(back_demod).\n",
+            &[
+                ("usable", "x = x.\nf(e, x) = x.\nf(g(x), x) = e.\nf(f(x, y), z) = f(x, f(y, z))."),
+                ("sos", "f(a, g(a)) != e."),
+            ],
+        );
+        let mut prover = build(&input);
+        let result = prover.search();
+        assert!(matches!(result, crate::inference::ProofResult::Proof { .. }), "{:?}", result);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
 }
+
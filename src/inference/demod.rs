@@ -2,9 +2,11 @@
 //!
 //! Demodulation simplifies terms by applying oriented equations as rewrite rules.
 
-use crate::data::{Clause, Literal, LRPO, Term};
-use crate::inference::Substitution;
+use crate::data::{Clause, DiscriminationTree, Literal, SharedTerm, SymbolId, Term, TermBank, TermId, TermOrdering, VariableId};
+use crate::inference::{match_shared, EvalContext, SharedSubstitution, Substitution};
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 /// A demodulator (oriented equation used for rewriting).
 #[derive(Clone, Debug)]
@@ -15,15 +17,31 @@ pub struct Demodulator {
     pub rhs: Term,
     /// Source clause ID (if any)
     pub source_id: Option<crate::data::ClauseId>,
+    /// Ordering for lex-dependent rules such as `x * y = y * x`: the rule
+    /// only rewrites an instance into something smaller in this ordering
+    pub lex_dependent: Option<Rc<dyn TermOrdering>>,
+    /// Condition of a rule `condition -> (lhs = rhs)`: the rule only
+    /// rewrites when the instantiated condition demodulates to `$T`
+    pub condition: Option<Term>,
 }
 
 impl Demodulator {
+    /// Approximate bytes the demodulator occupies.
+    pub fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.lhs.heap_bytes()
+            + self.rhs.heap_bytes()
+            + self.condition.as_ref().map_or(0, Term::heap_bytes)
+    }
+
     /// Create a new demodulator from an equation.
     pub fn new(lhs: Term, rhs: Term) -> Self {
         Self {
             lhs,
             rhs,
             source_id: None,
+            lex_dependent: None,
+            condition: None,
         }
     }
 
@@ -36,16 +54,32 @@ impl Demodulator {
     /// Try to apply this demodulator to a term.
     ///
     /// Returns Some(new_term) if the demodulator matches, None otherwise.
+    /// Conditional demodulators never apply here, as deciding their
+    /// condition needs the evaluable built-ins.
     pub fn apply(&self, term: &Term) -> Option<Term> {
+        if self.condition.is_some() {
+            return None;
+        }
+        self.instantiate(term).map(|(result, _)| result)
+    }
+
+    /// Match `term` against the LHS and return the instantiated RHS along
+    /// with the instantiated condition, if the rule has one.
+    fn instantiate(&self, term: &Term) -> Option<(Term, Option<Term>)> {
         // Try to match the term against the LHS using one-way matching
         let mut subst = Substitution::new();
+        if !self.match_pattern(&self.lhs, term, &mut subst) {
+            return None;
+        }
 
-        if self.match_pattern(&self.lhs, term, &mut subst) {
-            // Apply the substitution to the RHS
-            Some(subst.apply(&self.rhs))
-        } else {
-            None
+        // Apply the substitution to the RHS
+        let result = subst.instantiate(&self.rhs);
+        if let Some(ordering) = &self.lex_dependent
+            && !ordering.greater(term, &result)
+        {
+            return None;
         }
+        Some((result, self.condition.as_ref().map(|condition| subst.instantiate(condition))))
     }
 
     /// One-way matching: pattern variables match term subterms.
@@ -87,14 +121,68 @@ impl Demodulator {
 const MAX_DEMOD_DEPTH: usize = 50;
 
 pub fn demodulate_term(term: &Term, demods: &[Demodulator]) -> Term {
-    demodulate_term_with_depth(term, demods, 0, MAX_DEMOD_DEPTH)
+    demodulate_term_with_limit(term, demods, MAX_DEMOD_DEPTH)
 }
 
 pub fn demodulate_term_with_limit(term: &Term, demods: &[Demodulator], max_depth: usize) -> Term {
-    demodulate_term_with_depth(term, demods, 0, max_depth)
+    let index = demodulator_index(demods);
+    let rules = Rules { demods, index: Some(&index) };
+    demodulate_term_with_depth(term, rules, None, 0, max_depth, &mut Rewrites::default())
+}
+
+/// Discrimination tree over the left sides of `demods`, with each
+/// demodulator's position as its entry.
+pub fn demodulator_index(demods: &[Demodulator]) -> DiscriminationTree {
+    let mut index = DiscriminationTree::new();
+    for (position, demod) in demods.iter().enumerate() {
+        index.insert(&demod.lhs, position);
+    }
+    index
+}
+
+/// Demodulators, with an index over the left sides of a prefix of them.
+#[derive(Clone, Copy)]
+struct Rules<'a> {
+    demods: &'a [Demodulator],
+    index: Option<&'a DiscriminationTree>,
 }
 
-fn demodulate_term_with_depth(term: &Term, demods: &[Demodulator], depth: usize, max_depth: usize) -> Term {
+impl Rules<'_> {
+    /// Positions of the demodulators that may rewrite `term`, in
+    /// ascending order: the indexed ones whose left side generalizes it,
+    /// then every one added since the index was last extended.
+    fn candidates(&self, term: &Term) -> Vec<usize> {
+        match self.index {
+            Some(index) => {
+                let mut candidates = index.generalizations(term);
+                candidates.retain(|position| *position < self.demods.len());
+                candidates.extend(index.len().min(self.demods.len())..self.demods.len());
+                candidates
+            }
+            None => (0..self.demods.len()).collect(),
+        }
+    }
+}
+
+/// What rewrote a term.
+#[derive(Default)]
+struct Rewrites {
+    /// Index of every demodulator that fired, in firing order
+    fired: Vec<usize>,
+    /// Some built-in was evaluated
+    evaluated: bool,
+}
+
+/// Rewrite `term`, evaluating built-ins with `eval` and recording what fired
+/// in `rewrites`.
+fn demodulate_term_with_depth(
+    term: &Term,
+    rules: Rules,
+    eval: Option<EvalContext>,
+    depth: usize,
+    max_depth: usize,
+    rewrites: &mut Rewrites,
+) -> Term {
     // Prevent stack overflow and infinite demodulation loops
     if depth > max_depth {
         eprintln!("WARNING: Max demodulation depth {} reached, stopping", max_depth);
@@ -107,7 +195,7 @@ fn demodulate_term_with_depth(term: &Term, demods: &[Demodulator], depth: usize,
         Term::Application { symbol, args } => {
             let new_args: Vec<Term> = args
                 .iter()
-                .map(|arg| demodulate_term_with_depth(arg, demods, depth + 1, max_depth))
+                .map(|arg| demodulate_term_with_depth(arg, rules, eval, depth + 1, max_depth, rewrites))
                 .collect();
             Term::Application {
                 symbol: *symbol,
@@ -116,14 +204,34 @@ fn demodulate_term_with_depth(term: &Term, demods: &[Demodulator], depth: usize,
         }
     };
 
+    // Built-ins whose arguments are values evaluate before any rewriting
+    if let Some(ctx) = eval
+        && let Some(value) = ctx.evaluator.evaluate(&simplified, ctx.symbols)
+    {
+        rewrites.evaluated = true;
+        return demodulate_term_with_depth(&value, rules, eval, depth + 1, max_depth, rewrites);
+    }
+
     // Then try to apply demodulators at the root
-    for demod in demods {
-        if let Some(result) = demod.apply(&simplified) {
+    for index in rules.candidates(&simplified) {
+        if let Some((result, condition)) = rules.demods[index].instantiate(&simplified) {
+            if let Some(condition) = condition {
+                let Some(ctx) = eval else {
+                    continue;
+                };
+                let value =
+                    demodulate_term_with_depth(&condition, rules, eval, depth + 1, max_depth, &mut Rewrites::default());
+                if ctx.evaluator.truth_value(&value) != Some(true) {
+                    continue;
+                }
+                rewrites.evaluated = true;
+            }
             // Only recurse if the term actually changed
             // This prevents infinite loops from poorly-oriented demodulators
             if !terms_equal(&result, &simplified) {
+                rewrites.fired.push(index);
                 // Recursively demodulate the result
-                return demodulate_term_with_depth(&result, demods, depth + 1, max_depth);
+                return demodulate_term_with_depth(&result, rules, eval, depth + 1, max_depth, rewrites);
             } else {
                 // Term didn't change, return it as-is
                 return result;
@@ -155,10 +263,13 @@ pub fn demodulate_clause(clause: &Clause, demods: &[Demodulator]) -> Clause {
         .collect();
 
     let mut new_clause = Clause::new(new_literals);
+    new_clause.id = clause.id;
     new_clause.pick_weight = clause.pick_weight;
     new_clause.heat_level = clause.heat_level;
     new_clause.attributes = clause.attributes.clone();
     new_clause.parents = clause.parents.clone();
+    new_clause.rule = clause.rule;
+    new_clause.justification = clause.justification.clone();
     new_clause.proof_depth = clause.proof_depth;
     new_clause
 }
@@ -172,24 +283,330 @@ pub fn demodulate_clause_with_limit(clause: &Clause, demods: &[Demodulator], max
         .collect();
 
     let mut new_clause = Clause::new(new_literals);
+    new_clause.id = clause.id;
     new_clause.pick_weight = clause.pick_weight;
     new_clause.heat_level = clause.heat_level;
     new_clause.attributes = clause.attributes.clone();
     new_clause.parents = clause.parents.clone();
+    new_clause.rule = clause.rule;
+    new_clause.justification = clause.justification.clone();
     new_clause.proof_depth = clause.proof_depth;
     new_clause
 }
 
-/// Extract demodulators from a clause using LRPO ordering.
+/// Apply demodulators to simplify a clause with custom depth limit, also
+/// returning the indices into `demods` of the demodulators that rewrote
+/// something, in ascending order without repeats.
+pub fn demodulate_clause_traced(
+    clause: &Clause,
+    demods: &[Demodulator],
+    max_depth: usize,
+) -> (Clause, Vec<usize>) {
+    let index = demodulator_index(demods);
+    let (new_clause, fired, _) = demodulate_clause_evaluated(clause, demods, Some(&index), None, max_depth);
+    (new_clause, fired)
+}
+
+/// Like [`demodulate_clause_traced`], also evaluating built-ins with `eval`
+/// between rewrites; the flag reports whether anything was evaluated.
+/// `index` is a [`demodulator_index`] of `demods`, or of a prefix of them
+/// when demodulators have been added since; without one every demodulator
+/// is tried at every subterm.
+pub fn demodulate_clause_evaluated(
+    clause: &Clause,
+    demods: &[Demodulator],
+    index: Option<&DiscriminationTree>,
+    eval: Option<EvalContext>,
+    max_depth: usize,
+) -> (Clause, Vec<usize>, bool) {
+    let rules = Rules { demods, index };
+    let mut rewrites = Rewrites::default();
+    let new_literals: Vec<Literal> = clause
+        .literals
+        .iter()
+        .map(|lit| {
+            let atom = demodulate_term_with_depth(&lit.atom, rules, eval, 0, max_depth, &mut rewrites);
+            Literal::new(lit.sign, atom).with_target(lit.target)
+        })
+        .collect();
+    let mut fired = rewrites.fired;
+    fired.sort_unstable();
+    fired.dedup();
+
+    let mut new_clause = clause.clone();
+    new_clause.literals = new_literals;
+    (new_clause, fired, rewrites.evaluated)
+}
+
+/// Demodulators interned in a [`TermBank`], with the normal forms of the
+/// terms rewritten so far.
+///
+/// The normal forms stay valid while demodulators are only appended; they
+/// are dropped whenever a new one arrives.
+#[derive(Clone, Debug, Default)]
+pub struct DemodCache {
+    /// Left side, right side and condition of each demodulator
+    rules: Vec<(TermId, TermId, Option<TermId>)>,
+    normal_forms: HashMap<TermId, NormalForm>,
+    /// Bytes held by the normal forms, as inserted
+    bytes: usize,
+}
+
+#[derive(Clone, Debug)]
+struct NormalForm {
+    term: TermId,
+    /// Demodulators that fired on the way, in firing order
+    fired: Vec<usize>,
+    evaluated: bool,
+    /// Deepest recursion below the term, so a hit is only used where the
+    /// depth limit would not have cut it short
+    span: usize,
+}
+
+impl NormalForm {
+    /// Approximate bytes the cache entry occupies.
+    fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<(TermId, NormalForm)>() + self.fired.len() * std::mem::size_of::<usize>()
+    }
+}
+
+impl DemodCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached normal forms.
+    pub fn len(&self) -> usize {
+        self.normal_forms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.normal_forms.is_empty()
+    }
+
+    /// Approximate bytes held by the cache, not counting the bank.
+    pub fn memory_bytes(&self) -> usize {
+        self.rules.len() * std::mem::size_of::<(TermId, TermId, Option<TermId>)>() + self.bytes
+    }
+
+    fn remember(&mut self, term: TermId, form: NormalForm) {
+        self.bytes += form.memory_bytes();
+        if let Some(old) = self.normal_forms.insert(term, form) {
+            self.bytes -= old.memory_bytes();
+        }
+    }
+
+    /// Intern the demodulators added since the last call.
+    fn sync(&mut self, bank: &mut TermBank, demods: &[Demodulator]) {
+        if demods.len() < self.rules.len() {
+            self.rules.clear();
+        }
+        if demods.len() == self.rules.len() {
+            return;
+        }
+        for demod in &demods[self.rules.len()..] {
+            let condition = demod.condition.as_ref().map(|condition| bank.intern(condition));
+            self.rules.push((bank.intern(&demod.lhs), bank.intern(&demod.rhs), condition));
+        }
+        self.normal_forms.clear();
+        self.bytes = 0;
+    }
+}
+
+/// Rewriting of shared terms, the counterpart of
+/// [`demodulate_term_with_depth`].
+struct SharedRewriter<'a> {
+    bank: &'a mut TermBank,
+    cache: &'a mut DemodCache,
+    rules: Rules<'a>,
+    eval: Option<EvalContext<'a>>,
+    max_depth: usize,
+}
+
+impl SharedRewriter<'_> {
+    /// Rewrite `term`, returning it with the deepest recursion depth
+    /// reached, or `None` for the depth when the limit cut it short.
+    fn normalize(&mut self, term: TermId, depth: usize, rewrites: &mut Rewrites) -> (TermId, Option<usize>) {
+        if depth > self.max_depth {
+            eprintln!("WARNING: Max demodulation depth {} reached, stopping", self.max_depth);
+            return (term, None);
+        }
+        if let Some(form) = self.cache.normal_forms.get(&term)
+            && depth + form.span <= self.max_depth
+        {
+            rewrites.fired.extend_from_slice(&form.fired);
+            rewrites.evaluated |= form.evaluated;
+            return (form.term, Some(depth + form.span));
+        }
+
+        let mut local = Rewrites::default();
+        let (result, reached) = self.rewrite(term, depth, &mut local);
+        if let Some(reached) = reached {
+            let form = NormalForm {
+                term: result,
+                fired: local.fired.clone(),
+                evaluated: local.evaluated,
+                span: reached - depth,
+            };
+            self.cache.remember(term, form);
+        }
+        rewrites.fired.extend(local.fired);
+        rewrites.evaluated |= local.evaluated;
+        (result, reached)
+    }
+
+    fn rewrite(&mut self, term: TermId, depth: usize, rewrites: &mut Rewrites) -> (TermId, Option<usize>) {
+        let mut reached = Some(depth);
+
+        // First, recursively demodulate subterms
+        let simplified = match self.bank.get(term).clone() {
+            SharedTerm::Variable { .. } => term,
+            SharedTerm::Application { symbol, args } => {
+                let mut new_args = Vec::with_capacity(args.len());
+                for arg in args.iter() {
+                    let (new_arg, arg_reached) = self.normalize(*arg, depth + 1, rewrites);
+                    reached = deepest(reached, arg_reached);
+                    new_args.push(new_arg);
+                }
+                if *new_args == *args { term } else { self.bank.application(symbol, new_args) }
+            }
+        };
+
+        // Built-ins whose arguments are values evaluate before any rewriting
+        if let Some(ctx) = self.eval
+            && let Some(symbol) = self.bank.symbol(simplified)
+            && ctx.evaluator.is_evaluable(symbol)
+            && let Some(value) = ctx.evaluator.evaluate(&self.bank.to_term(simplified), ctx.symbols)
+        {
+            rewrites.evaluated = true;
+            let value = self.bank.intern(&value);
+            let (result, value_reached) = self.normalize(value, depth + 1, rewrites);
+            reached = deepest(reached, value_reached);
+            return (result, reached);
+        }
+
+        // Then try to apply demodulators at the root
+        for index in self.candidates(simplified) {
+            let (lhs, rhs, condition) = self.cache.rules[index];
+            let mut subst = SharedSubstitution::new();
+            if !match_shared(self.bank, lhs, simplified, &mut subst) {
+                continue;
+            }
+            let result = subst.instantiate(self.bank, rhs);
+            if let Some(ordering) = &self.rules.demods[index].lex_dependent
+                && !ordering.greater_shared(self.bank, simplified, result)
+            {
+                continue;
+            }
+            if let Some(condition) = condition {
+                let Some(ctx) = self.eval else {
+                    continue;
+                };
+                let condition = subst.instantiate(self.bank, condition);
+                let (value, condition_reached) = self.normalize(condition, depth + 1, &mut Rewrites::default());
+                reached = deepest(reached, condition_reached);
+                if ctx.evaluator.truth_value(&self.bank.to_term(value)) != Some(true) {
+                    continue;
+                }
+                rewrites.evaluated = true;
+            }
+            // Only recurse if the term actually changed
+            if result == simplified {
+                return (result, reached);
+            }
+            rewrites.fired.push(index);
+            let (result, result_reached) = self.normalize(result, depth + 1, rewrites);
+            reached = deepest(reached, result_reached);
+            return (result, reached);
+        }
+
+        (simplified, reached)
+    }
+
+    /// [`Rules::candidates`] for a shared term.
+    fn candidates(&self, term: TermId) -> Vec<usize> {
+        let demods = self.rules.demods;
+        match self.rules.index {
+            Some(index) => {
+                let mut candidates = index.generalizations_shared(self.bank, term);
+                candidates.retain(|position| *position < demods.len());
+                candidates.extend(index.len().min(demods.len())..demods.len());
+                candidates
+            }
+            None => (0..demods.len()).collect(),
+        }
+    }
+}
+
+/// [`demodulate_clause_evaluated`] on terms shared through `bank`.  Each
+/// subterm is rewritten once: its normal form is kept in `cache` until
+/// another demodulator is added to `demods`.
+pub fn demodulate_clause_shared(
+    bank: &mut TermBank,
+    cache: &mut DemodCache,
+    clause: &Clause,
+    demods: &[Demodulator],
+    index: Option<&DiscriminationTree>,
+    eval: Option<EvalContext>,
+    max_depth: usize,
+) -> (Clause, Vec<usize>, bool) {
+    cache.sync(bank, demods);
+    let mut rewriter = SharedRewriter { bank, cache, rules: Rules { demods, index }, eval, max_depth };
+    let mut rewrites = Rewrites::default();
+    let mut names = HashMap::new();
+    for lit in &clause.literals {
+        variable_names(&lit.atom, &mut names);
+    }
+    let new_literals: Vec<Literal> = clause
+        .literals
+        .iter()
+        .map(|lit| {
+            let atom = rewriter.bank.intern(&lit.atom);
+            let (atom, _) = rewriter.normalize(atom, 0, &mut rewrites);
+            Literal::new(lit.sign, rewriter.bank.to_term_named(atom, &names)).with_target(lit.target)
+        })
+        .collect();
+    let mut fired = rewrites.fired;
+    fired.sort_unstable();
+    fired.dedup();
+
+    let mut new_clause = clause.clone();
+    new_clause.literals = new_literals;
+    (new_clause, fired, rewrites.evaluated)
+}
+
+/// Record the names of the named variables of `term`, which the bank does
+/// not keep.
+fn variable_names(term: &Term, names: &mut HashMap<VariableId, SymbolId>) {
+    match term {
+        Term::Variable { id, symbol } => {
+            if let Some(symbol) = symbol {
+                names.insert(*id, *symbol);
+            }
+        }
+        Term::Application { args, .. } => {
+            for arg in args {
+                variable_names(arg, names);
+            }
+        }
+    }
+}
+
+/// The deeper of two depths reached, or `None` if either was cut short.
+fn deepest(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    a.zip(b).map(|(a, b)| a.max(b))
+}
+
+/// Extract demodulators from a clause using a term ordering.
 ///
 /// A clause can be used as a demodulator if it's a positive unit equality
-/// where one side is greater than the other in LRPO ordering.
+/// where one side is greater than the other in `ordering`.
 ///
-/// If `lrpo` is None, a default LRPO ordering is used.
+/// If `ordering` is None, the heavier side is rewritten to the lighter one.
 pub fn extract_demodulator(
     clause: &Clause,
     eq_symbol: crate::data::SymbolId,
-    lrpo: Option<&LRPO>,
+    ordering: Option<&dyn TermOrdering>,
 ) -> Option<Demodulator> {
     // Must be a unit clause
     if clause.literals.len() != 1 {
@@ -214,12 +631,12 @@ pub fn extract_demodulator(
                 return None;
             }
 
-            // Use LRPO as the primary ordering criterion for demodulation
+            // Use the term ordering as the primary criterion for demodulation
             // This ensures a well-founded ordering for Knuth-Bendix completion
-            let final_ordering = if let Some(ordering) = lrpo {
+            let final_ordering = if let Some(ordering) = ordering {
                 ordering.compare(lhs, rhs)
             } else {
-                // Fallback to weight-based ordering if no LRPO provided
+                // Fallback to weight-based ordering if no ordering provided
                 let weight_lhs = term_weight(lhs);
                 let weight_rhs = term_weight(rhs);
                 weight_lhs.cmp(&weight_rhs)
@@ -244,6 +661,85 @@ pub fn extract_demodulator(
     }
 }
 
+/// Build a demodulator from a user-supplied rule `lhs = rhs`, which is used
+/// left to right as written, whether or not `ordering` orients it that way.
+/// Rules whose sides differ only in their variables are lex-dependent.
+/// With `implication` the `->` symbol, a rule may also be conditional,
+/// `condition -> (lhs = rhs)`.
+pub fn user_demodulator(
+    clause: &Clause,
+    eq_symbol: crate::data::SymbolId,
+    implication: Option<crate::data::SymbolId>,
+    ordering: &Rc<dyn TermOrdering>,
+) -> Result<Demodulator, String> {
+    let (condition, equation) = match clause.literals.as_slice() {
+        [lit] if lit.sign => match &lit.atom {
+            Term::Application { symbol, args } if Some(*symbol) == implication && args.len() == 2 => {
+                (Some(&args[0]), &args[1])
+            }
+            atom => (None, atom),
+        },
+        _ => return Err("not a positive unit equality".to_string()),
+    };
+    let (lhs, rhs) = match equation {
+        Term::Application { symbol, args } if *symbol == eq_symbol && args.len() == 2 => (&args[0], &args[1]),
+        _ => return Err("not an equality".to_string()),
+    };
+
+    let mut lhs_vars = Vec::new();
+    collect_variables(lhs, &mut lhs_vars);
+    let mut rhs_vars = Vec::new();
+    collect_variables(rhs, &mut rhs_vars);
+    if rhs_vars.iter().any(|var| !lhs_vars.contains(var)) {
+        return Err("right side has variables that do not occur on the left side".to_string());
+    }
+    if let Some(condition) = condition {
+        let mut condition_vars = Vec::new();
+        collect_variables(condition, &mut condition_vars);
+        if condition_vars.iter().any(|var| !lhs_vars.contains(var)) {
+            return Err("condition has variables that do not occur on the left side".to_string());
+        }
+    }
+
+    let mut demod = Demodulator::new(lhs.clone(), rhs.clone());
+    demod.condition = condition.cloned();
+    if same_up_to_variables(lhs, rhs) {
+        demod.lex_dependent = Some(Rc::clone(ordering));
+    }
+    Ok(demod)
+}
+
+fn collect_variables(term: &Term, vars: &mut Vec<VariableId>) {
+    match term {
+        Term::Variable { id, .. } => {
+            if !vars.contains(id) {
+                vars.push(*id);
+            }
+        }
+        Term::Application { args, .. } => {
+            for arg in args {
+                collect_variables(arg, vars);
+            }
+        }
+    }
+}
+
+/// Check if two terms are equal when every variable is treated as the same.
+fn same_up_to_variables(t1: &Term, t2: &Term) -> bool {
+    match (t1, t2) {
+        (Term::Variable { .. }, Term::Variable { .. }) => true,
+        (
+            Term::Application { symbol: s1, args: args1 },
+            Term::Application { symbol: s2, args: args2 },
+        ) => {
+            s1 == s2
+                && args1.len() == args2.len()
+                && args1.iter().zip(args2.iter()).all(|(a, b)| same_up_to_variables(a, b))
+        }
+        _ => false,
+    }
+}
+
 /// Check if two terms are structurally equal.
 fn terms_equal(t1: &Term, t2: &Term) -> bool {
     match (t1, t2) {
@@ -273,7 +769,7 @@ fn term_weight(term: &Term) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{VariableId};
+    use crate::data::{VariableId, LRPO};
     use crate::data::symbol::{SymbolKind, SymbolTable};
 
     fn make_var(id: u16) -> Term {
@@ -379,4 +875,147 @@ mod tests {
         assert_eq!(demod.lhs, f_a);
         assert_eq!(demod.rhs, b);
     }
+
+    #[test]
+    fn user_demodulators_keep_their_direction() {
+        let table = SymbolTable::new();
+        let eq_sym = table.intern("=", 2, SymbolKind::Function);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let equation = |lhs: Term, rhs: Term| {
+            Clause::new(vec![Literal::new(true, Term::application(eq_sym, vec![lhs, rhs]))])
+        };
+
+        // b = f(a) is used right to left by extract_demodulator, but as
+        // written here
+        let f_a = make_app(&table, "f", vec![a.clone()]);
+        let unordered: Rc<dyn TermOrdering> = Rc::new(LRPO::new());
+        let demod = user_demodulator(&equation(b.clone(), f_a.clone()), eq_sym, None, &unordered).unwrap();
+        assert_eq!(demod.apply(&b), Some(f_a));
+
+        let f_x = make_app(&table, "f", vec![make_var(0)]);
+        let error = user_demodulator(&equation(a.clone(), f_x), eq_sym, None, &unordered).unwrap_err();
+        assert!(error.contains("right side"), "{}", error);
+
+        // Commutativity only rewrites towards the smaller instance
+        let mut lrpo = LRPO::new();
+        lrpo.set_precedence(a.symbol().unwrap(), 0);
+        lrpo.set_precedence(b.symbol().unwrap(), 1);
+        let lrpo: Rc<dyn TermOrdering> = Rc::new(lrpo);
+        let comm = user_demodulator(
+            &equation(
+                make_app(&table, "g", vec![make_var(0), make_var(1)]),
+                make_app(&table, "g", vec![make_var(1), make_var(0)]),
+            ),
+            eq_sym,
+            None,
+            &lrpo,
+        )
+        .unwrap();
+        assert!(comm.lex_dependent.is_some());
+        let g_a_b = make_app(&table, "g", vec![a.clone(), b.clone()]);
+        let g_b_a = make_app(&table, "g", vec![b, a]);
+        let (big, small) = if lrpo.greater(&g_a_b, &g_b_a) { (g_a_b, g_b_a) } else { (g_b_a, g_a_b) };
+        assert_eq!(comm.apply(&big), Some(small.clone()));
+        assert_eq!(comm.apply(&small), None);
+    }
+
+    #[test]
+    fn shared_demodulation_agrees_and_caches_normal_forms() {
+        let table = SymbolTable::new();
+        let x = make_var(0);
+        let y = make_var(1);
+        let a = make_const(&table, "a");
+        let e = make_const(&table, "e");
+        let f = |l: Term, r: Term| make_app(&table, "f", vec![l, r]);
+        let g = |arg: Term| make_app(&table, "g", vec![arg]);
+        let p = table.intern("P", 2, SymbolKind::Predicate);
+        let demods = vec![Demodulator::new(f(e.clone(), x.clone()), x.clone()), Demodulator::new(g(g(y.clone())), y)];
+        let clause = Clause::new(vec![Literal::new(
+            true,
+            Term::application(p, vec![f(e.clone(), g(g(a.clone()))), g(f(e.clone(), g(g(f(e.clone(), a.clone())))))]),
+        )]);
+
+        let index = demodulator_index(&demods);
+        let mut bank = TermBank::new();
+        let mut cache = DemodCache::new();
+        let expected = demodulate_clause_evaluated(&clause, &demods, Some(&index), None, 50);
+        let shared = demodulate_clause_shared(&mut bank, &mut cache, &clause, &demods, Some(&index), None, 50);
+        assert_eq!(crate::data::format_clause(&shared.0, &table), "P(a,g(a))");
+        assert_eq!(shared.0.literals, expected.0.literals);
+        assert_eq!(shared.1, vec![0, 1]);
+        assert_eq!(shared.1, expected.1);
+
+        // A second pass is answered from the cache
+        let cached = cache.len();
+        assert!(cached > 0);
+        let again = demodulate_clause_shared(&mut bank, &mut cache, &clause, &demods, Some(&index), None, 50);
+        assert_eq!(again.0.literals, shared.0.literals);
+        assert_eq!(again.1, shared.1);
+        assert_eq!(cache.len(), cached);
+
+        // A new demodulator invalidates the normal forms
+        let mut more = demods.clone();
+        more.push(Demodulator::new(a.clone(), e.clone()));
+        let rewritten = demodulate_clause_shared(&mut bank, &mut cache, &clause, &more, Some(&index), None, 50);
+        assert_eq!(crate::data::format_clause(&rewritten.0, &table), "P(e,g(e))");
+        assert_eq!(rewritten.1, vec![0, 1, 2]);
+
+        // Results cut short by the depth limit are the same as unshared ones
+        let shallow = demodulate_clause_shared(&mut bank, &mut cache, &clause, &more, Some(&index), None, 2);
+        let expected = demodulate_clause_evaluated(&clause, &more, Some(&index), None, 2);
+        assert_eq!(shallow.0.literals, expected.0.literals);
+        assert_eq!(shallow.1, expected.1);
+
+        // The clause's variable names survive, though the bank has none
+        let u = Term::named_variable(VariableId::new(0), table.intern("u", 0, SymbolKind::Variable));
+        let clause = Clause::new(vec![Literal::new(true, Term::application(p, vec![f(e.clone(), u.clone()), g(g(u))]))]);
+        let named = demodulate_clause_shared(&mut bank, &mut cache, &clause, &demods, Some(&index), None, 50);
+        assert_eq!(crate::data::format_clause(&named.0, &table), "P(u,u)");
+    }
+
+    #[test]
+    fn conditional_demodulators_need_a_true_condition() {
+        let table = SymbolTable::new();
+        let eq_sym = table.intern("=", 2, SymbolKind::Function);
+        let implies = table.intern("->", 2, SymbolKind::Predicate);
+        let x = make_var(0);
+        let condition = make_app(&table, "$LT", vec![x.clone(), make_const(&table, "3")]);
+        let equation = make_app(&table, "=", vec![make_app(&table, "f", vec![x.clone()]), make_app(&table, "g", vec![x])]);
+        let rule = Clause::new(vec![Literal::new(true, Term::application(implies, vec![condition, equation]))]);
+        let ordering: Rc<dyn TermOrdering> = Rc::new(LRPO::new());
+        let demod = user_demodulator(&rule, eq_sym, Some(implies), &ordering).unwrap();
+        assert!(demod.condition.is_some());
+
+        let evaluator = crate::inference::Evaluator::new(&table, &[]).unwrap();
+        let eval = EvalContext { evaluator: &evaluator, symbols: &table };
+        let p = table.intern("P", 1, SymbolKind::Predicate);
+        let clause_of = |n: &str| {
+            Clause::new(vec![Literal::new(true, Term::application(p, vec![make_app(&table, "f", vec![make_const(&table, n)])]))])
+        };
+        let demods = [demod];
+
+        let (rewritten, fired, evaluated) = demodulate_clause_evaluated(&clause_of("1"), &demods, None, Some(eval), 50);
+        assert_eq!(crate::data::format_clause(&rewritten, &table), "P(g(1))");
+        assert_eq!(fired, vec![0]);
+        assert!(evaluated, "deciding the condition counts as evaluation");
+
+        let (kept, fired, _) = demodulate_clause_evaluated(&clause_of("5"), &demods, None, Some(eval), 50);
+        assert_eq!(crate::data::format_clause(&kept, &table), "P(f(5))");
+        assert!(fired.is_empty());
+        let mut bank = TermBank::new();
+        let mut cache = DemodCache::new();
+        let (shared, fired, evaluated) =
+            demodulate_clause_shared(&mut bank, &mut cache, &clause_of("1"), &demods, None, Some(eval), 50);
+        assert_eq!(crate::data::format_clause(&shared, &table), "P(g(1))");
+        assert_eq!(fired, vec![0]);
+        assert!(evaluated);
+        assert_eq!(demods[0].apply(&make_app(&table, "f", vec![make_const(&table, "1")])), None);
+
+        let bad = make_app(&table, "$LT", vec![make_var(1), make_const(&table, "3")]);
+        let equation = make_app(&table, "=", vec![make_app(&table, "f", vec![make_var(0)]), make_const(&table, "3")]);
+        let rule = Clause::new(vec![Literal::new(true, Term::application(implies, vec![bad, equation]))]);
+        let error = user_demodulator(&rule, eq_sym, Some(implies), &ordering).unwrap_err();
+        assert!(error.contains("condition"), "{}", error);
+    }
 }
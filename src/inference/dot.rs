@@ -0,0 +1,134 @@
+//! GraphViz export of proofs.
+//!
+//! [`proof_dot`] renders a proof as a DOT digraph with an edge from each
+//! parent to the clause derived from it.  Input clauses and the empty
+//! clause are highlighted; optionally the kept clauses that did not
+//! contribute to the proof are drawn too, greyed out, to show dead ends.
+
+use crate::data::{format_clause, Clause, ClauseArena, ClauseId, SymbolTable};
+use crate::inference::proof::unique_parents;
+use crate::inference::Proof;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// What to include in a DOT export.
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions {
+    /// Also draw the kept clauses that are not part of the proof
+    pub include_kept: bool,
+}
+
+/// Render `proof` as a GraphViz digraph.  `arena` supplies the non-proof
+/// clauses when [`DotOptions::include_kept`] is set.
+pub fn proof_dot(
+    proof: &Proof,
+    arena: &ClauseArena,
+    symbols: &SymbolTable,
+    options: &DotOptions,
+) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph proof {{").unwrap();
+    writeln!(out, "  rankdir=TB;").unwrap();
+    writeln!(out, "  node [shape=box, fontname=\"monospace\"];").unwrap();
+
+    let in_proof: HashSet<ClauseId> = proof.steps().iter().map(|step| step.id()).collect();
+    for step in proof.steps() {
+        write_node(&mut out, &step.clause, symbols, false);
+        for parent in &step.parents {
+            writeln!(out, "  c{} -> c{};", parent.0, step.id().0).unwrap();
+        }
+    }
+
+    if options.include_kept {
+        for clause in arena.iter() {
+            let Some(id) = clause.id else {
+                continue;
+            };
+            if in_proof.contains(&id) {
+                continue;
+            }
+            write_node(&mut out, clause, symbols, true);
+            for parent in unique_parents(clause, arena) {
+                writeln!(out, "  c{} -> c{} [color=gray70];", parent.0, id.0).unwrap();
+            }
+        }
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+fn write_node(out: &mut String, clause: &Clause, symbols: &SymbolTable, dead_end: bool) {
+    let id = clause.id.expect("exported clauses come from the arena");
+    let rule = clause.rule.map_or("input", |rule| rule.name());
+    let label = format!(
+        "{} [{}] {}\\nweight {}",
+        id.0,
+        rule,
+        escape(&format_clause(clause, symbols)),
+        clause.pick_weight
+    );
+    let style = if clause.literals.is_empty() {
+        ", shape=doubleoctagon, style=filled, fillcolor=salmon"
+    } else if clause.rule.is_none() && clause.parents.is_empty() {
+        ", style=filled, fillcolor=lightblue"
+    } else if dead_end {
+        ", color=gray50, fontcolor=gray50"
+    } else {
+        ""
+    };
+    writeln!(out, "  c{} [label=\"{}\"{}];", id.0, label, style).unwrap();
+}
+
+/// Escape a string for use inside a double-quoted DOT label.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proof_dot, DotOptions};
+    use crate::data::{
+        Clause, ClauseArena, InferenceRule, Literal, SymbolKind, SymbolTable,
+        Term,
+    };
+    use crate::inference::Proof;
+
+    #[test]
+    fn proof_graph_links_parents_and_highlights_ends() {
+        let symbols = SymbolTable::new();
+        let p = symbols.intern("p", 0, SymbolKind::Predicate);
+        let q = symbols.intern("q", 0, SymbolKind::Predicate);
+        let mut arena = ClauseArena::new();
+
+        let pos = arena.insert(Clause::new(vec![Literal::new(true, Term::application(p, vec![]))]));
+        let neg = arena.insert(Clause::new(vec![Literal::new(false, Term::application(p, vec![]))]));
+        let unused = arena.insert(Clause::new(vec![Literal::new(true, Term::application(q, vec![]))]));
+        let mut dead = Clause::new(vec![Literal::new(true, Term::application(q, vec![]))])
+            .with_rule(InferenceRule::Factor);
+        dead.add_parent(unused);
+        let dead_id = arena.insert(dead);
+        let mut empty = Clause::new(vec![]).with_rule(InferenceRule::BinaryRes);
+        empty.add_parent(neg);
+        empty.add_parent(pos);
+        let empty_id = arena.insert(empty);
+
+        let proof = Proof::from_arena(&arena, empty_id).unwrap();
+        let dot = proof_dot(&proof, &arena, &symbols, &DotOptions::default());
+        assert!(dot.starts_with("digraph proof {\n"));
+        assert!(dot.contains("  c1 [label=\"1 [input] p\\nweight 0\", style=filled, fillcolor=lightblue];\n"));
+        assert!(dot.contains(&format!("  c{} -> c{};\n", neg.0, empty_id.0)));
+        assert!(dot.contains(&format!("  c{} -> c{};\n", pos.0, empty_id.0)));
+        assert!(dot.contains("shape=doubleoctagon"));
+        assert!(!dot.contains(&format!("c{} ", unused.0)));
+
+        let options = DotOptions { include_kept: true };
+        let dot = proof_dot(&proof, &arena, &symbols, &options);
+        assert!(dot.contains(&format!(
+            "  c{} [label=\"{} [factor] q\\nweight 0\", color=gray50, fontcolor=gray50];\n",
+            dead_id.0, dead_id.0
+        )));
+        assert!(dot.contains(&format!("  c{} -> c{} [color=gray70];\n", unused.0, dead_id.0)));
+        assert!(dot.ends_with("}\n"));
+    }
+}
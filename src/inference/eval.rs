@@ -0,0 +1,211 @@
+//! Evaluable built-in functions and predicates.
+//!
+//! Otter computes `$SUM`, `$LT`, `$IF` and friends while demodulating: once
+//! the arguments of such a term are integer constants (or `$T`/`$F` for the
+//! boolean ones) the term is replaced by its value.  `make_evaluable(_+_,
+//! $SUM(_,_))` lets an ordinary operator stand for a built-in.  Literals
+//! whose atom evaluates to `$T` or `$F` are then decided and simplified away
+//! by the prover.
+
+use crate::data::{SymbolId, SymbolKind, SymbolTable, Term};
+use std::collections::HashMap;
+
+/// A built-in function or predicate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Builtin {
+    Sum,
+    Diff,
+    Prod,
+    Div,
+    Mod,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Not,
+    /// `$IF(condition, then, else)`
+    If,
+}
+
+/// Name and arity of every built-in.
+const BUILTINS: &[(&str, u8, Builtin)] = &[
+    ("$SUM", 2, Builtin::Sum),
+    ("$DIFF", 2, Builtin::Diff),
+    ("$PROD", 2, Builtin::Prod),
+    ("$DIV", 2, Builtin::Div),
+    ("$MOD", 2, Builtin::Mod),
+    ("$EQ", 2, Builtin::Eq),
+    ("$NE", 2, Builtin::Ne),
+    ("$LT", 2, Builtin::Lt),
+    ("$LE", 2, Builtin::Le),
+    ("$GT", 2, Builtin::Gt),
+    ("$GE", 2, Builtin::Ge),
+    ("$AND", 2, Builtin::And),
+    ("$OR", 2, Builtin::Or),
+    ("$NOT", 1, Builtin::Not),
+    ("$IF", 3, Builtin::If),
+];
+
+impl Builtin {
+    /// The built-in called `name`, with its arity.
+    pub fn lookup(name: &str) -> Option<(Builtin, u8)> {
+        BUILTINS
+            .iter()
+            .find(|(builtin_name, _, _)| *builtin_name == name)
+            .map(|(_, arity, builtin)| (*builtin, *arity))
+    }
+}
+
+/// Which symbols evaluate, and the `$T`/`$F` constants they evaluate to.
+#[derive(Clone, Debug, Default)]
+pub struct Evaluator {
+    builtins: HashMap<SymbolId, Builtin>,
+    /// `$T` and `$F`, once any symbol is evaluable
+    truth: Option<(SymbolId, SymbolId)>,
+}
+
+impl Evaluator {
+    /// Make the built-ins occurring in `symbols` evaluable, along with the
+    /// operators of each `make_evaluable(operator, evaluator)` pair, such as
+    /// `("_+_", "$SUM(_,_)")`.
+    pub fn new(symbols: &SymbolTable, evaluables: &[(String, String)]) -> Result<Self, String> {
+        let mut builtins = HashMap::new();
+        let mut register = |name: &str, arity: u8, builtin: Builtin| {
+            for id in symbols.get_ids_by_name(name) {
+                if symbols.get(id).is_some_and(|symbol| symbol.arity == arity) {
+                    builtins.insert(id, builtin);
+                }
+            }
+        };
+        for (name, arity, builtin) in BUILTINS {
+            register(name, *arity, *builtin);
+        }
+        for (operator, evaluator) in evaluables {
+            let name = evaluator.split('(').next().unwrap_or_default().trim();
+            let (builtin, arity) = Builtin::lookup(name)
+                .ok_or_else(|| format!("make_evaluable: unknown evaluable function {}", evaluator))?;
+            register(operator.trim().trim_matches('_'), arity, builtin);
+        }
+
+        if builtins.is_empty() {
+            return Ok(Self::default());
+        }
+        let truth = (
+            symbols.intern("$T", 0, SymbolKind::Constant),
+            symbols.intern("$F", 0, SymbolKind::Constant),
+        );
+        Ok(Self { builtins, truth: Some(truth) })
+    }
+
+    /// No symbol is evaluable.
+    pub fn is_empty(&self) -> bool {
+        self.builtins.is_empty()
+    }
+
+    /// Whether terms with `symbol` at the root evaluate.
+    pub fn is_evaluable(&self, symbol: SymbolId) -> bool {
+        self.builtins.contains_key(&symbol)
+    }
+
+    /// The value of `term` if its root is a built-in whose arguments are
+    /// already values; only the root is evaluated.
+    pub fn evaluate(&self, term: &Term, symbols: &SymbolTable) -> Option<Term> {
+        let Term::Application { symbol, args } = term else {
+            return None;
+        };
+        let builtin = *self.builtins.get(symbol)?;
+        let int = |index: usize| integer_value(&args[index], symbols);
+        let boolean = |index: usize| self.truth_value(&args[index]);
+        let number = |value: i64| Term::application(symbols.intern(value.to_string(), 0, SymbolKind::Constant), vec![]);
+
+        match builtin {
+            Builtin::Sum => int(0)?.checked_add(int(1)?).map(number),
+            Builtin::Diff => int(0)?.checked_sub(int(1)?).map(number),
+            Builtin::Prod => int(0)?.checked_mul(int(1)?).map(number),
+            Builtin::Div => int(0)?.checked_div(int(1)?).map(number),
+            Builtin::Mod => int(0)?.checked_rem(int(1)?).map(number),
+            Builtin::Eq => self.truth_term(int(0)? == int(1)?),
+            Builtin::Ne => self.truth_term(int(0)? != int(1)?),
+            Builtin::Lt => self.truth_term(int(0)? < int(1)?),
+            Builtin::Le => self.truth_term(int(0)? <= int(1)?),
+            Builtin::Gt => self.truth_term(int(0)? > int(1)?),
+            Builtin::Ge => self.truth_term(int(0)? >= int(1)?),
+            Builtin::And => self.truth_term(boolean(0)? && boolean(1)?),
+            Builtin::Or => self.truth_term(boolean(0)? || boolean(1)?),
+            Builtin::Not => self.truth_term(!boolean(0)?),
+            Builtin::If => Some(args[if boolean(0)? { 1 } else { 2 }].clone()),
+        }
+    }
+
+    /// `Some(true)` for `$T`, `Some(false)` for `$F`.
+    pub fn truth_value(&self, term: &Term) -> Option<bool> {
+        let (t, f) = self.truth?;
+        match term {
+            Term::Application { symbol, args } if args.is_empty() && *symbol == t => Some(true),
+            Term::Application { symbol, args } if args.is_empty() && *symbol == f => Some(false),
+            _ => None,
+        }
+    }
+
+    fn truth_term(&self, value: bool) -> Option<Term> {
+        let (t, f) = self.truth?;
+        Some(Term::application(if value { t } else { f }, vec![]))
+    }
+}
+
+/// An evaluator with the symbol table of the terms it evaluates.
+#[derive(Clone, Copy)]
+pub struct EvalContext<'a> {
+    pub evaluator: &'a Evaluator,
+    pub symbols: &'a SymbolTable,
+}
+
+fn integer_value(term: &Term, symbols: &SymbolTable) -> Option<i64> {
+    match term {
+        Term::Application { symbol, args } if args.is_empty() => symbols.get(*symbol)?.name.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Evaluator;
+    use crate::data::{format_term, SymbolKind, SymbolTable, Term};
+
+    fn app(symbols: &SymbolTable, name: &str, args: Vec<Term>) -> Term {
+        let kind = if args.is_empty() { SymbolKind::Constant } else { SymbolKind::Function };
+        Term::application(symbols.intern(name, args.len() as u8, kind), args)
+    }
+
+    #[test]
+    fn evaluates_ground_arithmetic_and_logic() {
+        let symbols = SymbolTable::new();
+        let n = |value: &str| app(&symbols, value, vec![]);
+        let sum = app(&symbols, "$SUM", vec![n("2"), n("3")]);
+        let lt = app(&symbols, "$LT", vec![n("2"), n("3")]);
+        let div = app(&symbols, "$DIV", vec![n("7"), n("0")]);
+        let plus = app(&symbols, "+", vec![n("4"), n("-6")]);
+        let open = app(&symbols, "$SUM", vec![n("a"), n("1")]);
+        let evaluator =
+            Evaluator::new(&symbols, &[("_+_".to_string(), "$SUM(_,_)".to_string())]).expect("evaluator");
+        let eval = |term: &Term| evaluator.evaluate(term, &symbols).map(|value| format_term(&value, &symbols));
+
+        assert_eq!(eval(&sum).as_deref(), Some("5"));
+        assert_eq!(eval(&plus).as_deref(), Some("-2"));
+        assert_eq!(eval(&lt).as_deref(), Some("$T"));
+        assert_eq!(eval(&div), None, "division by zero stays unevaluated");
+        assert_eq!(eval(&open), None, "non-integer arguments stay unevaluated");
+
+        let t = app(&symbols, "$T", vec![]);
+        let and = app(&symbols, "$AND", vec![t.clone(), app(&symbols, "$NOT", vec![t.clone()])]);
+        let cond = app(&symbols, "$IF", vec![t, n("a"), n("b")]);
+        let evaluator = Evaluator::new(&symbols, &[]).expect("evaluator");
+        assert_eq!(evaluator.evaluate(&and, &symbols), None, "$NOT($T) is not a value yet");
+        assert_eq!(evaluator.evaluate(&cond, &symbols), Some(n("a")));
+        assert!(Evaluator::new(&symbols, &[("_+_".to_string(), "$ADD(_,_)".to_string())]).is_err());
+    }
+}
@@ -18,6 +18,8 @@ pub struct Factor {
     pub parent_id: Option<ClauseId>,
     /// The substitution used
     pub substitution: Substitution,
+    /// The literal kept and the literal merged into it
+    pub literals: (usize, usize),
 }
 
 /// Attempt to factor a clause by finding two unifiable literals of the same sign.
@@ -67,6 +69,7 @@ pub fn factor_clause(clause: &Clause, parent_id: Option<ClauseId>) -> Vec<Factor
                     clause: factored_clause,
                     parent_id,
                     substitution: subst,
+                    literals: (i, j),
                 });
             }
         }
@@ -87,21 +87,29 @@ impl Default for HintData {
 pub struct HintsList {
     /// Hint clauses paired with their configuration data
     pub hints: Vec<(Clause, HintData)>,
+    /// Sum of [`Clause::memory_bytes`] over the hints
+    bytes: usize,
 }
 
 impl HintsList {
     /// Create an empty hints list.
     pub fn new() -> Self {
-        Self { hints: Vec::new() }
+        Self { hints: Vec::new(), bytes: 0 }
     }
 
     /// Add a hint with its configuration.
     pub fn add_hint(&mut self, clause: Clause, data: HintData) {
         if data.is_active() {
+            self.bytes += clause.memory_bytes();
             self.hints.push((clause, data));
         }
     }
 
+    /// Approximate bytes held by the hint clauses.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
     /// Check if there are any hints.
     pub fn is_empty(&self) -> bool {
         self.hints.is_empty()
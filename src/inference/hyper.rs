@@ -13,7 +13,7 @@
 //! - Result contains only negative literals
 
 use crate::data::{Clause, ClauseId, Literal, Term, VariableId};
-use crate::inference::{apply_to_literal, Substitution, Unifier};
+use crate::inference::{apply_to_literal, variable_offset, Substitution, Unifier};
 
 /// Result of a hyperresolution step.
 #[derive(Clone, Debug)]
@@ -24,6 +24,9 @@ pub struct HyperResolvent {
     pub nucleus_id: Option<ClauseId>,
     /// The satellite clause IDs used
     pub satellite_ids: Vec<Option<ClauseId>>,
+    /// `(nucleus literal, satellite literal)` resolved with each satellite,
+    /// in the order of `satellite_ids`
+    pub clashes: Vec<(usize, usize)>,
     /// The combined substitution
     pub substitution: Substitution,
 }
@@ -101,7 +104,8 @@ pub fn hyperresolve(
         &pos_literals,
         Substitution::new(),
         Vec::new(),
-        100, // Variable offset for first satellite
+        Vec::new(),
+        variable_offset(nucleus), // Variable offset for first satellite
         nucleus.proof_depth,
         &mut results,
     );
@@ -121,6 +125,7 @@ fn hyperresolve_recursive(
     pos_literals: &[&Literal],
     current_subst: Substitution,
     used_satellites: Vec<Option<ClauseId>>,
+    used_literals: Vec<usize>,
     var_offset: u16,
     current_max_depth: usize,
     results: &mut Vec<HyperResolvent>,
@@ -158,6 +163,7 @@ fn hyperresolve_recursive(
             clause: result_clause,
             nucleus_id,
             satellite_ids: used_satellites,
+            clashes: neg_indices.iter().copied().zip(used_literals).collect(),
             substitution: current_subst,
         });
         return;
@@ -212,6 +218,8 @@ fn hyperresolve_recursive(
             // Recurse to resolve next negative literal
             let mut new_used = used_satellites.clone();
             new_used.push(satellite_ids.get(sat_idx).copied().flatten());
+            let mut new_literals = used_literals.clone();
+            new_literals.push(lit_idx);
 
             hyperresolve_recursive(
                 nucleus,
@@ -223,7 +231,8 @@ fn hyperresolve_recursive(
                 pos_literals,
                 combined_subst,
                 new_used,
-                var_offset + 100,
+                new_literals,
+                var_offset + variable_offset(satellite),
                 std::cmp::max(current_max_depth, satellite.proof_depth),
                 results,
             );
@@ -281,7 +290,7 @@ pub fn hyperresolve_units(
         &pos_literals,
         Substitution::new(),
         Vec::new(),
-        100,
+        variable_offset(nucleus),
         &mut results,
     );
 
@@ -323,6 +332,7 @@ fn hyperresolve_units_recursive(
             clause: result_clause,
             nucleus_id,
             satellite_ids: used_satellites,
+            clashes: neg_indices.iter().map(|&idx| (idx, 0)).collect(),
             substitution: current_subst,
         });
         return;
@@ -358,7 +368,7 @@ fn hyperresolve_units_recursive(
             pos_literals,
             new_subst,
             new_used,
-            var_offset + 100,
+            var_offset + variable_offset(satellite),
             results,
         );
     }
@@ -422,7 +432,8 @@ pub fn neg_hyperresolve(
         &neg_literals,
         Substitution::new(),
         Vec::new(),
-        100, // Variable offset for first satellite
+        Vec::new(),
+        variable_offset(nucleus), // Variable offset for first satellite
         nucleus.proof_depth,
         &mut results,
     );
@@ -442,6 +453,7 @@ fn neg_hyperresolve_recursive(
     neg_literals: &[&Literal],
     current_subst: Substitution,
     used_satellites: Vec<Option<ClauseId>>,
+    used_literals: Vec<usize>,
     var_offset: u16,
     current_max_depth: usize,
     results: &mut Vec<HyperResolvent>,
@@ -479,6 +491,7 @@ fn neg_hyperresolve_recursive(
             clause: result_clause,
             nucleus_id,
             satellite_ids: used_satellites,
+            clashes: pos_indices.iter().copied().zip(used_literals).collect(),
             substitution: current_subst,
         });
         return;
@@ -491,7 +504,7 @@ fn neg_hyperresolve_recursive(
     // Try each satellite
     for (sat_idx, satellite) in satellites.iter().enumerate() {
         // Look for a negative literal in the satellite that unifies with our positive
-        for sat_lit in satellite.literals.iter() {
+        for (lit_idx, sat_lit) in satellite.literals.iter().enumerate() {
             if sat_lit.sign {
                 continue; // Skip positive literals in satellite
             }
@@ -514,6 +527,8 @@ fn neg_hyperresolve_recursive(
             // Recurse to resolve next positive literal
             let mut new_used = used_satellites.clone();
             new_used.push(satellite_ids.get(sat_idx).copied().flatten());
+            let mut new_literals = used_literals.clone();
+            new_literals.push(lit_idx);
 
             neg_hyperresolve_recursive(
                 nucleus,
@@ -525,7 +540,8 @@ fn neg_hyperresolve_recursive(
                 neg_literals,
                 combined_subst,
                 new_used,
-                var_offset + 100,
+                new_literals,
+                var_offset + variable_offset(satellite),
                 std::cmp::max(current_max_depth, satellite.proof_depth),
                 results,
             );
@@ -581,7 +597,7 @@ pub fn neg_hyperresolve_units(
         &neg_literals,
         Substitution::new(),
         Vec::new(),
-        100,
+        variable_offset(nucleus),
         nucleus.proof_depth,
         &mut results,
     );
@@ -627,6 +643,7 @@ fn neg_hyperresolve_units_recursive(
             clause: result_clause,
             nucleus_id,
             satellite_ids: used_satellites,
+            clashes: pos_indices.iter().map(|&idx| (idx, 0)).collect(),
             substitution: current_subst,
         });
         return;
@@ -658,7 +675,7 @@ fn neg_hyperresolve_units_recursive(
             neg_literals,
             new_subst,
             new_used,
-            var_offset + 100,
+            var_offset + variable_offset(satellite),
             std::cmp::max(current_max_depth, satellite.proof_depth),
             results,
         );
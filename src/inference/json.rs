@@ -0,0 +1,132 @@
+//! JSON report of a prover run.
+//!
+//! [`json_report`] gathers what the text output spreads over several
+//! sections (the result, statistics, effective settings, the proofs and
+//! their answers) into a single JSON document for programs driving foras.
+
+use crate::data::{format_clause, SymbolTable};
+use crate::inference::{Proof, ProofResult, Prover, ProverConfig, ProverStats};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Report<'a> {
+    /// `proof`, `saturated` or `resource_limit`
+    result: &'static str,
+    /// The limit that stopped the search, for `resource_limit`
+    limit_type: Option<&'a str>,
+    statistics: ProverStats,
+    config: &'a ProverConfig,
+    /// Every proof found, in the order found; more than one with max_proofs
+    proofs: Vec<Vec<Step>>,
+    /// Arguments of each answer literal of every proof's last clause, in
+    /// the order of the proofs
+    answers: Vec<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct Step {
+    id: u32,
+    rule: Option<&'static str>,
+    parents: Vec<u32>,
+    /// Otter's bracketed justification, e.g. `para_into,3.1.1,2.1`
+    justification: String,
+    clause: String,
+    weight: i32,
+    depth: usize,
+}
+
+/// Render the outcome of `prover`'s last search, which returned `result`,
+/// as a pretty-printed JSON document.
+pub fn json_report(prover: &Prover, result: &ProofResult) -> String {
+    let (kind, limit_type, answers) = match result {
+        ProofResult::Proof { answers, .. } => ("proof", None, answers.as_slice()),
+        ProofResult::Saturated { .. } => ("saturated", None, &[][..]),
+        ProofResult::ResourceLimit { limit_type, .. } => ("resource_limit", Some(limit_type.as_str()), &[][..]),
+    };
+    let report = Report {
+        result: kind,
+        limit_type,
+        statistics: prover.statistics(),
+        config: prover.config(),
+        proofs: prover.proofs().iter().map(|proof| steps(proof, prover.symbols())).collect(),
+        answers: answers.iter().map(|answer| answer.format_args(prover.symbols())).collect(),
+    };
+    serde_json::to_string_pretty(&report).expect("reports contain only strings and numbers")
+}
+
+fn steps(proof: &Proof, symbols: &SymbolTable) -> Vec<Step> {
+    proof
+        .steps()
+        .iter()
+        .map(|step| Step {
+            id: step.id().0,
+            rule: step.rule.map(|rule| rule.name()),
+            parents: step.parents.iter().map(|id| id.0).collect(),
+            justification: step.otter_justification(),
+            clause: format_clause(&step.clause, symbols),
+            weight: step.clause.pick_weight,
+            depth: step.depth,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_report;
+    use crate::inference::ProverBuilder;
+    use crate::parser::Parser;
+
+    #[test]
+    fn report_carries_result_proof_and_answers() {
+        let input = r#"
+set(binary_res).
+list(usable).
+-P(x) | $Ans(x).
+end_of_list.
+list(sos).
+P(a).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        let result = prover.search();
+
+        let report: serde_json::Value = serde_json::from_str(&json_report(&prover, &result)).unwrap();
+        assert_eq!(report["result"], "proof");
+        assert!(report["limit_type"].is_null());
+        assert_eq!(report["config"]["use_binary_res"], true);
+        assert_eq!(report["statistics"]["clauses_given"], prover.statistics().clauses_given);
+        let proofs = report["proofs"].as_array().unwrap();
+        assert_eq!(proofs.len(), 1);
+        let steps = proofs[0].as_array().unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2]["rule"], "binary");
+        assert_eq!(steps[2]["clause"], "$Ans(a)");
+        assert_eq!(report["answers"], serde_json::json!([["a"]]));
+    }
+
+    #[test]
+    fn report_lists_every_proof() {
+        let input = r#"
+set(binary_res).
+assign(max_proofs, 2).
+list(usable).
+-P(x) | $Ans(x).
+end_of_list.
+list(sos).
+P(a).
+P(b).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let mut prover = ProverBuilder::new().build(&file).expect("build prover");
+        let result = prover.search();
+
+        let report: serde_json::Value = serde_json::from_str(&json_report(&prover, &result)).unwrap();
+        let proofs = report["proofs"].as_array().unwrap();
+        assert_eq!(proofs.len(), 2);
+        assert_eq!(proofs[0].as_array().unwrap().last().unwrap()["clause"], "$Ans(a)");
+        assert_eq!(proofs[1].as_array().unwrap().last().unwrap()["clause"], "$Ans(b)");
+        assert_eq!(report["answers"], serde_json::json!([["a"], ["b"]]));
+    }
+}
@@ -6,8 +6,8 @@
 //!
 //! Based on the original Foras 3.3 linkur.c implementation.
 
-use crate::data::{Clause, ClauseId, Literal};
-use crate::inference::resolution::rename_variables;
+use crate::data::{Clash, Clause, ClauseId, Literal, LiteralPos};
+use crate::inference::resolution::{rename_variables, term_variable_offset, variable_offset};
 use crate::inference::{Substitution, Unifier};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -56,6 +56,8 @@ pub struct LinkNode {
     goal: Literal,                      // Literal to resolve
     _goal_to_resolve: Option<Literal>,   // Instantiated form after substitution
     current_clause: Option<Clause>,     // Current clause being tried for resolution
+    literal_index: usize,               // Position of the goal in its own clause
+    satellite: Option<(usize, usize)>,  // Usable clause and literal the goal resolved with
 
     // Unification state
     subst: Substitution,
@@ -80,6 +82,8 @@ impl LinkNode {
             goal: goal.clone(),
             _goal_to_resolve: Some(goal),
             current_clause: None,
+            literal_index: 0,
+            satellite: None,
             subst: Substitution::new(),
             _near_poss_nuc: 0,
             _farthest_sat: 0,
@@ -145,9 +149,10 @@ pub fn initialize_tree(given: &Clause) -> Option<Rc<RefCell<LinkNode>>> {
     let root = Rc::new(RefCell::new(LinkNode::new(given.literals[0].clone())));
 
     // Create child nodes for each literal in the nucleus
-    for literal in &given.literals {
+    for (idx, literal) in given.literals.iter().enumerate() {
         let child = Rc::new(RefCell::new(LinkNode::new(literal.clone())));
         child.borrow_mut().parent = Some(root.clone());
+        child.borrow_mut().literal_index = idx;
         root.borrow_mut().add_child(child);
     }
 
@@ -282,6 +287,8 @@ pub struct ResolventResult {
     pub substitution: Substitution,
     /// Which literal in the clause was unified (index)
     pub unified_literal_idx: usize,
+    /// Index of the clause in the usable slice
+    pub usable_index: usize,
 }
 
 /// Attempt to find a clause from the usable set that can resolve with the goal.
@@ -297,7 +304,7 @@ pub fn generate_resolvent(
     var_offset: u16,
 ) -> Option<ResolventResult> {
     // Try each usable clause
-    for clause in usable {
+    for (usable_index, clause) in usable.iter().enumerate() {
         // Skip empty clauses
         if clause.literals.is_empty() {
             continue;
@@ -335,6 +342,7 @@ pub fn generate_resolvent(
                     clause: renamed_clause, // Return the renamed clause
                     substitution: new_subst,
                     unified_literal_idx: idx,
+                    usable_index,
                 });
             }
         }
@@ -350,10 +358,65 @@ pub struct LinkedURResolvent {
     pub clause: Clause,
     /// IDs of parent clauses (nucleus + satellites)
     pub parent_ids: Vec<Option<ClauseId>>,
+    /// Usable clauses resolved against, by index; occurrence `k` is parent
+    /// `k + 1` of `clashes`, parent 0 being the nucleus
+    pub satellites: Vec<usize>,
+    /// Resolved literal pairs
+    pub clashes: Vec<Clash>,
     /// Substitution applied
     pub substitution: Substitution,
 }
 
+/// Collect the satellites and clashes of a resolved tree, depth first.
+///
+/// The nucleus is parent 0; each resolved node adds its satellite as the
+/// next parent, and the node's children are literals of that satellite.
+fn collect_clashes(tree: &Rc<RefCell<LinkNode>>) -> (Vec<usize>, Vec<Clash>) {
+    let mut satellites = Vec::new();
+    let mut clashes = Vec::new();
+    let mut pending = vec![(tree.clone(), 0)];
+    while let Some((node, occurrence)) = pending.pop() {
+        let mut current_child = node.borrow().first_child.clone();
+        while let Some(child_node) = current_child {
+            let child = child_node.borrow();
+            if let (Some((usable_index, literal)), Some(_)) =
+                (child.satellite, &child.current_clause)
+            {
+                satellites.push(usable_index);
+                let child_occurrence = satellites.len();
+                clashes.push(Clash::new(
+                    LiteralPos::new(occurrence, child.literal_index),
+                    LiteralPos::new(child_occurrence, literal),
+                ));
+                pending.push((child_node.clone(), child_occurrence));
+            }
+            current_child = child.next_sibling.clone();
+        }
+    }
+    (satellites, clashes)
+}
+
+/// The first variable id above every variable of the search so far: the
+/// nucleus, the goals and satellites in the tree and the bindings of
+/// `subst`.
+fn fresh_variable_offset(nucleus: &Clause, tree: &Rc<RefCell<LinkNode>>, subst: &Substitution) -> u16 {
+    let mut offset = variable_offset(nucleus);
+    for (var, term) in subst.iter() {
+        offset = offset.max(var.as_u16() + 1).max(term_variable_offset(term));
+    }
+    let mut pending = vec![tree.clone()];
+    while let Some(node) = pending.pop() {
+        let node = node.borrow();
+        offset = offset.max(term_variable_offset(&node.goal.atom));
+        if let Some(clause) = &node.current_clause {
+            offset = offset.max(variable_offset(clause));
+        }
+        pending.extend(node.first_child.clone());
+        pending.extend(node.next_sibling.clone());
+    }
+    offset
+}
+
 /// Build the final UR resolvent from a complete resolution path.
 ///
 /// Walks back through the tree collecting all the clauses that were used,
@@ -491,10 +554,8 @@ pub fn linked_ur_resolve(
         let goal = current.borrow().goal.clone();
         let current_subst = current.borrow().subst.clone();
 
-        // Calculate variable offset based on depth to ensure unique variables
-        // Use a large multiplier to avoid collisions (e.g. 1000 per level)
-        // Level 0 is nucleus. Level 1 is first satellite (1000), etc.
-        let var_offset = ((depth as u16) + 1) * 1000;
+        // Rename the next satellite apart from every variable so far
+        let var_offset = fresh_variable_offset(nucleus, &root, &current_subst);
 
         // Debug: trace for Nucleus 4 (CD)
         let debug = nucleus_id == Some(crate::data::ClauseId(4));
@@ -514,6 +575,8 @@ pub fn linked_ur_resolve(
                 {
                     let mut node_mut = current.borrow_mut();
                     node_mut.current_clause = Some(resolvent.clause.clone());
+                    node_mut.satellite =
+                        Some((resolvent.usable_index, resolvent.unified_literal_idx));
                     node_mut.subst = resolvent.substitution.clone();
                     node_mut.first = false;
                 }
@@ -524,14 +587,15 @@ pub fn linked_ur_resolve(
                     .iter()
                     .enumerate()
                     .filter(|(idx, _)| *idx != resolvent.unified_literal_idx)
-                    .map(|(_, lit)| lit.clone())
+                    .map(|(idx, lit)| (idx, lit.clone()))
                     .collect();
 
                 if !remaining_literals.is_empty() {
                     // Create child nodes for each remaining literal
-                    for lit in &remaining_literals {
+                    for (idx, lit) in &remaining_literals {
                         let child = Rc::new(RefCell::new(LinkNode::new(lit.clone())));
                         child.borrow_mut().parent = Some(current.clone());
+                        child.borrow_mut().literal_index = *idx;
                         child.borrow_mut().subst = resolvent.substitution.clone();
                         current.borrow_mut().add_child(child);
                     }
@@ -545,8 +609,8 @@ pub fn linked_ur_resolve(
                     let mut final_clause = build_ur_resolvent(&root, nucleus, &resolvent.substitution);
 
                     let parent_ids = vec![nucleus_id];
-                    // In full implementation, collect all satellite clause IDs
-                    
+                    let (satellites, clashes) = collect_clashes(&root);
+
                     if let Some(id) = nucleus_id {
                         final_clause.add_parent(id);
                     }
@@ -554,6 +618,8 @@ pub fn linked_ur_resolve(
                     results.push(LinkedURResolvent {
                         clause: final_clause,
                         parent_ids,
+                        satellites,
+                        clashes,
                         substitution: resolvent.substitution.clone(),
                     });
 
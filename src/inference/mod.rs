@@ -5,38 +5,56 @@
 
 mod builder;
 mod demod;
+mod dot;
+mod eval;
 mod factor;
 mod hints;
 mod hyper;
+mod json;
 mod linked_ur;
 mod output;
 mod para;
+mod proof;
+mod proof_check;
+mod proof_object;
 mod prover;
 mod resolution;
+mod shorten;
 mod subsume;
+mod superposition;
+mod szs;
 mod unify;
 mod unit_del;
 mod ur;
 
 pub use builder::ProverBuilder;
 pub use demod::{
-    demodulate_clause, demodulate_clause_with_limit, demodulate_literal, demodulate_term, extract_demodulator,
-    Demodulator,
+    demodulate_clause, demodulate_clause_evaluated, demodulate_clause_shared, demodulate_clause_traced, demodulate_clause_with_limit, demodulate_literal, demodulate_term, demodulator_index, extract_demodulator,
+    user_demodulator, DemodCache, Demodulator,
 };
+pub use dot::{proof_dot, DotOptions};
+pub use eval::{Builtin, EvalContext, Evaluator};
 pub use factor::{factor_clause, Factor};
 pub use hints::{adjust_weight_with_hints, hint_keep_test, HintData, HintsList, MAX_WEIGHT};
 pub use hyper::{
     hyperresolve, hyperresolve_units, neg_hyperresolve, neg_hyperresolve_units, HyperResolvent,
 };
+pub use json::json_report;
 pub use linked_ur::{linked_ur_resolve, LinkedURConfig, LinkedURResolvent};
 pub use output::{OutputFormatter, ProverStats};
 pub use para::{paramodulate_into, Paramodulant};
-pub use prover::{ProofResult, Prover, ProverConfig};
+pub use proof::{Proof, ProofStep};
+pub use proof_check::{check_proof, check_proof_with_evaluator, ProofCheckError};
+pub use proof_object::{ObjectRule, ObjectStep, ProofObject};
+pub use prover::{Answer, ProofResult, Prover, ProverConfig};
 pub use resolution::{
     all_resolvents, apply_to_clause, apply_to_literal, binary_resolve,
-    rename_variables, Resolvent,
+    rename_variables, variable_offset, Resolvent,
 };
+pub use shorten::{shorten_proof, ShortenConfig, ShortenedSearch};
+pub use szs::{SzsStatus, input_has_conjecture, tstp_derivation};
 pub use subsume::{back_subsumed, forward_subsumed, forward_subsumed_ancestor, subsumes};
-pub use unify::{unify, Substitution, UnificationError, Unifier};
+pub use superposition::{equality_factors, equality_resolvents, superpose, EqualityFactor, EqualityResolvent};
+pub use unify::{match_shared, unify, SharedSubstitution, Substitution, UnificationError, Unifier};
 pub use unit_del::{forward_unit_deletion, unit_delete, UnitDeleted};
 pub use ur::{ur_resolve, URResolvent};
@@ -2,24 +2,33 @@
 //!
 //! Generates output in Foras's format to enable parity testing.
 
-use crate::inference::{ProofResult, ProverConfig};
+use crate::data::{SymbolTable, format_clause};
+use crate::inference::{Proof, ProofObject, ProofResult, ProverConfig};
+use serde::{Serialize, Serializer};
 use std::fmt::Write;
 use std::time::Duration;
 
-/// Statistics from a prover run.
-#[derive(Clone, Debug, Default)]
+/// Statistics from a prover run.  Times serialize as fractional seconds.
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct ProverStats {
     pub clauses_given: usize,
     pub clauses_generated: usize,
     pub clauses_kept: usize,
     pub clauses_forward_subsumed: usize,
     pub clauses_back_subsumed: usize,
+    #[serde(serialize_with = "seconds")]
     pub user_cpu_time: Duration,
+    #[serde(serialize_with = "seconds")]
     pub system_cpu_time: Duration,
+    #[serde(serialize_with = "seconds")]
     pub wall_clock_time: Duration,
     pub kbytes_malloced: usize,
 }
 
+fn seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
 /// Format prover output in Foras style.
 pub struct OutputFormatter {
     /// Buffer for output
@@ -74,14 +83,55 @@ impl OutputFormatter {
         }
     }
 
+    /// Write a proof in Otter's layout, one `id [rule,parents] clause.` line
+    /// per step.  The closing "end of proof" line comes from
+    /// [`write_result`](Self::write_result).
+    pub fn write_proof(&mut self, proof: &Proof, symbols: &SymbolTable) {
+        writeln!(&mut self.output).unwrap();
+        writeln!(
+            &mut self.output,
+            "Length of proof is {}.  Level of proof is {}.",
+            proof.length(),
+            proof.level()
+        )
+        .unwrap();
+        writeln!(&mut self.output).unwrap();
+        writeln!(&mut self.output, "---------------- PROOF ----------------")
+            .unwrap();
+        writeln!(&mut self.output).unwrap();
+        for step in proof.steps() {
+            writeln!(
+                &mut self.output,
+                "{} [{}] {}.",
+                step.id().0,
+                step.otter_justification(),
+                format_clause(&step.clause, symbols)
+            )
+            .unwrap();
+        }
+    }
+
+    /// Close a proof written with [`write_proof`](Self::write_proof) when
+    /// another proof follows it.
+    pub fn write_end_of_proof(&mut self) {
+        writeln!(&mut self.output).unwrap();
+        writeln!(&mut self.output, "------------ end of proof -------------")
+            .unwrap();
+        writeln!(&mut self.output).unwrap();
+    }
+
+    /// Write a proof object, as Otter does after the proof when
+    /// `build_proof_object` is set.
+    pub fn write_proof_object(&mut self, object: &ProofObject, symbols: &SymbolTable) {
+        writeln!(&mut self.output).unwrap();
+        self.output.push_str(&object.to_ivy(symbols));
+    }
+
     /// Write the result of proof search.
     pub fn write_result(&mut self, result: &ProofResult) {
         match result {
             ProofResult::Proof { .. } => {
-                writeln!(&mut self.output).unwrap();
-                writeln!(&mut self.output, "------------ end of proof -------------")
-                    .unwrap();
-                writeln!(&mut self.output).unwrap();
+                self.write_end_of_proof();
                 writeln!(&mut self.output).unwrap();
                 writeln!(
                     &mut self.output,
@@ -267,6 +317,8 @@ mod tests {
             empty_clause_id: crate::data::ClauseId(1),
             clauses_generated: 100,
             clauses_kept: 50,
+            answers: Vec::new(),
+            proofs: vec![crate::data::ClauseId(1)],
         };
 
         formatter.write_result(&result);
@@ -274,4 +326,37 @@ mod tests {
 
         assert!(output.contains("end of proof"));
     }
+
+    #[test]
+    fn format_proof_in_otter_layout() {
+        use crate::data::{
+            Clause, ClauseArena, InferenceRule, Literal, SymbolKind, Term,
+        };
+
+        let symbols = SymbolTable::new();
+        let p = symbols.intern("p", 0, SymbolKind::Predicate);
+        let mut arena = ClauseArena::new();
+        let pos = arena.insert(Clause::new(vec![Literal::new(
+            true,
+            Term::application(p, vec![]),
+        )]));
+        let neg = arena.insert(Clause::new(vec![Literal::new(
+            false,
+            Term::application(p, vec![]),
+        )]));
+        let mut empty = Clause::new(vec![]).with_rule(InferenceRule::BinaryRes);
+        empty.add_parent(neg);
+        empty.add_parent(pos);
+        let empty_id = arena.insert(empty);
+        let proof = Proof::from_arena(&arena, empty_id).unwrap();
+
+        let mut formatter = OutputFormatter::new();
+        formatter.write_proof(&proof, &symbols);
+        let output = formatter.output();
+
+        assert!(output.contains("Length of proof is 1.  Level of proof is 1."));
+        assert!(output.contains(
+            "---------------- PROOF ----------------\n\n1 [] p.\n2 [] -p.\n3 [binary,2,1] $F.\n"
+        ));
+    }
 }
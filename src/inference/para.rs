@@ -16,7 +16,7 @@
 
 use crate::data::{Clause, ClauseId, Literal, Term};
 use crate::inference::{Substitution, Unifier};
-use crate::inference::resolution::rename_variables;
+use crate::inference::resolution::{rename_variables, variable_offset};
 
 /// Result of a paramodulation step.
 #[derive(Clone, Debug)]
@@ -27,6 +27,14 @@ pub struct Paramodulant {
     pub from_id: Option<ClauseId>,
     /// The "into" clause ID (being paramodulated into)
     pub into_id: Option<ClauseId>,
+    /// Index of the equality literal in the from clause
+    pub from_literal: usize,
+    /// The right side of the equality was the one unified with the subterm
+    pub from_right: bool,
+    /// Index of the literal paramodulated into
+    pub into_literal: usize,
+    /// Path of argument indices from the into atom to the rewritten subterm
+    pub position: Vec<usize>,
     /// The substitution used
     pub substitution: Substitution,
 }
@@ -76,11 +84,11 @@ pub fn paramodulate_into(
     // CRITICAL: Rename variables in from_clause to avoid conflicts with into_clause.
     // Without this, shared VariableIds cause the unifier to incorrectly conflate
     // independent variables, producing unsound results (e.g., deriving x=z from group axioms).
-    let var_offset = max_variable_id(into_clause).map_or(100, |id| id + 100);
+    let var_offset = variable_offset(into_clause);
     let renamed_from = rename_variables(from_clause, var_offset);
 
     // Find positive equality literals in the renamed from clause
-    for from_lit in &renamed_from.literals {
+    for (from_lit_idx, from_lit) in renamed_from.literals.iter().enumerate() {
         if !from_lit.sign || !is_equality(from_lit, eq_symbol) {
             continue;
         }
@@ -106,14 +114,14 @@ pub fn paramodulate_into(
                     para_into_right,
                 );
 
-                for (new_atom, subst) in replacements {
+                for (new_atom, subst, position) in replacements {
                     results.push(build_paramodulant(
                         &renamed_from,
                         from_id,
                         into_clause,
                         into_id,
-                        from_lit,
-                        into_lit_idx,
+                        (from_lit_idx, false),
+                        (into_lit_idx, position),
                         new_atom,
                         subst,
                     ));
@@ -132,14 +140,14 @@ pub fn paramodulate_into(
                     para_into_right,
                 );
 
-                for (new_atom, subst) in replacements {
+                for (new_atom, subst, position) in replacements {
                     results.push(build_paramodulant(
                         &renamed_from,
                         from_id,
                         into_clause,
                         into_id,
-                        from_lit,
-                        into_lit_idx,
+                        (from_lit_idx, true),
+                        (into_lit_idx, position),
                         new_atom,
                         subst,
                     ));
@@ -151,47 +159,28 @@ pub fn paramodulate_into(
     results
 }
 
-/// Find the maximum variable ID used in a clause.
-fn max_variable_id(clause: &Clause) -> Option<u16> {
-    let mut max_id: Option<u16> = None;
-    for lit in &clause.literals {
-        max_id = max_var_in_term(&lit.atom, max_id);
-    }
-    max_id
-}
-
-fn max_var_in_term(term: &Term, current_max: Option<u16>) -> Option<u16> {
-    match term {
-        Term::Variable { id, .. } => {
-            let v = id.as_u16();
-            Some(current_max.map_or(v, |m| m.max(v)))
-        }
-        Term::Application { args, .. } => {
-            let mut max = current_max;
-            for arg in args {
-                max = max_var_in_term(arg, max);
-            }
-            max
-        }
-    }
-}
-
 /// Build a paramodulant clause from the components.
-fn build_paramodulant(
+///
+/// `from` is the equality literal's index and whether its right side was
+/// used; `into` is the literal index and position rewritten.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn build_paramodulant(
     from_clause: &Clause,
     from_id: Option<ClauseId>,
     into_clause: &Clause,
     into_id: Option<ClauseId>,
-    from_lit: &Literal,
-    into_lit_idx: usize,
+    from: (usize, bool),
+    into: (usize, Vec<usize>),
     new_atom: Term,
     subst: Substitution,
 ) -> Paramodulant {
+    let (from_lit_idx, from_right) = from;
+    let (into_lit_idx, position) = into;
     let mut new_literals = Vec::new();
 
     // Add all literals from from_clause except the equality used
-    for lit in &from_clause.literals {
-        if !std::ptr::eq(lit, from_lit) {
+    for (idx, lit) in from_clause.literals.iter().enumerate() {
+        if idx != from_lit_idx {
             new_literals.push(subst.apply_to_literal(lit));
         }
     }
@@ -222,6 +211,10 @@ fn build_paramodulant(
         clause: result_clause,
         from_id,
         into_id,
+        from_literal: from_lit_idx,
+        from_right,
+        into_literal: into_lit_idx,
+        position,
         substitution: subst,
     }
 }
@@ -239,7 +232,7 @@ fn find_and_replace_subterms(
     is_target_equality: bool,
     para_into_left: bool,
     para_into_right: bool,
-) -> Vec<(Term, Substitution)> {
+) -> Vec<(Term, Substitution, Vec<usize>)> {
     let mut results = Vec::new();
 
     // Try to unify the entire term with the pattern
@@ -247,7 +240,7 @@ fn find_and_replace_subterms(
     if unifier.unify(target, pattern).is_ok() {
         let subst = unifier.into_substitution();
         let new_term = subst.apply(replacement);
-        results.push((new_term, subst));
+        results.push((new_term, subst, Vec::new()));
     }
 
     // Recursively try to replace subterms
@@ -274,14 +267,15 @@ fn find_and_replace_subterms(
                 para_into_right,
             );
 
-            for (new_arg, subst) in sub_replacements {
+            for (new_arg, subst, mut position) in sub_replacements {
                 let mut new_args = args.clone();
                 new_args[i] = new_arg;
                 let new_term = Term::Application {
                     symbol: *symbol,
                     args: new_args,
                 };
-                results.push((new_term, subst));
+                position.insert(0, i);
+                results.push((new_term, subst, position));
             }
         }
     }
@@ -0,0 +1,189 @@
+//! Proof extraction.
+//!
+//! A refutation is the empty (or answer) clause together with every ancestor
+//! reachable through `Clause::parents`.  [`Proof`] collects them in clause-id
+//! order, which is also derivation order: a clause is always stored after
+//! the clauses it was derived from.
+
+use crate::data::{Clause, ClauseArena, ClauseId, InferenceRule};
+use std::collections::HashMap;
+
+/// One clause of a proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofStep {
+    /// The clause, as stored in the arena
+    pub clause: Clause,
+    /// Distinct parents, in the order the inference recorded them
+    pub parents: Vec<ClauseId>,
+    /// Rule that derived the clause; `None` for input clauses
+    pub rule: Option<InferenceRule>,
+    /// Inference depth: 0 for input clauses, else one more than the deepest
+    /// parent
+    pub depth: usize,
+}
+
+impl ProofStep {
+    pub fn id(&self) -> ClauseId {
+        self.clause.id.expect("proof clauses come from the arena")
+    }
+
+    pub fn is_input(&self) -> bool {
+        self.rule.is_none() && self.parents.is_empty()
+    }
+
+    /// The bracketed part of Otter's proof line, e.g. `para_into,3.1.1,2.1`;
+    /// empty for input clauses.
+    pub fn otter_justification(&self) -> String {
+        match &self.clause.justification {
+            Some(justification) => justification.otter_string(self.rule),
+            None if self.is_input() => String::new(),
+            None => {
+                let mut parts = vec![self.rule.map_or("unknown", |rule| rule.name()).to_string()];
+                parts.extend(self.parents.iter().map(|id| id.0.to_string()));
+                parts.join(",")
+            }
+        }
+    }
+}
+
+/// An ordered proof, ending with the clause that completed the refutation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Proof {
+    steps: Vec<ProofStep>,
+}
+
+impl Proof {
+    /// Collect the proof of `conclusion` from `arena`, or `None` when the
+    /// clause is not stored there.
+    pub fn from_arena(arena: &ClauseArena, conclusion: ClauseId) -> Option<Self> {
+        arena.get(conclusion)?;
+
+        let mut pending = vec![conclusion];
+        let mut found: HashMap<ClauseId, &Clause> = HashMap::new();
+        while let Some(id) = pending.pop() {
+            if found.contains_key(&id) {
+                continue;
+            }
+            let Some(clause) = arena.get(id) else {
+                continue;
+            };
+            found.insert(id, clause);
+            pending.extend(unique_parents(clause, arena));
+        }
+
+        let mut ids: Vec<ClauseId> = found.keys().copied().collect();
+        ids.sort();
+        let mut depths: HashMap<ClauseId, usize> = HashMap::new();
+        let mut steps = Vec::with_capacity(ids.len());
+        for id in ids {
+            let clause = found[&id];
+            let parents = unique_parents(clause, arena);
+            let depth = parents
+                .iter()
+                .map(|parent| depths.get(parent).copied().unwrap_or(0) + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(id, depth);
+            steps.push(ProofStep {
+                clause: clause.clone(),
+                parents,
+                rule: clause.rule,
+                depth,
+            });
+        }
+        Some(Self { steps })
+    }
+
+    pub fn steps(&self) -> &[ProofStep] {
+        &self.steps
+    }
+
+    pub fn step(&self, id: ClauseId) -> Option<&ProofStep> {
+        self.steps.iter().find(|step| step.id() == id)
+    }
+
+    /// The empty or answer clause the proof ends with.
+    pub fn conclusion(&self) -> Option<&ProofStep> {
+        self.steps.last()
+    }
+
+    /// Otter's "length of proof": the number of derived (non-input) steps.
+    pub fn length(&self) -> usize {
+        self.steps.iter().filter(|step| !step.is_input()).count()
+    }
+
+    /// Otter's "level of proof": the depth of the conclusion.
+    pub fn level(&self) -> usize {
+        self.steps.iter().map(|step| step.depth).max().unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Parents in recorded order, without the repeats unit deletion can leave
+/// behind and without ids missing from the arena.
+pub(crate) fn unique_parents(clause: &Clause, arena: &ClauseArena) -> Vec<ClauseId> {
+    let mut parents = Vec::new();
+    for parent in clause.parents.iter() {
+        if Some(*parent) != clause.id
+            && !parents.contains(parent)
+            && arena.get(*parent).is_some()
+        {
+            parents.push(*parent);
+        }
+    }
+    parents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Proof;
+    use crate::data::{
+        Clause, ClauseArena, ClauseId, InferenceRule, Literal, SymbolKind,
+        SymbolTable, Term,
+    };
+
+    #[test]
+    fn collects_ancestors_in_order_with_depths() {
+        let symbols = SymbolTable::new();
+        let p = symbols.intern("p", 0, SymbolKind::Predicate);
+        let q = symbols.intern("q", 0, SymbolKind::Predicate);
+        let atom = |sym| Term::application(sym, vec![]);
+        let mut arena = ClauseArena::new();
+
+        let a = arena.insert(Clause::new(vec![
+            Literal::new(true, atom(p)),
+            Literal::new(true, atom(q)),
+        ]));
+        let b = arena.insert(Clause::new(vec![Literal::new(false, atom(p))]));
+        let unused = arena.insert(Clause::new(vec![Literal::new(true, atom(p))]));
+        let c = arena.insert(Clause::new(vec![Literal::new(false, atom(q))]));
+        let mut resolvent = Clause::new(vec![Literal::new(true, atom(q))])
+            .with_rule(InferenceRule::BinaryRes);
+        resolvent.add_parent(a);
+        resolvent.add_parent(b);
+        let d = arena.insert(resolvent);
+        let mut empty = Clause::new(vec![]).with_rule(InferenceRule::BinaryRes);
+        empty.add_parent(d);
+        empty.add_parent(c);
+        empty.add_parent(c);
+        let e = arena.insert(empty);
+
+        let proof = Proof::from_arena(&arena, e).expect("proof");
+        let ids: Vec<ClauseId> = proof.steps().iter().map(|s| s.id()).collect();
+        assert_eq!(ids, vec![a, b, c, d, e]);
+        assert!(proof.step(unused).is_none());
+        assert_eq!(proof.step(e).unwrap().parents, vec![d, c]);
+        assert_eq!(proof.step(d).unwrap().depth, 1);
+        assert_eq!(proof.conclusion().unwrap().depth, 2);
+        assert_eq!(proof.length(), 2);
+        assert_eq!(proof.level(), 2);
+        assert!(Proof::from_arena(&arena, ClauseId(99)).is_none());
+    }
+}
@@ -0,0 +1,761 @@
+//! Independent proof checking.
+//!
+//! [`check_proof`] replays every derived step of a [`Proof`] from its
+//! [`Justification`]: the parents are renamed apart, the recorded inference
+//! is redone with fresh unification and matching, the simplifications are
+//! applied in order, and the result must subsume the clause the prover
+//! stored.  None of the inference rules' code is reused, so a step that a
+//! buggy rule got wrong is reported instead of trusted.  Evaluation of
+//! built-ins needs the input's `make_evaluable` declarations: steps that
+//! evaluated are only replayed by [`check_proof_with_evaluator`], which
+//! re-evaluates their evaluable terms.
+
+use crate::data::{
+    Clash, Clause, ClauseId, Inference, Justification, Literal, Simplification, SymbolId, Term, VariableId,
+};
+use crate::inference::{subsumes, EvalContext, Proof, Substitution, Unifier};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Rewrites allowed while replaying one demodulation, as a guard against
+/// non-terminating demodulator sets.
+const MAX_REWRITES: usize = 10_000;
+
+/// A demodulator as replayed: left side, right side, and the condition of
+/// a conditional one.
+type Rule = (Term, Term, Option<Term>);
+
+/// Why a proof was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofCheckError {
+    /// A step refers to a clause that is not an earlier step of the proof
+    MissingClause { step: ClauseId, parent: ClauseId },
+    /// A derived clause carries no justification to replay
+    MissingJustification(ClauseId),
+    /// Replaying the justification does not yield the stored clause
+    Unsound { step: ClauseId, reason: String },
+}
+
+impl fmt::Display for ProofCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofCheckError::MissingClause { step, parent } => {
+                write!(f, "clause {} uses clause {}, which is not an earlier proof step", step.0, parent.0)
+            }
+            ProofCheckError::MissingJustification(id) => {
+                write!(f, "clause {} has no justification", id.0)
+            }
+            ProofCheckError::Unsound { step, reason } => {
+                write!(f, "clause {} does not follow: {}", step.0, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofCheckError {}
+
+/// Check every step of `proof`.
+///
+/// Input clauses are accepted as they are; every other clause must be
+/// justified in terms of earlier steps.  `eq_symbol` is needed to check
+/// `xx_res` steps.  Steps that evaluated built-ins are rejected; see
+/// [`check_proof_with_evaluator`].
+pub fn check_proof(proof: &Proof, eq_symbol: Option<SymbolId>) -> Result<(), ProofCheckError> {
+    check_proof_with_evaluator(proof, eq_symbol, None)
+}
+
+/// [`check_proof`], replaying the steps that evaluated built-ins with
+/// `eval`: evaluation happens between rewrites, conditional demodulators
+/// rewrite where their condition evaluates to `$T`, and only literals that
+/// evaluate to false may be dropped.
+pub fn check_proof_with_evaluator(
+    proof: &Proof,
+    eq_symbol: Option<SymbolId>,
+    eval: Option<EvalContext>,
+) -> Result<(), ProofCheckError> {
+    let clauses: HashMap<ClauseId, &Clause> = proof.steps().iter().map(|step| (step.id(), &step.clause)).collect();
+    for step in proof.steps() {
+        if step.is_input() && step.clause.justification.is_none() {
+            continue;
+        }
+        let id = step.id();
+        let justification = step
+            .clause
+            .justification
+            .as_ref()
+            .ok_or(ProofCheckError::MissingJustification(id))?;
+        let lookup = |parent: ClauseId| -> Result<&Clause, ProofCheckError> {
+            match clauses.get(&parent) {
+                Some(clause) if parent < id => Ok(*clause),
+                _ => Err(ProofCheckError::MissingClause { step: id, parent }),
+            }
+        };
+        check_step(&step.clause, justification, &lookup, eq_symbol, eval)
+            .map_err(|err| match err {
+                StepError::Missing(parent) => ProofCheckError::MissingClause { step: id, parent },
+                StepError::Unsound(reason) => ProofCheckError::Unsound { step: id, reason },
+            })?;
+    }
+    Ok(())
+}
+
+enum StepError {
+    Missing(ClauseId),
+    Unsound(String),
+}
+
+impl From<ProofCheckError> for StepError {
+    fn from(err: ProofCheckError) -> Self {
+        match err {
+            ProofCheckError::MissingClause { parent, .. } => StepError::Missing(parent),
+            other => StepError::Unsound(other.to_string()),
+        }
+    }
+}
+
+fn unsound<T>(reason: impl Into<String>) -> Result<T, StepError> {
+    Err(StepError::Unsound(reason.into()))
+}
+
+fn check_step<'a>(
+    clause: &Clause,
+    justification: &Justification,
+    lookup: &dyn Fn(ClauseId) -> Result<&'a Clause, ProofCheckError>,
+    eq_symbol: Option<SymbolId>,
+    eval: Option<EvalContext>,
+) -> Result<(), StepError> {
+    // Only steps recorded as evaluating may change anything by evaluation
+    let eval = if justification.simplifications.contains(&Simplification::Eval) {
+        match eval {
+            Some(eval) => Some(eval),
+            None => return unsound("built-in evaluation cannot be replayed without the evaluator"),
+        }
+    } else {
+        None
+    };
+    let mut renamer = Renamer::default();
+    let mut literals = match &justification.inference {
+        Inference::Resolution { parents, clashes } => {
+            let copies = parents
+                .iter()
+                .map(|id| Ok(renamer.fresh(lookup(*id)?)))
+                .collect::<Result<Vec<_>, ProofCheckError>>()?;
+            resolve(&copies, clashes)?
+        }
+        Inference::Paramodulation {
+            from,
+            from_literal,
+            from_right,
+            into,
+            into_literal,
+            position,
+        } => {
+            let from_copy = renamer.fresh(lookup(*from)?);
+            let into_copy = renamer.fresh(lookup(*into)?);
+            paramodulate(&from_copy, *from_literal, *from_right, &into_copy, *into_literal, position, eq_symbol)?
+        }
+        Inference::EqualityResolution { parent, literal } => {
+            equality_resolve(renamer.fresh(lookup(*parent)?), *literal, eq_symbol)?
+        }
+        Inference::EqualityFactoring { parent, literal, right, other, other_right } => equality_factor(
+            renamer.fresh(lookup(*parent)?),
+            (*literal, *right),
+            (*other, *other_right),
+            eq_symbol,
+        )?,
+        Inference::Copy { parent } => renamer.fresh(lookup(*parent)?),
+    };
+
+    for simplification in &justification.simplifications {
+        literals = match simplification {
+            Simplification::Factor { kept, merged } => factor(literals, *kept, *merged)?,
+            Simplification::Demod(steps) => {
+                let mut rules = Vec::new();
+                for (id, flipped) in steps {
+                    let (lhs, rhs, condition) = rewrite_rule(&renamer.fresh(lookup(*id)?), eq_symbol, eval)
+                        .ok_or_else(|| StepError::Unsound(format!("clause {} is not a unit equality", id.0)))?;
+                    rules.push(if *flipped { (rhs, lhs, condition) } else { (lhs, rhs, condition) });
+                }
+                let mut budget = MAX_REWRITES;
+                literals
+                    .iter()
+                    .map(|lit| Literal::new(lit.sign, normalize(&lit.atom, &rules, eval, &mut budget)))
+                    .collect()
+            }
+            Simplification::UnitDel(units) => {
+                for id in units {
+                    let unit = renamer.fresh(lookup(*id)?);
+                    literals = unit_delete(literals, &unit, *id)?;
+                }
+                literals
+            }
+            Simplification::XxRes => {
+                let eq = match eq_symbol {
+                    Some(eq) => eq,
+                    None => return unsound("xx_res without an equality symbol"),
+                };
+                let before = literals.len();
+                literals.retain(|lit| !is_trivial_disequality(lit, eq));
+                if literals.len() == before {
+                    return unsound("xx_res found no t!=t literal");
+                }
+                literals
+            }
+            Simplification::Eval => {
+                let Some(ctx) = eval else {
+                    return unsound("built-in evaluation cannot be replayed without the evaluator");
+                };
+                let mut budget = MAX_REWRITES;
+                let mut evaluated = Vec::new();
+                for lit in literals {
+                    let atom = normalize(&lit.atom, &[], eval, &mut budget);
+                    match ctx.evaluator.truth_value(&atom) {
+                        Some(value) if value == lit.sign => return unsound("a literal evaluates to true"),
+                        Some(_) => {}
+                        None => evaluated.push(Literal::new(lit.sign, atom)),
+                    }
+                }
+                evaluated
+            }
+        };
+    }
+
+    let mut replayed: Vec<Literal> = Vec::new();
+    for lit in literals {
+        if !replayed.iter().any(|seen| same_literal(seen, &lit)) {
+            replayed.push(lit);
+        }
+    }
+    if subsumes(&Clause::new(replayed), clause) {
+        Ok(())
+    } else {
+        unsound("the replayed inference does not subsume the stored clause")
+    }
+}
+
+/// Resolve renamed-apart `copies` on `clashes` with a single unifier.
+fn resolve(copies: &[Vec<Literal>], clashes: &[Clash]) -> Result<Vec<Literal>, StepError> {
+    let literal = |pos: crate::data::LiteralPos| -> Result<&Literal, StepError> {
+        match copies.get(pos.parent).and_then(|lits| lits.get(pos.literal)) {
+            Some(lit) => Ok(lit),
+            None => unsound(format!("no literal {} in parent {}", pos.literal + 1, pos.parent + 1)),
+        }
+    };
+    if clashes.is_empty() {
+        return unsound("resolution without clashing literals");
+    }
+
+    let mut unifier = Unifier::new();
+    let mut used = HashSet::new();
+    for clash in clashes {
+        let (left, right) = (literal(clash.left)?, literal(clash.right)?);
+        if left.sign == right.sign {
+            return unsound("clashing literals have the same sign");
+        }
+        if !used.insert(clash.left) || !used.insert(clash.right) {
+            return unsound("a literal is resolved twice");
+        }
+        if unifier.unify(&left.atom, &right.atom).is_err() {
+            return unsound("clashing atoms do not unify");
+        }
+    }
+    // Every parent after the first must take part in a clash
+    for parent in 1..copies.len() {
+        if !used.iter().any(|pos| pos.parent == parent) {
+            return unsound(format!("parent {} is not resolved", parent + 1));
+        }
+    }
+
+    let subst = unifier.into_substitution();
+    let mut result = Vec::new();
+    for (parent, lits) in copies.iter().enumerate() {
+        for (index, lit) in lits.iter().enumerate() {
+            if !used.contains(&crate::data::LiteralPos::new(parent, index)) {
+                result.push(subst.apply_to_literal(lit));
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn paramodulate(
+    from: &[Literal],
+    from_literal: usize,
+    from_right: bool,
+    into: &[Literal],
+    into_literal: usize,
+    position: &[usize],
+    eq_symbol: Option<SymbolId>,
+) -> Result<Vec<Literal>, StepError> {
+    let equality = match from.get(from_literal) {
+        Some(lit) if lit.sign => lit,
+        _ => return unsound("paramodulation from a literal that is not a positive equality"),
+    };
+    let (lhs, rhs) = match equality_sides(&equality.atom, eq_symbol) {
+        Some(sides) => sides,
+        None => return unsound("paramodulation from a literal that is not a positive equality"),
+    };
+    let (pattern, replacement) = if from_right { (rhs, lhs) } else { (lhs, rhs) };
+
+    let target = match into.get(into_literal) {
+        Some(lit) => lit,
+        None => return unsound("paramodulation into a missing literal"),
+    };
+    let subterm = match subterm_at(&target.atom, position) {
+        Some(term) => term,
+        None => return unsound("paramodulation into a missing position"),
+    };
+    let mut unifier = Unifier::new();
+    if unifier.unify(subterm, pattern).is_err() {
+        return unsound("the equality side does not unify with the subterm");
+    }
+    let subst = unifier.into_substitution();
+    let rewritten = replace_at(&target.atom, position, replacement);
+
+    let mut result = Vec::new();
+    for (index, lit) in from.iter().enumerate() {
+        if index != from_literal {
+            result.push(subst.apply_to_literal(lit));
+        }
+    }
+    for (index, lit) in into.iter().enumerate() {
+        if index == into_literal {
+            result.push(Literal::new(lit.sign, subst.apply(&rewritten)));
+        } else {
+            result.push(subst.apply_to_literal(lit));
+        }
+    }
+    Ok(result)
+}
+
+fn equality_resolve(literals: Vec<Literal>, literal: usize, eq_symbol: Option<SymbolId>) -> Result<Vec<Literal>, StepError> {
+    let (lhs, rhs) = match literals.get(literal) {
+        Some(lit) if !lit.sign => match equality_sides(&lit.atom, eq_symbol) {
+            Some(sides) => sides,
+            None => return unsound("equality resolution on a literal that is not a disequality"),
+        },
+        _ => return unsound("equality resolution on a literal that is not a disequality"),
+    };
+    let mut unifier = Unifier::new();
+    if unifier.unify(lhs, rhs).is_err() {
+        return unsound("the sides of the disequality do not unify");
+    }
+    let subst = unifier.into_substitution();
+    Ok(literals
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != literal)
+        .map(|(_, lit)| subst.apply_to_literal(lit))
+        .collect())
+}
+
+/// Unify the chosen sides of two positive equalities and turn the first
+/// into the disequality of the remaining sides.
+fn equality_factor(
+    literals: Vec<Literal>,
+    (literal, right): (usize, bool),
+    (other, other_right): (usize, bool),
+    eq_symbol: Option<SymbolId>,
+) -> Result<Vec<Literal>, StepError> {
+    let sides = |index: usize| match literals.get(index) {
+        Some(lit) if lit.sign => equality_sides(&lit.atom, eq_symbol),
+        _ => None,
+    };
+    let (Some((lhs, rhs)), Some((other_lhs, other_rhs))) = (sides(literal), sides(other)) else {
+        return unsound("equality factoring of a literal that is not a positive equality");
+    };
+    if literal == other {
+        return unsound("equality factoring of a literal with itself");
+    }
+    let (side, rest) = if right { (rhs, lhs) } else { (lhs, rhs) };
+    let (other_side, other_rest) = if other_right { (other_rhs, other_lhs) } else { (other_lhs, other_rhs) };
+    let mut unifier = Unifier::new();
+    if unifier.unify(side, other_side).is_err() {
+        return unsound("the factored sides do not unify");
+    }
+    let subst = unifier.into_substitution();
+    let Term::Application { symbol: eq, .. } = &literals[literal].atom else {
+        return unsound("equality factoring of a variable");
+    };
+    let disequality = Literal::new(false, Term::application(*eq, vec![subst.apply(rest), subst.apply(other_rest)]));
+    Ok(literals
+        .iter()
+        .enumerate()
+        .map(|(index, lit)| if index == literal { disequality.clone() } else { subst.apply_to_literal(lit) })
+        .collect())
+}
+
+fn factor(literals: Vec<Literal>, kept: usize, merged: usize) -> Result<Vec<Literal>, StepError> {
+    let (Some(a), Some(b)) = (literals.get(kept), literals.get(merged)) else {
+        return unsound("factoring a missing literal");
+    };
+    if kept == merged || a.sign != b.sign {
+        return unsound("factored literals differ in sign");
+    }
+    let mut unifier = Unifier::new();
+    if unifier.unify(&a.atom, &b.atom).is_err() {
+        return unsound("factored literals do not unify");
+    }
+    let subst = unifier.into_substitution();
+    Ok(literals
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != merged)
+        .map(|(_, lit)| subst.apply_to_literal(lit))
+        .collect())
+}
+
+/// Resolve away the first literal complementary to `unit`.
+fn unit_delete(literals: Vec<Literal>, unit: &[Literal], id: ClauseId) -> Result<Vec<Literal>, StepError> {
+    let [unit_lit] = unit else {
+        return unsound(format!("unit_del with clause {}, which is not a unit", id.0));
+    };
+    for (index, lit) in literals.iter().enumerate() {
+        if lit.sign == unit_lit.sign {
+            continue;
+        }
+        let mut unifier = Unifier::new();
+        if unifier.unify(&lit.atom, &unit_lit.atom).is_ok() {
+            let subst = unifier.into_substitution();
+            return Ok(literals
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, lit)| subst.apply_to_literal(lit))
+                .collect());
+        }
+    }
+    unsound(format!("unit {} deletes no literal", id.0))
+}
+
+/// The sides of a positive unit equality.
+fn equation(unit: &[Literal], eq_symbol: Option<SymbolId>) -> Option<(Term, Term)> {
+    match unit {
+        [lit] if lit.sign => equality_sides(&lit.atom, eq_symbol).map(|(l, r)| (l.clone(), r.clone())),
+        _ => None,
+    }
+}
+
+/// The rule of a unit equality or, with an evaluator, of a conditional
+/// demodulator `condition -> (lhs = rhs)`.
+fn rewrite_rule(unit: &[Literal], eq_symbol: Option<SymbolId>, eval: Option<EvalContext>) -> Option<Rule> {
+    if let (Some(ctx), [lit]) = (eval, unit)
+        && let Term::Application { symbol, args } = &lit.atom
+        && lit.sign
+        && args.len() == 2
+        && ctx.symbols.get(*symbol).is_some_and(|symbol| symbol.name == "->")
+    {
+        let (lhs, rhs) = equality_sides(&args[1], eq_symbol)?;
+        return Some((lhs.clone(), rhs.clone(), Some(args[0].clone())));
+    }
+    equation(unit, eq_symbol).map(|(lhs, rhs)| (lhs, rhs, None))
+}
+
+pub(crate) fn equality_sides(atom: &Term, eq_symbol: Option<SymbolId>) -> Option<(&Term, &Term)> {
+    match atom {
+        Term::Application { symbol, args } if args.len() == 2 && eq_symbol.is_none_or(|eq| eq == *symbol) => {
+            Some((&args[0], &args[1]))
+        }
+        _ => None,
+    }
+}
+
+fn is_trivial_disequality(lit: &Literal, eq: SymbolId) -> bool {
+    !lit.sign && matches!(&lit.atom, Term::Application { symbol, args } if *symbol == eq && args.len() == 2 && args[0] == args[1])
+}
+
+/// Rewrite innermost first, evaluating built-ins with `eval` before
+/// rewriting, using the first rule that matches at each subterm and
+/// rewriting its result again.
+fn normalize(term: &Term, rules: &[Rule], eval: Option<EvalContext>, budget: &mut usize) -> Term {
+    let term = match term {
+        Term::Variable { .. } => term.clone(),
+        Term::Application { symbol, args } => Term::Application {
+            symbol: *symbol,
+            args: args.iter().map(|arg| normalize(arg, rules, eval, budget)).collect(),
+        },
+    };
+    if let Some(ctx) = eval
+        && let Some(value) = ctx.evaluator.evaluate(&term, ctx.symbols)
+        && *budget > 0
+    {
+        *budget -= 1;
+        return normalize(&value, rules, eval, budget);
+    }
+    for (lhs, rhs, condition) in rules {
+        let mut bindings = Substitution::new();
+        if !matches(lhs, &term, &mut bindings) {
+            continue;
+        }
+        if let Some(condition) = condition {
+            let Some(ctx) = eval else {
+                continue;
+            };
+            let value = normalize(&bindings.apply(condition), rules, eval, budget);
+            if ctx.evaluator.truth_value(&value) != Some(true) {
+                continue;
+            }
+        }
+        let rewritten = bindings.apply(rhs);
+        if rewritten == term || *budget == 0 {
+            return term;
+        }
+        *budget -= 1;
+        return normalize(&rewritten, rules, eval, budget);
+    }
+    term
+}
+
+/// One-way matching of `pattern` onto `term`.
+pub(crate) fn matches(pattern: &Term, term: &Term, bindings: &mut Substitution) -> bool {
+    match (pattern, term) {
+        (Term::Variable { id, .. }, _) => match bindings.lookup(*id) {
+            Some(bound) => bound == term,
+            None => {
+                bindings.bind(*id, term.clone());
+                true
+            }
+        },
+        (Term::Application { symbol: s1, args: a1 }, Term::Application { symbol: s2, args: a2 }) => {
+            s1 == s2 && a1.len() == a2.len() && a1.iter().zip(a2).all(|(p, t)| matches(p, t, bindings))
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn subterm_at<'t>(term: &'t Term, position: &[usize]) -> Option<&'t Term> {
+    match position.split_first() {
+        None => Some(term),
+        Some((index, rest)) => match term {
+            Term::Application { args, .. } => subterm_at(args.get(*index)?, rest),
+            Term::Variable { .. } => None,
+        },
+    }
+}
+
+pub(crate) fn replace_at(term: &Term, position: &[usize], replacement: &Term) -> Term {
+    match (position.split_first(), term) {
+        (None, _) => replacement.clone(),
+        (Some((index, rest)), Term::Application { symbol, args }) => Term::Application {
+            symbol: *symbol,
+            args: args
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| if i == *index { replace_at(arg, rest, replacement) } else { arg.clone() })
+                .collect(),
+        },
+        (Some(_), Term::Variable { .. }) => term.clone(),
+    }
+}
+
+fn same_literal(a: &Literal, b: &Literal) -> bool {
+    a.sign == b.sign && a.atom == b.atom
+}
+
+/// Gives every clause it copies variables no other copy uses.
+#[derive(Default)]
+struct Renamer {
+    next: u16,
+}
+
+impl Renamer {
+    fn fresh(&mut self, clause: &Clause) -> Vec<Literal> {
+        let mut map = HashMap::new();
+        clause
+            .literals
+            .iter()
+            .map(|lit| Literal::new(lit.sign, self.rename(&lit.atom, &mut map)))
+            .collect()
+    }
+
+    fn rename(&mut self, term: &Term, map: &mut HashMap<VariableId, VariableId>) -> Term {
+        match term {
+            Term::Variable { id, symbol } => {
+                let fresh = *map.entry(*id).or_insert_with(|| {
+                    let fresh = VariableId::new(self.next);
+                    self.next += 1;
+                    fresh
+                });
+                Term::Variable { id: fresh, symbol: *symbol }
+            }
+            Term::Application { symbol, args } => Term::Application {
+                symbol: *symbol,
+                args: args.iter().map(|arg| self.rename(arg, map)).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_proof, check_proof_with_evaluator, ProofCheckError};
+    use crate::data::{
+        Clause, ClauseArena, ClauseId, Inference, InferenceRule, Justification, Literal, Simplification, SymbolKind,
+        SymbolTable, Term, VariableId,
+    };
+    use crate::inference::{EvalContext, Evaluator, Proof};
+
+    fn setup() -> (SymbolTable, ClauseArena) {
+        (SymbolTable::new(), ClauseArena::new())
+    }
+
+    fn pred(table: &SymbolTable, name: &str, arg: Term) -> Term {
+        Term::application(table.intern(name, 1, SymbolKind::Predicate), vec![arg])
+    }
+
+    fn constant(table: &SymbolTable, name: &str) -> Term {
+        Term::application(table.intern(name, 0, SymbolKind::Constant), vec![])
+    }
+
+    fn x() -> Term {
+        Term::variable(VariableId::new(0))
+    }
+
+    fn derived(literals: Vec<Literal>, parents: &[ClauseId], justification: Justification) -> Clause {
+        let mut clause = Clause::new(literals)
+            .with_rule(InferenceRule::BinaryRes)
+            .with_justification(justification);
+        for parent in parents {
+            clause.add_parent(*parent);
+        }
+        clause
+    }
+
+    #[test]
+    fn accepts_resolution_with_unit_deletion() {
+        let (table, mut arena) = setup();
+        let a = constant(&table, "a");
+        // 1: P(x) | Q(x) | R(x)   2: -P(a)   3: -Q(y)
+        let c1 = arena.insert(Clause::new(vec![
+            Literal::new(true, pred(&table, "P", x())),
+            Literal::new(true, pred(&table, "Q", x())),
+            Literal::new(true, pred(&table, "R", x())),
+        ]));
+        let c2 = arena.insert(Clause::new(vec![Literal::new(false, pred(&table, "P", a.clone()))]));
+        let c3 = arena.insert(Clause::new(vec![Literal::new(
+            false,
+            pred(&table, "Q", Term::variable(VariableId::new(1))),
+        )]));
+        // 4: R(a) by binary resolution of 1 and 2, then unit deletion with 3
+        let mut justification = Justification::binary(c1, 0, c2, 0);
+        justification.push(Simplification::UnitDel(vec![c3]));
+        let c4 = arena.insert(derived(
+            vec![Literal::new(true, pred(&table, "R", a))],
+            &[c1, c2, c3],
+            justification,
+        ));
+
+        let proof = Proof::from_arena(&arena, c4).unwrap();
+        assert_eq!(check_proof(&proof, None), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_resolvent_that_does_not_follow() {
+        let (table, mut arena) = setup();
+        let a = constant(&table, "a");
+        let b = constant(&table, "b");
+        // 1: P(a) | Q(a)   2: -P(b)   3: Q(a) claimed from them
+        let c1 = arena.insert(Clause::new(vec![
+            Literal::new(true, pred(&table, "P", a.clone())),
+            Literal::new(true, pred(&table, "Q", a.clone())),
+        ]));
+        let c2 = arena.insert(Clause::new(vec![Literal::new(false, pred(&table, "P", b))]));
+        let c3 = arena.insert(derived(
+            vec![Literal::new(true, pred(&table, "Q", a))],
+            &[c1, c2],
+            Justification::binary(c1, 0, c2, 0),
+        ));
+
+        let proof = Proof::from_arena(&arena, c3).unwrap();
+        assert!(matches!(check_proof(&proof, None), Err(ProofCheckError::Unsound { step, .. }) if step == c3));
+    }
+
+    #[test]
+    fn rejects_a_derived_clause_without_justification() {
+        let (table, mut arena) = setup();
+        let c1 = arena.insert(Clause::new(vec![Literal::new(true, pred(&table, "P", x()))]));
+        let mut clause = Clause::new(vec![]).with_rule(InferenceRule::BinaryRes);
+        clause.add_parent(c1);
+        let c2 = arena.insert(clause);
+
+        let proof = Proof::from_arena(&arena, c2).unwrap();
+        assert_eq!(check_proof(&proof, None), Err(ProofCheckError::MissingJustification(c2)));
+    }
+
+    #[test]
+    fn replays_equality_resolution_and_factoring() {
+        let (table, mut arena) = setup();
+        let eq = table.intern("=", 2, SymbolKind::Function);
+        let equal = |s: Term, t: Term| Term::application(eq, vec![s, t]);
+        let fun = |name: &str, arg: Term| Term::application(table.intern(name, 1, SymbolKind::Function), vec![arg]);
+        let (a, b, c) = (constant(&table, "a"), constant(&table, "b"), constant(&table, "c"));
+
+        // 1: f(x) = a | f(b) = c   2: a != c | f(b) = c by equality factoring
+        let c1 = arena.insert(Clause::new(vec![
+            Literal::new(true, equal(fun("f", x()), a.clone())),
+            Literal::new(true, equal(fun("f", b.clone()), c.clone())),
+        ]));
+        let factoring = |other_right| {
+            Justification::new(Inference::EqualityFactoring { parent: c1, literal: 0, right: false, other: 1, other_right })
+        };
+        let c2 = arena.insert(derived(
+            vec![Literal::new(false, equal(a.clone(), c.clone())), Literal::new(true, equal(fun("f", b.clone()), c.clone()))],
+            &[c1],
+            factoring(false),
+        ));
+        let proof = Proof::from_arena(&arena, c2).unwrap();
+        assert_eq!(check_proof(&proof, Some(eq)), Ok(()));
+        // f(x) and c do not unify
+        let c3 = arena.insert(derived(vec![Literal::new(false, equal(a.clone(), fun("f", b)))], &[c1], factoring(true)));
+        let proof = Proof::from_arena(&arena, c3).unwrap();
+        assert!(matches!(check_proof(&proof, Some(eq)), Err(ProofCheckError::Unsound { step, .. }) if step == c3));
+
+        // 4: g(x) != g(a) | P(x)   5: P(a) by equality resolution
+        let c4 = arena.insert(Clause::new(vec![
+            Literal::new(false, equal(fun("g", x()), fun("g", a.clone()))),
+            Literal::new(true, pred(&table, "P", x())),
+        ]));
+        let resolution = |literal| Justification::new(Inference::EqualityResolution { parent: c4, literal });
+        let c5 = arena.insert(derived(vec![Literal::new(true, pred(&table, "P", a.clone()))], &[c4], resolution(0)));
+        let proof = Proof::from_arena(&arena, c5).unwrap();
+        assert_eq!(check_proof(&proof, Some(eq)), Ok(()));
+        let c6 = arena.insert(derived(vec![Literal::new(true, pred(&table, "P", a))], &[c4], resolution(1)));
+        let proof = Proof::from_arena(&arena, c6).unwrap();
+        assert!(matches!(check_proof(&proof, Some(eq)), Err(ProofCheckError::Unsound { step, .. }) if step == c6));
+    }
+
+    #[test]
+    fn replays_evaluation_with_the_evaluator() {
+        let (table, mut arena) = setup();
+        let builtin = |name: &str, args: Vec<Term>| Term::application(table.intern(name, 2, SymbolKind::Function), args);
+        let (zero, one, two, three) =
+            (constant(&table, "0"), constant(&table, "1"), constant(&table, "2"), constant(&table, "3"));
+        // 1: -P(x) | -$LT(x, 3) | P($SUM(x, 1))   2: P(0)
+        let c1 = arena.insert(Clause::new(vec![
+            Literal::new(false, pred(&table, "P", x())),
+            Literal::new(false, builtin("$LT", vec![x(), three])),
+            Literal::new(true, pred(&table, "P", builtin("$SUM", vec![x(), one.clone()]))),
+        ]));
+        let c2 = arena.insert(Clause::new(vec![Literal::new(true, pred(&table, "P", zero))]));
+        let evaluated = || {
+            let mut justification = Justification::binary(c1, 0, c2, 0);
+            justification.push(Simplification::Eval);
+            justification
+        };
+        // 3: P(1), with 0 < 3 evaluated to $T and dropped
+        let c3 = arena.insert(derived(vec![Literal::new(true, pred(&table, "P", one))], &[c1, c2], evaluated()));
+        // 4: P(2) does not follow by evaluation
+        let c4 = arena.insert(derived(vec![Literal::new(true, pred(&table, "P", two))], &[c1, c2], evaluated()));
+
+        let evaluator = Evaluator::new(&table, &[]).expect("evaluator");
+        let eval = EvalContext { evaluator: &evaluator, symbols: &table };
+        let proof = Proof::from_arena(&arena, c3).unwrap();
+        assert_eq!(check_proof_with_evaluator(&proof, None, Some(eval)), Ok(()));
+        assert!(matches!(check_proof(&proof, None), Err(ProofCheckError::Unsound { step, .. }) if step == c3));
+        let proof = Proof::from_arena(&arena, c4).unwrap();
+        assert!(matches!(
+            check_proof_with_evaluator(&proof, None, Some(eval)),
+            Err(ProofCheckError::Unsound { step, .. }) if step == c4
+        ));
+    }
+}
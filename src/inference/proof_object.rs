@@ -0,0 +1,863 @@
+//! Ivy-style proof objects.
+//!
+//! Otter's `set(build_proof_object)` prints a proof as a list of steps in
+//! which every inference is elementary and explicit: `instantiate` applies a
+//! substitution, `resolve` and `paramod` act on literals and terms that are
+//! already identical, and `merge` drops a duplicate literal.  External
+//! checkers such as Ivy verify each step without doing any unification.
+//!
+//! [`ProofObject::from_proof`] expands the [`Justification`] of every proof
+//! clause into such steps: resolution and paramodulation become
+//! instantiations followed by the ground-level rule, factoring becomes an
+//! instantiation and a merge, each demodulation rewrite becomes an instance
+//! of the demodulator and a paramodulation, and `xx_res` and equality
+//! resolution resolve with an instance of `x = x`.  Equality factoring
+//! rewrites with the tautology `t != t' | t = t'`, a propositional
+//! consequence of `t = t`, and flips equations by paramodulating into an
+//! instance of `x = x`.
+
+use super::proof_check::{equality_sides, matches, replace_at, subterm_at};
+use crate::data::{
+    Clash, Clause, ClauseId, Inference, Justification, Literal, LiteralPos, Simplification, SymbolId, SymbolTable,
+    Term, VariableId,
+};
+use crate::inference::{check_proof, Proof, ProofCheckError, Substitution, Unifier};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Rewrites allowed while expanding one demodulation.
+const MAX_REWRITES: usize = 10_000;
+
+/// Simultaneous substitution of terms for variables.
+type Bindings = HashMap<VariableId, Term>;
+
+/// How a proof object step follows from earlier steps.  Literal indices are
+/// 0-based positions in the parent step; term positions are 0-based
+/// argument paths from the atom.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectRule {
+    Input,
+    Instantiate { parent: usize, bindings: Vec<(VariableId, Term)> },
+    Resolve { left: usize, left_literal: usize, right: usize, right_literal: usize },
+    Paramod {
+        from: usize,
+        from_literal: usize,
+        /// The right side of the equation is the term being replaced
+        from_right: bool,
+        into: usize,
+        into_literal: usize,
+        position: Vec<usize>,
+    },
+    Merge { parent: usize, literal: usize },
+    Propositional { parent: usize },
+}
+
+/// One step of a proof object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectStep {
+    /// 1-based step number
+    pub id: usize,
+    pub rule: ObjectRule,
+    pub literals: Vec<Literal>,
+    /// The proof clause this step establishes, if any
+    pub clause_id: Option<ClauseId>,
+}
+
+/// A proof in which every step is elementary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProofObject {
+    steps: Vec<ObjectStep>,
+}
+
+impl ProofObject {
+    /// Expand `proof` into elementary steps.  The proof is checked first, so
+    /// an unsound step is reported instead of expanded.
+    pub fn from_proof(proof: &Proof, eq_symbol: Option<SymbolId>) -> Result<Self, ProofCheckError> {
+        check_proof(proof, eq_symbol)?;
+        let mut builder = Builder { eq_symbol, ..Builder::default() };
+        for step in proof.steps() {
+            let id = step.id();
+            let object_id = match &step.clause.justification {
+                None => builder.push(ObjectRule::Input, step.clause.literals.clone()),
+                Some(justification) => builder
+                    .expand(&step.clause, justification)
+                    .map_err(|reason| ProofCheckError::Unsound { step: id, reason })?,
+            };
+            builder.steps[object_id - 1].clause_id = Some(id);
+            builder.ids.insert(id, object_id);
+        }
+        Ok(Self { steps: builder.steps })
+    }
+
+    pub fn steps(&self) -> &[ObjectStep] {
+        &self.steps
+    }
+
+    /// Print the object as Otter does, between `;; BEGINNING OF PROOF OBJECT`
+    /// and `;; END OF PROOF OBJECT`.
+    pub fn to_ivy(&self, symbols: &SymbolTable) -> String {
+        let mut out = String::new();
+        writeln!(out, ";; BEGINNING OF PROOF OBJECT").unwrap();
+        writeln!(out, "(").unwrap();
+        for step in &self.steps {
+            let label = step.clause_id.map_or_else(|| "NIL".to_string(), |id| format!("({})", id.0));
+            writeln!(
+                out,
+                "({} {} {} {})",
+                step.id,
+                self.ivy_rule(&step.rule, symbols),
+                ivy_clause(&step.literals, symbols),
+                label
+            )
+            .unwrap();
+        }
+        writeln!(out, ")").unwrap();
+        writeln!(out, ";; END OF PROOF OBJECT").unwrap();
+        out
+    }
+
+    fn literals(&self, id: usize) -> &[Literal] {
+        &self.steps[id - 1].literals
+    }
+
+    fn ivy_rule(&self, rule: &ObjectRule, symbols: &SymbolTable) -> String {
+        match rule {
+            ObjectRule::Input => "(input)".to_string(),
+            ObjectRule::Instantiate { parent, bindings } => {
+                let pairs: Vec<String> =
+                    bindings.iter().map(|(var, term)| format!("(v{} . {})", var.as_u16(), ivy_term(term, symbols))).collect();
+                format!("(instantiate {} ({}))", parent, pairs.join(" "))
+            }
+            ObjectRule::Resolve { left, left_literal, right, right_literal } => format!(
+                "(resolve {} {} {} {})",
+                left,
+                ivy_path(&literal_path(self.literals(*left), *left_literal, &[])),
+                right,
+                ivy_path(&literal_path(self.literals(*right), *right_literal, &[]))
+            ),
+            ObjectRule::Paramod { from, from_literal, from_right, into, into_literal, position } => format!(
+                "(paramod {} {} {} {})",
+                from,
+                ivy_path(&literal_path(self.literals(*from), *from_literal, &[usize::from(*from_right)])),
+                into,
+                ivy_path(&literal_path(self.literals(*into), *into_literal, position))
+            ),
+            ObjectRule::Merge { parent, literal } => {
+                format!("(merge {} {})", parent, ivy_path(&literal_path(self.literals(*parent), *literal, &[])))
+            }
+            ObjectRule::Propositional { parent } => format!("(propositional {})", parent),
+        }
+    }
+}
+
+/// The Ivy position of term `position` in literal `index` of a clause
+/// printed as right-nested binary `or`s: 1-based, stepping through `not`
+/// to reach a term of a negative literal.
+fn literal_path(literals: &[Literal], index: usize, position: &[usize]) -> Vec<usize> {
+    let last = literals.len() - 1;
+    let mut path = vec![2; index.min(last)];
+    if index < last {
+        path.push(1);
+    }
+    if !position.is_empty() && !literals[index].sign {
+        path.push(1);
+    }
+    path.extend(position.iter().map(|arg| arg + 1));
+    path
+}
+
+fn ivy_path(path: &[usize]) -> String {
+    let parts: Vec<String> = path.iter().map(usize::to_string).collect();
+    format!("({})", parts.join(" "))
+}
+
+fn ivy_clause(literals: &[Literal], symbols: &SymbolTable) -> String {
+    match literals.split_first() {
+        None => "false".to_string(),
+        Some((first, [])) => ivy_literal(first, symbols),
+        Some((first, rest)) => format!("(or {} {})", ivy_literal(first, symbols), ivy_clause(rest, symbols)),
+    }
+}
+
+fn ivy_literal(literal: &Literal, symbols: &SymbolTable) -> String {
+    let atom = ivy_term(&literal.atom, symbols);
+    if literal.sign { atom } else { format!("(not {})", atom) }
+}
+
+fn ivy_term(term: &Term, symbols: &SymbolTable) -> String {
+    match term {
+        Term::Variable { id, .. } => format!("v{}", id.as_u16()),
+        Term::Application { symbol, args } => {
+            let name = symbols.get(*symbol).map_or_else(|| format!("s{}", symbol.as_raw()), |sym| sym.name);
+            let mut out = format!("({}", name);
+            for arg in args {
+                out.push(' ');
+                out.push_str(&ivy_term(arg, symbols));
+            }
+            out.push(')');
+            out
+        }
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    steps: Vec<ObjectStep>,
+    ids: HashMap<ClauseId, usize>,
+    reflexivity: Option<usize>,
+    eq_symbol: Option<SymbolId>,
+    next_variable: u16,
+}
+
+impl Builder {
+    fn push(&mut self, rule: ObjectRule, literals: Vec<Literal>) -> usize {
+        let id = self.steps.len() + 1;
+        self.steps.push(ObjectStep { id, rule, literals, clause_id: None });
+        id
+    }
+
+    fn literals(&self, id: usize) -> Vec<Literal> {
+        self.steps[id - 1].literals.clone()
+    }
+
+    fn object(&self, clause: ClauseId) -> Result<usize, String> {
+        self.ids.get(&clause).copied().ok_or_else(|| format!("clause {} is not in the proof", clause.0))
+    }
+
+    /// Instantiate step `parent` with `bindings`, or return `parent` when
+    /// they change nothing.
+    fn instantiate(&mut self, parent: usize, bindings: &Bindings) -> usize {
+        let literals = self.literals(parent);
+        let mut used: Vec<(VariableId, Term)> = variables(&literals)
+            .into_iter()
+            .filter_map(|var| match bindings.get(&var) {
+                Some(term) if !matches!(term, Term::Variable { id, .. } if *id == var) => Some((var, term.clone())),
+                _ => None,
+            })
+            .collect();
+        if used.is_empty() {
+            return parent;
+        }
+        used.sort_by_key(|(var, _)| var.as_u16());
+        let instance = literals.iter().map(|lit| substitute_literal(lit, bindings)).collect();
+        self.push(ObjectRule::Instantiate { parent, bindings: used }, instance)
+    }
+
+    fn resolve(&mut self, left: usize, left_literal: usize, right: usize, right_literal: usize) -> usize {
+        let mut literals = without(&self.literals(left), left_literal);
+        literals.extend(without(&self.literals(right), right_literal));
+        self.push(ObjectRule::Resolve { left, left_literal, right, right_literal }, literals)
+    }
+
+    /// Fresh variables for every variable of step `id`, as bindings from the
+    /// step's variables.
+    fn rename_apart(&mut self, id: usize) -> Bindings {
+        let mut renaming = Bindings::new();
+        for var in variables(&self.literals(id)) {
+            renaming.insert(var, Term::variable(VariableId::new(self.next_variable)));
+            self.next_variable += 1;
+        }
+        renaming
+    }
+
+    /// Compose a renaming with a unifier into bindings for the original
+    /// variables.
+    fn through(renaming: &Bindings, unifier: &Substitution) -> Bindings {
+        renaming.iter().map(|(var, term)| (*var, unifier.apply(term))).collect()
+    }
+
+    fn expand(&mut self, clause: &Clause, justification: &Justification) -> Result<usize, String> {
+        // Keep fresh variables clear of every variable printed so far
+        self.next_variable = self
+            .steps
+            .iter()
+            .flat_map(|step| variables(&step.literals))
+            .chain(variables(&clause.literals))
+            .map(|var| var.as_u16() + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut current = match &justification.inference {
+            Inference::Resolution { parents, clashes } => self.expand_resolution(parents, clashes)?,
+            Inference::Paramodulation { from, from_literal, from_right, into, into_literal, position } => {
+                let (from, into) = (self.object(*from)?, self.object(*into)?);
+                self.expand_paramodulation(from, *from_literal, *from_right, into, *into_literal, position)?
+            }
+            Inference::EqualityResolution { parent, literal } => {
+                self.expand_equality_resolution(self.object(*parent)?, *literal)?
+            }
+            Inference::EqualityFactoring { parent, literal, right, other, other_right } => {
+                self.expand_equality_factoring(self.object(*parent)?, *literal, *right, *other, *other_right)?
+            }
+            Inference::Copy { parent } => self.object(*parent)?,
+        };
+
+        for simplification in &justification.simplifications {
+            current = match simplification {
+                Simplification::Factor { kept, merged } => {
+                    let literals = self.literals(current);
+                    let mut unifier = Unifier::new();
+                    unifier
+                        .unify(&literals[*kept].atom, &literals[*merged].atom)
+                        .map_err(|_| "factored literals do not unify".to_string())?;
+                    let bindings = Self::through(&identity(&literals), &unifier.into_substitution());
+                    let instance = self.instantiate(current, &bindings);
+                    let merged_literals = without(&self.literals(instance), *merged);
+                    self.push(ObjectRule::Merge { parent: instance, literal: *merged }, merged_literals)
+                }
+                Simplification::Demod(steps) => self.expand_demodulation(current, steps)?,
+                Simplification::UnitDel(units) => {
+                    for unit in units {
+                        current = self.expand_unit_deletion(current, self.object(*unit)?)?;
+                    }
+                    current
+                }
+                Simplification::XxRes => self.expand_xx_res(current)?,
+                Simplification::Eval => return Err("built-in evaluation has no proof object rule".to_string()),
+            };
+        }
+        self.conclude(current, &clause.literals)
+    }
+
+    fn expand_resolution(&mut self, parents: &[ClauseId], clashes: &[Clash]) -> Result<usize, String> {
+        let objects = parents.iter().map(|id| self.object(*id)).collect::<Result<Vec<_>, _>>()?;
+        let renamings: Vec<Bindings> = objects.iter().map(|id| self.rename_apart(*id)).collect();
+        let copies: Vec<Vec<Literal>> = objects
+            .iter()
+            .zip(&renamings)
+            .map(|(id, renaming)| self.literals(*id).iter().map(|lit| substitute_literal(lit, renaming)).collect())
+            .collect();
+        let literal = |pos: LiteralPos| {
+            copies.get(pos.parent).and_then(|lits| lits.get(pos.literal)).ok_or("missing clash literal".to_string())
+        };
+        let mut unifier = Unifier::new();
+        for clash in clashes {
+            unifier
+                .unify(&literal(clash.left)?.atom, &literal(clash.right)?.atom)
+                .map_err(|_| "clashing atoms do not unify".to_string())?;
+        }
+        let unifier = unifier.into_substitution();
+        let instances: Vec<usize> = objects
+            .iter()
+            .zip(&renamings)
+            .map(|(id, renaming)| self.instantiate(*id, &Self::through(renaming, &unifier)))
+            .collect();
+
+        // Resolve the instances one satellite at a time, tracking where each
+        // literal of the accumulated clause came from
+        let mut current = instances[0];
+        let mut origins: Vec<LiteralPos> = (0..copies[0].len()).map(|i| LiteralPos::new(0, i)).collect();
+        let mut joined: HashSet<usize> = HashSet::from([0]);
+        for clash in clashes {
+            let (inside, outside) = match (joined.contains(&clash.left.parent), joined.contains(&clash.right.parent)) {
+                (true, false) => (clash.left, clash.right),
+                (false, true) => (clash.right, clash.left),
+                _ => return Err("a clash does not join a new parent".to_string()),
+            };
+            let position = origins
+                .iter()
+                .position(|origin| *origin == inside)
+                .ok_or("a literal is resolved twice".to_string())?;
+            current = self.resolve(current, position, instances[outside.parent], outside.literal);
+            origins.remove(position);
+            origins.extend(
+                (0..copies[outside.parent].len())
+                    .filter(|i| *i != outside.literal)
+                    .map(|i| LiteralPos::new(outside.parent, i)),
+            );
+            joined.insert(outside.parent);
+        }
+        Ok(current)
+    }
+
+    fn expand_paramodulation(
+        &mut self,
+        from: usize,
+        from_literal: usize,
+        from_right: bool,
+        into: usize,
+        into_literal: usize,
+        position: &[usize],
+    ) -> Result<usize, String> {
+        let from_renaming = self.rename_apart(from);
+        let into_renaming = self.rename_apart(into);
+        let from_copy: Vec<Literal> = self.literals(from).iter().map(|lit| substitute_literal(lit, &from_renaming)).collect();
+        let into_copy: Vec<Literal> = self.literals(into).iter().map(|lit| substitute_literal(lit, &into_renaming)).collect();
+
+        let (lhs, rhs) = from_copy
+            .get(from_literal)
+            .and_then(|lit| equality_sides(&lit.atom, self.eq_symbol))
+            .ok_or("paramodulation from a non-equality".to_string())?;
+        let pattern = if from_right { rhs } else { lhs };
+        let subterm = into_copy
+            .get(into_literal)
+            .and_then(|lit| subterm_at(&lit.atom, position))
+            .ok_or("paramodulation into a missing position".to_string())?;
+        let mut unifier = Unifier::new();
+        unifier.unify(pattern, subterm).map_err(|_| "paramodulation terms do not unify".to_string())?;
+        let unifier = unifier.into_substitution();
+
+        let from_instance = self.instantiate(from, &Self::through(&from_renaming, &unifier));
+        let into_instance = self.instantiate(into, &Self::through(&into_renaming, &unifier));
+        Ok(self.paramod(from_instance, from_literal, from_right, into_instance, into_literal, position))
+    }
+
+    /// Paramodulate between identical terms.
+    fn paramod(
+        &mut self,
+        from: usize,
+        from_literal: usize,
+        from_right: bool,
+        into: usize,
+        into_literal: usize,
+        position: &[usize],
+    ) -> usize {
+        let from_literals = self.literals(from);
+        let (lhs, rhs) = equality_sides(&from_literals[from_literal].atom, None).expect("checked equality");
+        let replacement = if from_right { lhs } else { rhs };
+        let mut literals = without(&from_literals, from_literal);
+        for (index, lit) in self.literals(into).into_iter().enumerate() {
+            if index == into_literal {
+                literals.push(Literal::new(lit.sign, replace_at(&lit.atom, position, replacement)));
+            } else {
+                literals.push(lit);
+            }
+        }
+        self.push(
+            ObjectRule::Paramod { from, from_literal, from_right, into, into_literal, position: position.to_vec() },
+            literals,
+        )
+    }
+
+    fn expand_demodulation(&mut self, mut current: usize, steps: &[(ClauseId, bool)]) -> Result<usize, String> {
+        let mut rules = Vec::new();
+        for (id, flipped) in steps {
+            let object = self.object(*id)?;
+            let renaming = self.rename_apart(object);
+            let literals = self.literals(object);
+            let [unit] = literals.as_slice() else {
+                return Err(format!("demodulator {} is not a unit", id.0));
+            };
+            let (lhs, rhs) = equality_sides(&substitute(&unit.atom, &renaming), self.eq_symbol)
+                .map(|(l, r)| (l.clone(), r.clone()))
+                .ok_or(format!("demodulator {} is not an equality", id.0))?;
+            let (pattern, result) = if *flipped { (rhs, lhs) } else { (lhs, rhs) };
+            rules.push((object, renaming, *flipped, pattern, result));
+        }
+
+        let mut rewrites = Vec::new();
+        let mut budget = MAX_REWRITES;
+        let literals = self.literals(current);
+        for (index, lit) in literals.iter().enumerate() {
+            let rules: Vec<(Term, Term)> = rules.iter().map(|(_, _, _, l, r)| (l.clone(), r.clone())).collect();
+            let mut path = Vec::new();
+            record_rewrites(&lit.atom, &rules, &mut path, &mut budget, &mut |rule, path, matcher| {
+                rewrites.push((index, rule, path.to_vec(), matcher));
+            });
+        }
+
+        for (index, rule, path, matcher) in rewrites {
+            let (object, renaming, flipped, _, _) = &rules[rule];
+            let bindings: Bindings = renaming.iter().map(|(var, term)| (*var, substitute(term, &matcher))).collect();
+            let instance = self.instantiate(*object, &bindings);
+            current = self.paramod(instance, 0, *flipped, current, index, &path);
+        }
+        Ok(current)
+    }
+
+    fn expand_unit_deletion(&mut self, current: usize, unit: usize) -> Result<usize, String> {
+        let renaming = self.rename_apart(unit);
+        let unit_literal = match self.literals(unit).as_slice() {
+            [lit] => substitute_literal(lit, &renaming),
+            _ => return Err("unit deletion with a non-unit".to_string()),
+        };
+        let literals = self.literals(current);
+        for (index, lit) in literals.iter().enumerate() {
+            if lit.sign == unit_literal.sign {
+                continue;
+            }
+            let mut unifier = Unifier::new();
+            if unifier.unify(&lit.atom, &unit_literal.atom).is_ok() {
+                let unifier = unifier.into_substitution();
+                let instance = self.instantiate(current, &Self::through(&identity(&literals), &unifier));
+                let unit_instance = self.instantiate(unit, &Self::through(&renaming, &unifier));
+                return Ok(self.resolve(instance, index, unit_instance, 0));
+            }
+        }
+        Err("the unit deletes no literal".to_string())
+    }
+
+    fn expand_equality_resolution(&mut self, parent: usize, literal: usize) -> Result<usize, String> {
+        let literals = self.literals(parent);
+        let (lhs, rhs) = literals
+            .get(literal)
+            .filter(|lit| !lit.sign)
+            .and_then(|lit| equality_sides(&lit.atom, self.eq_symbol))
+            .ok_or("equality resolution on a literal that is not a disequality".to_string())?;
+        let mut unifier = Unifier::new();
+        unifier.unify(lhs, rhs).map_err(|_| "the sides of the disequality do not unify".to_string())?;
+        let bindings = Self::through(&identity(&literals), &unifier.into_substitution());
+        let instance = self.instantiate(parent, &bindings);
+        let side = equality_sides(&self.literals(instance)[literal].atom, None).expect("checked equality").0.clone();
+        self.resolve_reflexive(instance, literal, side)
+    }
+
+    /// Factor `s = t | s' = t'` into `t != t' | s' = t'`: rewrite `t` to
+    /// `t'` in the instance with the tautology `t != t' | t = t'`, and flip
+    /// the result when the two equalities are oriented differently.
+    fn expand_equality_factoring(
+        &mut self,
+        parent: usize,
+        literal: usize,
+        right: bool,
+        other: usize,
+        other_right: bool,
+    ) -> Result<usize, String> {
+        let eq = self.eq_symbol.ok_or("equality factoring without an equality symbol".to_string())?;
+        let literals = self.literals(parent);
+        let sides = |index: usize| {
+            literals
+                .get(index)
+                .filter(|lit| lit.sign)
+                .and_then(|lit| equality_sides(&lit.atom, self.eq_symbol))
+                .ok_or("equality factoring on a literal that is not an equality".to_string())
+        };
+        let ((lhs, rhs), (other_lhs, other_rhs)) = (sides(literal)?, sides(other)?);
+        let mut unifier = Unifier::new();
+        unifier
+            .unify(if right { rhs } else { lhs }, if other_right { other_rhs } else { other_lhs })
+            .map_err(|_| "the factored sides do not unify".to_string())?;
+        let bindings = Self::through(&identity(&literals), &unifier.into_substitution());
+        let instance = self.instantiate(parent, &bindings);
+
+        let literals = self.literals(instance);
+        let (lhs, rhs) = equality_sides(&literals[literal].atom, None).expect("checked equality");
+        let (other_lhs, other_rhs) = equality_sides(&literals[other].atom, None).expect("checked equality");
+        let (rest, position) = if right { (lhs.clone(), 0) } else { (rhs.clone(), 1) };
+        let other_rest = if other_right { other_lhs.clone() } else { other_rhs.clone() };
+        let equation = Term::application(eq, vec![rest.clone(), other_rest]);
+        let reflexive = self.reflexive(rest)?;
+        let tautology = vec![Literal::new(false, equation.clone()), Literal::new(true, equation)];
+        let tautology = self.push(ObjectRule::Propositional { parent: reflexive }, tautology);
+        let current = self.paramod(tautology, 1, false, instance, literal, &[position]);
+        if right == other_right {
+            return Ok(current);
+        }
+        // The rewritten literal follows the negated equation
+        let lhs = equality_sides(&self.literals(current)[literal + 1].atom, None).expect("checked equality").0.clone();
+        let reflexive = self.reflexive(lhs)?;
+        Ok(self.paramod(current, literal + 1, false, reflexive, 0, &[0]))
+    }
+
+    fn expand_xx_res(&mut self, mut current: usize) -> Result<usize, String> {
+        let eq = self.eq_symbol.ok_or("xx_res without an equality symbol".to_string())?;
+        loop {
+            let literals = self.literals(current);
+            let Some((index, side)) = literals.iter().enumerate().find_map(|(index, lit)| match &lit.atom {
+                Term::Application { symbol, args } if !lit.sign && *symbol == eq && args.len() == 2 && args[0] == args[1] => {
+                    Some((index, args[0].clone()))
+                }
+                _ => None,
+            }) else {
+                return Ok(current);
+            };
+            current = self.resolve_reflexive(current, index, side)?;
+        }
+    }
+
+    /// Resolve the disequality `index` of step `current`, whose sides are
+    /// both `side`, with an instance of `x = x`.
+    fn resolve_reflexive(&mut self, current: usize, index: usize, side: Term) -> Result<usize, String> {
+        let instance = self.reflexive(side)?;
+        Ok(self.resolve(current, index, instance, 0))
+    }
+
+    /// The instance `side = side` of `x = x`.
+    fn reflexive(&mut self, side: Term) -> Result<usize, String> {
+        let eq = self.eq_symbol.ok_or("reflexivity without an equality symbol".to_string())?;
+        let reflexivity = match self.reflexivity {
+            Some(id) => id,
+            None => {
+                let x = Term::variable(VariableId::new(0));
+                let id = self.push(ObjectRule::Input, vec![Literal::new(true, Term::application(eq, vec![x.clone(), x]))]);
+                self.reflexivity = Some(id);
+                id
+            }
+        };
+        Ok(self.instantiate(reflexivity, &Bindings::from([(VariableId::new(0), side)])))
+    }
+
+    /// Turn the replayed clause of step `current` into exactly `stored`.
+    fn conclude(&mut self, mut current: usize, stored: &[Literal]) -> Result<usize, String> {
+        // Merge duplicate literals
+        while let Some(duplicate) = {
+            let literals = self.literals(current);
+            (1..literals.len()).find(|i| literals[..*i].contains(&literals[*i]))
+        } {
+            let literals = without(&self.literals(current), duplicate);
+            current = self.push(ObjectRule::Merge { parent: current, literal: duplicate }, literals);
+        }
+
+        let replayed = self.literals(current);
+        let bindings = match_clause(&replayed, stored).ok_or("the replayed clause does not subsume the stored one")?;
+        current = self.instantiate(current, &bindings);
+        let already_labelled = self.steps[current - 1].clause_id.is_some();
+        if self.literals(current) != stored || already_labelled {
+            current = self.push(ObjectRule::Propositional { parent: current }, stored.to_vec());
+        }
+        Ok(current)
+    }
+}
+
+/// Record the rewrites of the checker's innermost-first strategy, with
+/// the rule used, the position rewritten and the matching substitution.
+fn record_rewrites(
+    term: &Term,
+    rules: &[(Term, Term)],
+    path: &mut Vec<usize>,
+    budget: &mut usize,
+    record: &mut dyn FnMut(usize, &[usize], Bindings),
+) -> Term {
+    let term = match term {
+        Term::Variable { .. } => term.clone(),
+        Term::Application { symbol, args } => {
+            let mut new_args = Vec::with_capacity(args.len());
+            for (index, arg) in args.iter().enumerate() {
+                path.push(index);
+                new_args.push(record_rewrites(arg, rules, path, budget, record));
+                path.pop();
+            }
+            Term::Application { symbol: *symbol, args: new_args }
+        }
+    };
+    for (rule, (lhs, rhs)) in rules.iter().enumerate() {
+        let mut matcher = Substitution::new();
+        if matches(lhs, &term, &mut matcher) {
+            let matcher: Bindings = matcher.iter().map(|(var, term)| (*var, term.clone())).collect();
+            let rewritten = substitute(rhs, &matcher);
+            if rewritten == term || *budget == 0 {
+                return term;
+            }
+            *budget -= 1;
+            record(rule, path, matcher);
+            return record_rewrites(&rewritten, rules, path, budget, record);
+        }
+    }
+    term
+}
+
+/// Bindings that map `from`'s literals injectively onto literals of `to`.
+fn match_clause(from: &[Literal], to: &[Literal]) -> Option<Bindings> {
+    fn search(from: &[Literal], to: &[Literal], used: &mut Vec<bool>, bindings: Substitution) -> Option<Substitution> {
+        let Some((first, rest)) = from.split_first() else {
+            return Some(bindings);
+        };
+        for (index, candidate) in to.iter().enumerate() {
+            if used[index] || candidate.sign != first.sign {
+                continue;
+            }
+            let mut attempt = bindings.clone();
+            if matches(&first.atom, &candidate.atom, &mut attempt) {
+                used[index] = true;
+                if let Some(found) = search(rest, to, used, attempt) {
+                    return Some(found);
+                }
+                used[index] = false;
+            }
+        }
+        None
+    }
+    let found = search(from, to, &mut vec![false; to.len()], Substitution::new())?;
+    Some(found.iter().map(|(var, term)| (*var, term.clone())).collect())
+}
+
+fn identity(literals: &[Literal]) -> Bindings {
+    variables(literals).into_iter().map(|var| (var, Term::variable(var))).collect()
+}
+
+fn without(literals: &[Literal], index: usize) -> Vec<Literal> {
+    literals.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, lit)| lit.clone()).collect()
+}
+
+fn variables(literals: &[Literal]) -> Vec<VariableId> {
+    fn walk(term: &Term, out: &mut Vec<VariableId>) {
+        match term {
+            Term::Variable { id, .. } => {
+                if !out.contains(id) {
+                    out.push(*id);
+                }
+            }
+            Term::Application { args, .. } => args.iter().for_each(|arg| walk(arg, out)),
+        }
+    }
+    let mut out = Vec::new();
+    for lit in literals {
+        walk(&lit.atom, &mut out);
+    }
+    out
+}
+
+fn substitute(term: &Term, bindings: &Bindings) -> Term {
+    match term {
+        Term::Variable { id, .. } => bindings.get(id).cloned().unwrap_or_else(|| term.clone()),
+        Term::Application { symbol, args } => Term::Application {
+            symbol: *symbol,
+            args: args.iter().map(|arg| substitute(arg, bindings)).collect(),
+        },
+    }
+}
+
+fn substitute_literal(literal: &Literal, bindings: &Bindings) -> Literal {
+    Literal::new(literal.sign, substitute(&literal.atom, bindings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ObjectRule, ProofObject};
+    use crate::data::{
+        Clause, ClauseArena, ClauseId, Inference, InferenceRule, Justification, Literal, Simplification, SymbolKind,
+        SymbolTable, Term, VariableId,
+    };
+    use crate::inference::Proof;
+
+    struct Problem {
+        table: SymbolTable,
+        arena: ClauseArena,
+        eq: crate::data::SymbolId,
+    }
+
+    impl Problem {
+        fn new() -> Self {
+            let table = SymbolTable::new();
+            let eq = table.intern("=", 2, SymbolKind::Predicate);
+            Self { table, arena: ClauseArena::new(), eq }
+        }
+
+        fn constant(&self, name: &str) -> Term {
+            Term::application(self.table.intern(name, 0, SymbolKind::Constant), vec![])
+        }
+
+        fn f(&self, arg: Term) -> Term {
+            Term::application(self.table.intern("f", 1, SymbolKind::Function), vec![arg])
+        }
+
+        fn p(&self, arg: Term) -> Term {
+            Term::application(self.table.intern("P", 1, SymbolKind::Predicate), vec![arg])
+        }
+
+        fn equal(&self, left: Term, right: Term) -> Term {
+            Term::application(self.eq, vec![left, right])
+        }
+
+        fn derive(&mut self, literals: Vec<Literal>, justification: Justification) -> ClauseId {
+            let mut clause = Clause::new(literals).with_rule(InferenceRule::BackDemod);
+            for id in justification.clause_ids() {
+                clause.add_parent(id);
+            }
+            self.arena.insert(clause.with_justification(justification))
+        }
+    }
+
+    fn var(id: u16) -> Term {
+        Term::variable(VariableId::new(id))
+    }
+
+    #[test]
+    fn demodulation_and_factoring_become_elementary_steps() {
+        let mut problem = Problem::new();
+        let a = problem.constant("a");
+        // 1: f(x) = x   2: P(f(a)) | P(y)
+        let c1 = problem.arena.insert(Clause::new(vec![Literal::new(true, problem.equal(problem.f(var(0)), var(0)))]));
+        let c2 = problem.arena.insert(Clause::new(vec![
+            Literal::new(true, problem.p(problem.f(a.clone()))),
+            Literal::new(true, problem.p(var(1))),
+        ]));
+        // 3: P(a), rewriting 2 with 1 and factoring
+        let mut justification = Justification::new(Inference::Copy { parent: c2 });
+        justification.push(Simplification::Demod(vec![(c1, false)]));
+        justification.push(Simplification::Factor { kept: 0, merged: 1 });
+        let c3 = problem.derive(vec![Literal::new(true, problem.p(a))], justification);
+
+        let proof = Proof::from_arena(&problem.arena, c3).unwrap();
+        let object = ProofObject::from_proof(&proof, Some(problem.eq)).unwrap();
+        assert_eq!(
+            object.to_ivy(&problem.table),
+            ";; BEGINNING OF PROOF OBJECT\n\
+             (\n\
+             (1 (input) (= (f v0) v0) (1))\n\
+             (2 (input) (or (P (f (a))) (P v1)) (2))\n\
+             (3 (instantiate 1 ((v0 . (a)))) (= (f (a)) (a)) NIL)\n\
+             (4 (paramod 3 (1) 2 (1 1)) (or (P (a)) (P v1)) NIL)\n\
+             (5 (instantiate 4 ((v1 . (a)))) (or (P (a)) (P (a))) NIL)\n\
+             (6 (merge 5 (2)) (P (a)) (3))\n\
+             )\n\
+             ;; END OF PROOF OBJECT\n"
+        );
+    }
+
+    #[test]
+    fn xx_res_resolves_with_reflexivity() {
+        let mut problem = Problem::new();
+        let b = problem.constant("b");
+        // 1: f(x) = x   2: f(b) != b
+        let c1 = problem.arena.insert(Clause::new(vec![Literal::new(true, problem.equal(problem.f(var(0)), var(0)))]));
+        let c2 = problem.arena.insert(Clause::new(vec![Literal::new(false, problem.equal(problem.f(b.clone()), b))]));
+        let mut justification = Justification::new(Inference::Copy { parent: c2 });
+        justification.push(Simplification::Demod(vec![(c1, false)]));
+        justification.push(Simplification::XxRes);
+        let c3 = problem.derive(vec![], justification);
+
+        let proof = Proof::from_arena(&problem.arena, c3).unwrap();
+        let object = ProofObject::from_proof(&proof, Some(problem.eq)).unwrap();
+        let last = object.steps().last().unwrap();
+        assert!(last.literals.is_empty());
+        assert_eq!(last.clause_id, Some(c3));
+        let ObjectRule::Resolve { right, .. } = last.rule else {
+            panic!("expected a resolve step, got {:?}", last.rule);
+        };
+        let reflexivity = &object.steps()[right - 1];
+        assert!(matches!(reflexivity.rule, ObjectRule::Instantiate { .. }));
+        assert!(object.to_ivy(&problem.table).contains("(input) (= v0 v0) NIL)"));
+    }
+
+    #[test]
+    fn equality_factoring_rewrites_with_a_tautology() {
+        let mut problem = Problem::new();
+        let (a, b, c) = (problem.constant("a"), problem.constant("b"), problem.constant("c"));
+        // 1: b = f(x) | f(a) = c   2: b != c | f(a) = c
+        let c1 = problem.arena.insert(Clause::new(vec![
+            Literal::new(true, problem.equal(b.clone(), problem.f(var(0)))),
+            Literal::new(true, problem.equal(problem.f(a.clone()), c.clone())),
+        ]));
+        let justification = Justification::new(Inference::EqualityFactoring {
+            parent: c1,
+            literal: 0,
+            right: true,
+            other: 1,
+            other_right: false,
+        });
+        let c2 = problem.derive(
+            vec![Literal::new(false, problem.equal(b, c.clone())), Literal::new(true, problem.equal(problem.f(a), c))],
+            justification,
+        );
+
+        let proof = Proof::from_arena(&problem.arena, c2).unwrap();
+        let object = ProofObject::from_proof(&proof, Some(problem.eq)).unwrap();
+        assert_eq!(
+            object.to_ivy(&problem.table),
+            ";; BEGINNING OF PROOF OBJECT\n\
+             (\n\
+             (1 (input) (or (= (b) (f v0)) (= (f (a)) (c))) (1))\n\
+             (2 (instantiate 1 ((v0 . (a)))) (or (= (b) (f (a))) (= (f (a)) (c))) NIL)\n\
+             (3 (input) (= v0 v0) NIL)\n\
+             (4 (instantiate 3 ((v0 . (b)))) (= (b) (b)) NIL)\n\
+             (5 (propositional 4) (or (not (= (b) (c))) (= (b) (c))) NIL)\n\
+             (6 (paramod 5 (2 1) 2 (1 1)) (or (not (= (b) (c))) (or (= (c) (f (a))) (= (f (a)) (c)))) NIL)\n\
+             (7 (instantiate 3 ((v0 . (c)))) (= (c) (c)) NIL)\n\
+             (8 (paramod 6 (2 1 1) 7 (1)) (or (not (= (b) (c))) (or (= (f (a)) (c)) (= (f (a)) (c)))) NIL)\n\
+             (9 (merge 8 (2 2)) (or (not (= (b) (c))) (= (f (a)) (c))) (2))\n\
+             )\n\
+             ;; END OF PROOF OBJECT\n"
+        );
+    }
+}
@@ -3,12 +3,28 @@
 //! This module provides a simple saturation-based prover that uses binary
 //! resolution to search for contradictions (empty clauses).
 
-use crate::data::{Clause, ClauseArena, ClauseId, ClauseList, LRPO, SymbolId, SymbolTable, WeightTable};
+use crate::data::{
+    format_term, Clause, ClauseArena, ClauseId, ClauseList, DiscriminationTree, Inference, InferenceRule, Justification, KBO,
+    LRPO, PartnerIndex, Simplification, SubsumptionIndex, SymbolId, SymbolPrecedence, SymbolTable, Term, TermBank,
+    TermOrdering, WeightTable, WeightTemplate,
+};
 use crate::inference::{
-    all_resolvents, back_subsumed, demodulate_clause_with_limit, extract_demodulator, factor_clause, forward_subsumed, forward_subsumed_ancestor,
-    forward_unit_deletion, hyperresolve_units, linked_ur_resolve, paramodulate_into, ur_resolve,
-    Demodulator, LinkedURConfig,
+    all_resolvents, check_proof_with_evaluator, demodulate_clause_shared, demodulate_clause_with_limit, extract_demodulator, factor_clause, forward_subsumed, forward_subsumed_ancestor,
+    forward_unit_deletion, hyperresolve_units, linked_ur_resolve, paramodulate_into, subsumes, superpose, ur_resolve, user_demodulator, UnitDeleted,
+    equality_factors, equality_resolvents, DemodCache, Demodulator, EqualityFactor, EqualityResolvent, EvalContext, Evaluator,
+    LinkedURConfig, Paramodulant, Proof, ProofCheckError, ProofObject, ProverStats,
 };
+use serde::Serialize;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Distinct terms the forward demodulation bank may hold before it is
+/// dropped and started afresh.
+const MAX_SHARED_TERMS: usize = 1 << 18;
+
+/// Given clauses between garbage collections, which also run when the
+/// memory limit is reached.
+const GC_INTERVAL: usize = 100;
 
 /// Result of a proof search.
 #[derive(Clone, Debug)]
@@ -21,6 +37,12 @@ pub enum ProofResult {
         clauses_generated: usize,
         /// Clauses kept after filtering
         clauses_kept: usize,
+        /// Answer literals of every proof clause, in the order the proofs
+        /// were found; empty for refutations
+        answers: Vec<Answer>,
+        /// Empty or answer clause of every proof, in the order found; the
+        /// first is `empty_clause_id`
+        proofs: Vec<ClauseId>,
     },
     /// Search exhausted without finding proof
     Saturated {
@@ -35,15 +57,37 @@ pub enum ProofResult {
     },
 }
 
+/// An instantiated `$Ans` literal of a proof clause: the witness for the
+/// variables of the goal it was attached to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    /// The answer predicate, `$Ans` or a variant such as `$Ans_x`
+    pub predicate: SymbolId,
+    pub args: Vec<Term>,
+}
+
+impl Answer {
+    /// Each argument printed through `symbols`.
+    pub fn format_args(&self, symbols: &SymbolTable) -> Vec<String> {
+        self.args.iter().map(|arg| format_term(arg, symbols)).collect()
+    }
+
+    /// The whole literal, e.g. `$Ans(f(a))`.
+    pub fn format(&self, symbols: &SymbolTable) -> String {
+        format_term(&Term::application(self.predicate, self.args.clone()), symbols)
+    }
+}
+
 /// Configuration for the prover.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ProverConfig {
     /// Maximum number of clauses to generate
     pub max_clauses: usize,
     /// Maximum number of given clauses to process
     pub max_given: usize,
     /// Ratio of clauses selected by weight vs FIFO (pick_given_ratio)
-    /// For every N clauses selected by weight, select 1 by FIFO
+    /// For every N clauses selected by weight, select 1 by FIFO;
+    /// `usize::MAX` (Otter's -1) always selects by weight
     pub pick_given_ratio: usize,
     /// Maximum proof search time in seconds (0 = unlimited)
     pub max_seconds: u64,
@@ -81,6 +125,12 @@ pub struct ProverConfig {
     pub use_ancestor_subsume: bool,
     /// Use unit deletion to simplify clauses
     pub use_unit_deletion: bool,
+    /// Orient equalities with the Knuth-Bendix ordering instead of LRPO (kbo)
+    pub use_kbo: bool,
+    /// Use ordered superposition, equality resolution and equality factoring
+    /// in place of paramodulation (superposition).  Input clauses of usable
+    /// are moved to sos so that every clause is given
+    pub use_superposition: bool,
     /// Maximum weight for clauses (higher weight clauses are discarded)
     pub max_weight: i32,
     /// Weight for forward subsumption hint matching
@@ -103,8 +153,17 @@ pub struct ProverConfig {
     pub max_clauses_per_given: usize,
     /// Maximum iterations for demodulation rewrites (prevent infinite rewrite loops)
     pub max_demod_iterations: usize,
-    /// Memory limit in bytes (0 = unlimited)
+    /// Memory limit in bytes, measured by [`Prover::memory_bytes`] (0 = unlimited)
     pub max_memory_bytes: usize,
+    /// Lower max_weight as memory fills, and purge sos instead of stopping
+    /// at the memory limit (control_memory)
+    pub control_memory: bool,
+    /// Purge the heaviest sos clauses beyond this many (0 = unlimited)
+    pub max_sos: usize,
+    /// Print an Ivy-style proof object after each proof (build_proof_object)
+    pub build_proof_object: bool,
+    /// Stop after this many distinct proofs (max_proofs)
+    pub max_proofs: usize,
 }
 
 impl Default for ProverConfig {
@@ -131,6 +190,8 @@ impl Default for ProverConfig {
             use_subsumption: false,
             use_ancestor_subsume: false,
             use_unit_deletion: false,
+            use_kbo: false,
+            use_superposition: false,
             max_weight: i32::MAX,
             fsub_hint_wt: crate::inference::MAX_WEIGHT,
             fsub_hint_add_wt: 0,
@@ -143,6 +204,10 @@ impl Default for ProverConfig {
             max_clauses_per_given: 0,  // 0 = unlimited (like original Foras)
             max_demod_iterations: 100,
             max_memory_bytes: 0,
+            control_memory: false,
+            max_sos: 0,
+            build_proof_object: false,
+            max_proofs: 1,
         }
     }
 }
@@ -159,24 +224,70 @@ pub struct Prover {
     sos: ClauseList,
     /// Usable clauses (clauses to resolve against)
     usable: ClauseList,
+    /// Passive clauses: never given and never inference parents, only used
+    /// for forward subsumption and unit conflict
+    passive: ClauseList,
     /// Equality symbol (if set)
     eq_symbol: Option<SymbolId>,
     /// Demodulators for term rewriting
     demodulators: Vec<Demodulator>,
+    /// Sum of [`Demodulator::memory_bytes`] over the demodulators
+    demodulator_bytes: usize,
+    /// Discrimination tree over the demodulators' left sides, extended
+    /// before each forward demodulation
+    demod_index: DiscriminationTree,
+    /// Hash-consed copies of the terms forward demodulation rewrites, with
+    /// their normal forms.  Only a cache: kept clauses own their terms.
+    /// Both are dropped at garbage collection and once the bank holds
+    /// `MAX_SHARED_TERMS` terms
+    term_bank: TermBank,
+    demod_cache: DemodCache,
+    /// Feature-vector index over usable, sos and passive for subsumption
+    subsumption_index: SubsumptionIndex,
+    /// Term index over usable for finding inference partners
+    partner_index: PartnerIndex,
     /// Symbol weight table for clause selection
     weight_table: WeightTable,
-    /// Term ordering for demodulation and paramodulation
-    lrpo: LRPO,
+    /// Weights for the max_weight test, when `weight_list(purge_gen)`
+    /// differs from the pick_given list
+    purge_table: Option<WeightTable>,
+    /// Built-ins evaluated during demodulation
+    evaluator: Evaluator,
+    /// Symbol precedence from `lex`
+    precedence: SymbolPrecedence,
+    /// Term ordering for demodulation and paramodulation: KBO with the
+    /// kbo flag, LRPO otherwise; rebuilt when the precedence or the symbol
+    /// weights change
+    ordering: Rc<dyn TermOrdering>,
     /// Hints for guiding the search
     hints: crate::inference::HintsList,
     /// Statistics
     clauses_generated: usize,
     clauses_kept: usize,
     given_count: usize,
+    clauses_forward_subsumed: usize,
+    clauses_back_subsumed: usize,
+    /// Wall-clock time spent in `search`
+    search_time: std::time::Duration,
+    /// Memory use at which control_memory next lowers max_weight
+    next_memory_control: usize,
+    /// max_weight as lowered by memory control during the current search;
+    /// the configured value is left alone
+    memory_max_weight: i32,
+    /// Sos clauses given up by max_sos or memory control
+    clauses_purged: usize,
+    /// Deleted clauses reclaimed by garbage collection
+    clauses_reclaimed: usize,
     /// Counter for pick_given_ratio (tracks when to select by FIFO vs weight)
     pick_count: usize,
-    /// Proof found during back-demodulation (t != t contradiction)
-    proof_from_back_demod: Option<ClauseId>,
+    /// Empty clause found while keeping clauses, by back-demodulation
+    /// (t != t) or a unit conflict with the passive list
+    pending_proof: Option<ClauseId>,
+    /// Size of the usable list when the search started
+    usable_at_start: Option<usize>,
+    /// Empty or answer clauses of the proofs found by the most recent
+    /// search, in the order found
+    proofs: Vec<ClauseId>,
 }
 
 impl Prover {
@@ -187,33 +298,86 @@ impl Prover {
 
     /// Create a new prover with custom configuration.
     pub fn with_config(config: ProverConfig, symbols: SymbolTable) -> Self {
-        Self {
+        let mut prover = Self {
             config,
             symbols,
             arena: ClauseArena::new(),
             sos: ClauseList::new("sos"),
             usable: ClauseList::new("usable"),
+            passive: ClauseList::new("passive"),
             eq_symbol: None,
             demodulators: Vec::new(),
+            demodulator_bytes: 0,
+            demod_index: DiscriminationTree::new(),
+            term_bank: TermBank::new(),
+            demod_cache: DemodCache::new(),
+            subsumption_index: SubsumptionIndex::new(),
+            partner_index: PartnerIndex::new(),
             weight_table: WeightTable::new(),
-            lrpo: LRPO::new(),
+            purge_table: None,
+            evaluator: Evaluator::default(),
+            precedence: SymbolPrecedence::new(),
+            ordering: Rc::new(LRPO::new()),
             hints: crate::inference::HintsList::new(),
             clauses_generated: 0,
             clauses_kept: 0,
             given_count: 0,
+            clauses_forward_subsumed: 0,
+            clauses_back_subsumed: 0,
+            search_time: std::time::Duration::ZERO,
+            next_memory_control: 0,
+            memory_max_weight: i32::MAX,
+            clauses_purged: 0,
+            clauses_reclaimed: 0,
             pick_count: 0,
-            proof_from_back_demod: None,
-        }
+            pending_proof: None,
+            usable_at_start: None,
+            proofs: Vec::new(),
+        };
+        prover.update_ordering();
+        prover
     }
 
     /// Set symbol weight for clause selection.
     pub fn set_symbol_weight(&mut self, symbol: SymbolId, weight: i32) {
         self.weight_table.set_weight(symbol, weight);
+        self.update_ordering();
     }
 
     /// Set default weight for unlisted symbols.
     pub fn set_default_weight(&mut self, weight: i32) {
         self.weight_table.set_default(weight);
+        self.update_ordering();
+    }
+
+    /// Install the templates of `weight_list(pick_given)` and
+    /// `weight_list(purge_gen)`, each in list order.
+    pub fn set_weight_templates(&mut self, pick: Vec<WeightTemplate>, purge: Vec<WeightTemplate>) {
+        self.purge_table = (pick != purge).then(|| {
+            let mut table = self.weight_table.clone();
+            for template in purge {
+                table.add_template(template);
+            }
+            table
+        });
+        for template in pick {
+            self.weight_table.add_template(template);
+        }
+        self.update_ordering();
+    }
+
+    /// Weight compared against max_weight: the pick weight, unless a
+    /// separate purge_gen list is in effect.
+    fn purge_weight(&self, clause: &Clause) -> i32 {
+        match &self.purge_table {
+            Some(table) => table.weight_clause(clause),
+            None => clause.pick_weight,
+        }
+    }
+
+    /// Set the built-ins to evaluate while demodulating.
+    pub fn set_evaluator(&mut self, evaluator: Evaluator) {
+        self.evaluator = evaluator;
     }
 
     /// Set the equality symbol for paramodulation.
@@ -223,7 +387,18 @@ impl Prover {
 
     /// Set symbol precedence for term ordering (lower value = higher precedence).
     pub fn set_symbol_precedence(&mut self, sym: SymbolId, prec: u32) {
-        self.lrpo.set_precedence(sym, prec);
+        self.precedence.set(sym, prec);
+        self.update_ordering();
+    }
+
+    /// Rebuild the term ordering from the precedence and, for KBO, the
+    /// pick_given symbol weights.
+    fn update_ordering(&mut self) {
+        self.ordering = if self.config.use_kbo {
+            Rc::new(KBO::new(self.precedence.clone(), self.weight_table.clone()))
+        } else {
+            Rc::new(LRPO::with_precedence(self.precedence.clone()))
+        };
     }
 
     /// Add a hint clause to guide the search.
@@ -284,15 +459,53 @@ impl Prover {
         if self.config.use_factor {
             // Try to factor the clause once
             let factors = factor_clause(&clause, None);
-            if !factors.is_empty() {
+            if let Some(factor) = factors.first() {
                 // Use the first factor (in full Foras, might generate all factors)
-                clause = factors[0].clause.clone();
+                let rule = clause.rule;
+                let justification = clause.justification.take();
+                clause = factor.clause.clone();
+                clause.rule = rule;
+                clause.justification = justification;
+                let (kept, merged) = factor.literals;
+                clause.add_simplification(Simplification::Factor { kept, merged });
             }
         }
 
-        // Apply forward demodulation if enabled
-        if self.config.use_demod && !self.demodulators.is_empty() {
-            clause = demodulate_clause_with_limit(&clause, &self.demodulators, self.config.max_demod_iterations);
+        // Apply forward demodulation and evaluation; without demod_inf only
+        // user demodulators are present
+        if !self.demodulators.is_empty() || !self.evaluator.is_empty() {
+            for position in self.demod_index.len()..self.demodulators.len() {
+                self.demod_index.insert(&self.demodulators[position].lhs, position);
+            }
+            if self.term_bank.len() >= MAX_SHARED_TERMS {
+                self.term_bank = TermBank::new();
+                self.demod_cache = DemodCache::new();
+            }
+            let eval = EvalContext { evaluator: &self.evaluator, symbols: &self.symbols };
+            let (demodulated, fired, evaluated) = demodulate_clause_shared(
+                &mut self.term_bank,
+                &mut self.demod_cache,
+                &clause,
+                &self.demodulators,
+                Some(&self.demod_index),
+                Some(eval),
+                self.config.max_demod_iterations,
+            );
+            clause = demodulated;
+            if !fired.is_empty() {
+                let steps = fired.iter().filter_map(|idx| self.demod_step(&self.demodulators[*idx])).collect();
+                clause.add_simplification(Simplification::Demod(steps));
+            }
+            if evaluated {
+                // A literal evaluated to true makes the clause true; ones
+                // evaluated to false are dropped
+                let truth = |lit: &crate::data::Literal| self.evaluator.truth_value(&lit.atom).map(|value| value == lit.sign);
+                if clause.literals.iter().any(|lit| truth(lit) == Some(true)) {
+                    return None;
+                }
+                clause.literals.retain(|lit| truth(lit).is_none());
+                clause.add_simplification(Simplification::Eval);
+            }
         }
 
         // Check for xx_res: negated reflexive equality (t != t) is immediately false
@@ -306,8 +519,11 @@ impl Prover {
                         if *symbol == eq_sym && args.len() == 2 {
                             // Check if both sides are syntactically equal
                             if args[0] == args[1] {
-                                // t != t is a contradiction - return empty clause
-                                return Some(Clause::new(vec![]));
+                                // t != t is a contradiction - return empty clause,
+                                // keeping the derivation for proof output
+                                clause.literals.clear();
+                                clause.add_simplification(Simplification::XxRes);
+                                return Some(clause);
                             }
                         }
                     }
@@ -315,19 +531,6 @@ impl Prover {
             }
         }
 
-        // Check if this clause is a demodulator
-        if self.config.use_demod {
-            if let Some(eq_sym) = self.eq_symbol {
-                if let Some(demod) = extract_demodulator(&clause, eq_sym, Some(&self.lrpo)) {
-                    // Apply back-demodulation: rewrite existing clauses with new demodulator
-                    if self.config.use_back_demod {
-                        self.back_demodulate(&demod);
-                    }
-                    self.demodulators.push(demod);
-                }
-            }
-        }
-
         if clause.parents.is_empty() && input_parents > 0 {
              eprintln!("PROCESS: Parents lost during processing! Input: {}, Output: 0", input_parents);
         }
@@ -350,6 +553,7 @@ impl Prover {
         // Input clauses bypass max_weight check
         let id = self.arena.insert(clause);
         self.sos.push(id);
+        self.index_for_subsumption(id);
         self.clauses_kept += 1;
         id
     }
@@ -367,7 +571,7 @@ impl Prover {
         }
 
         // Check max_weight constraint for inferred clauses
-        if self.config.max_weight < i32::MAX && clause.pick_weight > self.config.max_weight {
+        if self.max_weight() < i32::MAX && self.purge_weight(&clause) > self.max_weight() {
             // Clause exceeds max_weight - check if it should be kept due to hints
             let keep = crate::inference::hint_keep_test(
                 &clause,
@@ -382,7 +586,13 @@ impl Prover {
 
         let id = self.arena.insert(clause);
         self.sos.push(id);
+        self.index_for_subsumption(id);
         self.clauses_kept += 1;
+        if self.pending_proof.is_none()
+            && let Some(conflict) = self.passive_conflict(id)
+        {
+            self.pending_proof = Some(self.arena.insert(conflict));
+        }
         Some(id)
     }
 
@@ -404,7 +614,7 @@ impl Prover {
         }
 
         // Check max_weight constraint for inferred clauses
-        if self.config.max_weight < i32::MAX && clause.pick_weight > self.config.max_weight {
+        if self.max_weight() < i32::MAX && self.purge_weight(&clause) > self.max_weight() {
             // Clause exceeds max_weight - check if it should be kept due to hints
             let keep = crate::inference::hint_keep_test(
                 &clause,
@@ -419,10 +629,52 @@ impl Prover {
 
         let id = self.arena.insert(clause);
         self.sos.push(id);
+        self.index_for_subsumption(id);
         self.clauses_kept += 1;
+        if self.pending_proof.is_none()
+            && let Some(conflict) = self.passive_conflict(id)
+        {
+            self.pending_proof = Some(self.arena.insert(conflict));
+        }
+
+        // Check if the kept clause is a demodulator
+        if self.config.use_demod {
+            if let Some(eq_sym) = self.eq_symbol {
+                let demod = self
+                    .arena
+                    .get(id)
+                    .and_then(|clause| extract_demodulator(clause, eq_sym, Some(self.ordering.as_ref())));
+                if let Some(demod) = demod {
+                    let demod = demod.with_source(id);
+                    // Apply back-demodulation: rewrite existing clauses with new demodulator
+                    if self.config.use_back_demod {
+                        self.back_demodulate(&demod);
+                    }
+                    self.push_demodulator(demod);
+                }
+            }
+        }
         true
     }
 
+    /// The justification entry for a rewrite by `demod`: its source clause
+    /// and whether the equation is used right to left.
+    fn demod_step(&self, demod: &Demodulator) -> Option<(ClauseId, bool)> {
+        let source = demod.source_id?;
+        let clause = self.arena.get(source)?;
+        let mut equation = clause.literals.first().map(|lit| &lit.atom);
+        if demod.condition.is_some()
+            && let Some(crate::data::Term::Application { args, .. }) = equation
+        {
+            equation = args.get(1);
+        }
+        let flipped = match equation {
+            Some(crate::data::Term::Application { args, .. }) => args.first() != Some(&demod.lhs),
+            _ => false,
+        };
+        Some((source, flipped))
+    }
+
     /// Add a clause to the usable set.
     pub fn add_usable(&mut self, mut clause: Clause) -> ClauseId {
         // Cache the weight (may be used if clause moves to SOS later)
@@ -430,10 +682,97 @@ impl Prover {
 
         let id = self.arena.insert(clause);
         self.usable.push(id);
+        self.index_for_subsumption(id);
+        self.index_as_partner(id);
+        self.clauses_kept += 1;
+        id
+    }
+
+    /// Add a rule from `list(demodulators)`.  Rules are used left to right
+    /// in the order they are added, ahead of the demodulators found during
+    /// the search; `condition -> (lhs = rhs)` rules are conditional.
+    pub fn add_demodulator(&mut self, clause: Clause) -> Result<ClauseId, String> {
+        let eq_symbol = self.eq_symbol.ok_or("no equality symbol for demodulators")?;
+        let implication = self.symbols.get_ids_by_name("->").into_iter().find(|id| {
+            self.symbols.get(*id).is_some_and(|symbol| symbol.arity == 2)
+        });
+        let demod = user_demodulator(&clause, eq_symbol, implication, &self.ordering).map_err(|error| {
+            format!("demodulator {}: {}", crate::data::format_clause(&clause, &self.symbols), error)
+        })?;
+        let id = self.arena.insert(clause);
+        self.push_demodulator(demod.with_source(id));
+        Ok(id)
+    }
+
+    /// Add a clause to the passive list.
+    pub fn add_passive(&mut self, mut clause: Clause) -> ClauseId {
+        clause.pick_weight = self.weight_table.weight_clause(&clause);
+
+        let id = self.arena.insert(clause);
+        self.passive.push(id);
+        self.index_for_subsumption(id);
         self.clauses_kept += 1;
         id
     }
 
+    fn index_for_subsumption(&mut self, id: ClauseId) {
+        if let Some(clause) = self.arena.get(id) {
+            self.subsumption_index.insert(id, clause);
+        }
+    }
+
+    /// The clauses of `ids`, in order, copied out of the arena so the
+    /// prover can be changed while they are inferred with.
+    fn partner_clauses(&self, ids: Vec<ClauseId>) -> Vec<(ClauseId, Clause)> {
+        ids.into_iter().filter_map(|id| self.arena.get(id).map(|clause| (id, clause.clone()))).collect()
+    }
+
+    /// Unit deletion of `clause` by the usable units that clash with it.
+    fn forward_unit_deletion(&self, clause: &Clause) -> Option<UnitDeleted> {
+        let (units, unit_ids): (Vec<Clause>, Vec<Option<ClauseId>>) = self
+            .partner_index
+            .resolution_partners(clause)
+            .into_iter()
+            .filter_map(|id| self.arena.get(id).map(|unit| (id, unit)))
+            .filter(|(_, unit)| unit.literals.len() == 1)
+            .map(|(id, unit)| (unit.clone(), Some(id)))
+            .unzip();
+        forward_unit_deletion(clause, None, &units, &unit_ids)
+    }
+
+    fn index_as_partner(&mut self, id: ClauseId) {
+        if let Some(clause) = self.arena.get(id) {
+            self.partner_index.insert(id, clause);
+        }
+    }
+
+    /// Move every clause of usable into sos.
+    fn move_usable_to_sos(&mut self) {
+        let moved: Vec<ClauseId> = self.usable.iter().copied().collect();
+        self.usable.retain(|_| false);
+        for id in moved {
+            self.partner_index.remove(id);
+            self.sos.push(id);
+        }
+    }
+
+    /// Otter's unit conflict: the proof clause obtained by resolving the
+    /// unit clause `id` with a passive clause, if there is one.
+    fn passive_conflict(&self, id: ClauseId) -> Option<Clause> {
+        let unit = self.arena.get(id).filter(|clause| clause.literals.len() == 1)?;
+        self.passive.iter().find_map(|passive_id| {
+            let passive = self.arena.get(*passive_id)?;
+            all_resolvents(unit, passive, Some(id), Some(*passive_id))
+                .into_iter()
+                .find(|resolvent| self.is_proof(&resolvent.clause))
+                .map(|resolvent| {
+                    resolvent.clause.with_rule(InferenceRule::BinaryRes).with_justification(
+                        Justification::binary(id, resolvent.lit1_index, *passive_id, resolvent.lit2_index),
+                    )
+                })
+        })
+    }
+
     /// Select and remove the lightest clause from SOS based on weight.
     ///
     /// This scans all clauses in SOS, finds the one with minimum weight,
@@ -464,6 +803,102 @@ impl Prover {
         self.sos.remove(min_index)
     }
 
+    /// Approximate bytes held by the search state: stored clauses with the
+    /// subsumption and partner indexes, demodulators with their index and
+    /// the shared terms of forward demodulation, hints and the clause lists.
+    pub fn memory_bytes(&self) -> usize {
+        self.arena.memory_bytes()
+            + self.subsumption_index.memory_bytes()
+            + self.partner_index.memory_bytes()
+            + self.demodulator_bytes
+            + self.demod_index.memory_bytes()
+            + self.term_bank.memory_bytes()
+            + self.demod_cache.memory_bytes()
+            + self.hints.memory_bytes()
+            + (self.sos.len() + self.usable.len() + self.passive.len()) * std::mem::size_of::<ClauseId>()
+    }
+
+    /// The max_weight in force: the configured one, or lower once memory
+    /// control has lowered it.
+    fn max_weight(&self) -> i32 {
+        self.config.max_weight.min(self.memory_max_weight)
+    }
+
+    /// Pick weights of the sos clauses, lightest first.
+    fn sos_weights(&self) -> Vec<i32> {
+        let mut weights: Vec<i32> = self
+            .sos
+            .iter()
+            .filter_map(|id| self.arena.get(*id).map(|clause| clause.pick_weight))
+            .collect();
+        weights.sort_unstable();
+        weights
+    }
+
+    /// Otter's control_memory: once a third of `max_mem` is in use, and at
+    /// every further twentieth, lower max_weight so that the heaviest fifth
+    /// of sos would no longer be kept.
+    fn control_memory(&mut self) {
+        let limit = self.config.max_memory_bytes;
+        if self.next_memory_control == 0 {
+            self.next_memory_control = limit / 3;
+        }
+        let used = self.memory_bytes();
+        if used < self.next_memory_control {
+            return;
+        }
+        self.next_memory_control = used + (limit / 20).max(1);
+        let weights = self.sos_weights();
+        if let Some(&cutoff) = weights.get(weights.len() * 4 / 5) {
+            self.memory_max_weight = self.memory_max_weight.min(cutoff);
+        }
+    }
+
+    /// Remove the heaviest sos clauses until `keep` remain, newest first
+    /// among equal weights.  Purged clauses no other clause, demodulator or
+    /// proof descends from are dropped from the arena as well.
+    fn purge_sos(&mut self, keep: usize) {
+        if self.sos.len() <= keep {
+            return;
+        }
+        let mut ranked: Vec<(usize, i32, ClauseId)> = self
+            .sos
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (index, self.arena.get(*id).map_or(i32::MAX, |clause| clause.pick_weight), *id))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+        let purged: HashSet<ClauseId> =
+            ranked.iter().take(self.sos.len() - keep).map(|(_, _, id)| *id).collect();
+        self.sos.retain(|id| !purged.contains(&id));
+        self.clauses_purged += purged.len();
+        for id in &purged {
+            self.subsumption_index.remove(*id);
+            self.arena.delete(*id);
+        }
+        self.collect_garbage();
+    }
+
+    /// Reclaim the deleted clauses that no kept clause, demodulator or proof
+    /// descends from, and return how many were reclaimed.  Back subsumed,
+    /// back demodulated and purged clauses are deleted but stay in the arena
+    /// until collected.  The shared terms of forward demodulation are
+    /// dropped as well.
+    pub fn collect_garbage(&mut self) -> usize {
+        let roots: Vec<ClauseId> = self
+            .demodulators
+            .iter()
+            .filter_map(|demod| demod.source_id)
+            .chain(self.proofs.iter().copied())
+            .chain(self.pending_proof)
+            .collect();
+        self.term_bank = TermBank::new();
+        self.demod_cache = DemodCache::new();
+        let reclaimed = self.arena.collect(roots);
+        self.clauses_reclaimed += reclaimed;
+        reclaimed
+    }
+
     /// Pre-process initial clauses to extract demodulators.
     fn preprocess_initial_clauses(&mut self) {
         if !self.config.use_demod {
@@ -475,31 +910,40 @@ impl Prover {
             None => return,
         };
 
-        // Extract demodulators from usable clauses
-        for clause_id in self.usable.iter() {
-            if let Some(clause) = self.arena.get(*clause_id) {
-                if let Some(demod) = extract_demodulator(clause, eq_sym, Some(&self.lrpo)) {
-                    self.demodulators.push(demod);
-                }
-            }
+        // Extract demodulators from usable clauses, then from SOS clauses
+        let found: Vec<Demodulator> = self
+            .usable
+            .iter()
+            .chain(self.sos.iter())
+            .filter_map(|clause_id| {
+                let clause = self.arena.get(*clause_id)?;
+                let demod = extract_demodulator(clause, eq_sym, Some(self.ordering.as_ref()))?;
+                Some(demod.with_source(*clause_id))
+            })
+            .collect();
+        for demod in found {
+            self.push_demodulator(demod);
         }
+    }
 
-        // Also extract demodulators from SOS clauses
-        for clause_id in self.sos.iter() {
-            if let Some(clause) = self.arena.get(*clause_id) {
-                if let Some(demod) = extract_demodulator(clause, eq_sym, Some(&self.lrpo)) {
-                    self.demodulators.push(demod);
-                }
-            }
-        }
+    fn push_demodulator(&mut self, demod: Demodulator) {
+        self.demodulator_bytes += demod.memory_bytes();
+        self.demodulators.push(demod);
     }
 
-    /// Check if a clause is forward subsumed, using ancestor subsumption if enabled.
-    fn is_forward_subsumed(&self, clause: &Clause, usable_refs: &[&Clause], sos_refs: &[&Clause]) -> bool {
+    /// Check if a clause is forward subsumed by usable, sos or passive
+    /// clauses, using ancestor subsumption if enabled.
+    fn is_forward_subsumed(&self, clause: &Clause) -> bool {
+        let candidates: Vec<&Clause> = self
+            .subsumption_index
+            .subsumer_candidates(clause)
+            .into_iter()
+            .filter_map(|id| self.arena.get(id))
+            .collect();
         if self.config.use_ancestor_subsume {
-            forward_subsumed_ancestor(clause, usable_refs) || forward_subsumed_ancestor(clause, sos_refs)
+            forward_subsumed_ancestor(clause, &candidates)
         } else {
-            forward_subsumed(clause, usable_refs) || forward_subsumed(clause, sos_refs)
+            forward_subsumed(clause, &candidates)
         }
     }
 
@@ -512,168 +956,213 @@ impl Prover {
             return;
         }
 
-        // Collect clause IDs and references from usable
-        let usable_pairs: Vec<(ClauseId, &Clause)> = self.usable.iter()
-            .filter_map(|id| self.arena.get(*id).map(|c| (*id, c)))
-            .collect();
-
-        // Collect clause IDs and references from SOS
-        let sos_pairs: Vec<(ClauseId, &Clause)> = self.sos.iter()
-            .filter_map(|id| self.arena.get(*id).map(|c| (*id, c)))
+        // Passive clauses are never removed, and the given clause is in
+        // neither list while its inferences are made
+        let subsumed: HashSet<ClauseId> = self
+            .subsumption_index
+            .subsumee_candidates(new_clause)
+            .into_iter()
+            .filter(|id| self.arena.get(*id).is_some_and(|clause| subsumes(new_clause, clause)))
             .collect();
-
-        // Find clause IDs subsumed by the new clause
-        let usable_refs: Vec<&Clause> = usable_pairs.iter().map(|(_, c)| *c).collect();
-        let sos_refs: Vec<&Clause> = sos_pairs.iter().map(|(_, c)| *c).collect();
-
-        let subsumed_in_usable = back_subsumed(new_clause, &usable_refs);
-        let subsumed_in_sos = back_subsumed(new_clause, &sos_refs);
-
-        // Collect IDs to remove from usable
-        let mut usable_ids_to_remove: Vec<ClauseId> = Vec::new();
-        for subsumed_clause in subsumed_in_usable {
-            for (id, clause) in &usable_pairs {
-                if std::ptr::eq(*clause, subsumed_clause) {
-                    usable_ids_to_remove.push(*id);
-                    break;
-                }
-            }
-        }
-
-        // Collect IDs to remove from SOS
-        let mut sos_ids_to_remove: Vec<ClauseId> = Vec::new();
-        for subsumed_clause in subsumed_in_sos {
-            for (id, clause) in &sos_pairs {
-                if std::ptr::eq(*clause, subsumed_clause) {
-                    sos_ids_to_remove.push(*id);
-                    break;
-                }
-            }
-        }
-
-        // Remove clauses from usable by finding and removing each ID
-        for id_to_remove in usable_ids_to_remove {
-            let mut index_to_remove = None;
-            for (idx, id) in self.usable.iter().enumerate() {
-                if *id == id_to_remove {
-                    index_to_remove = Some(idx);
-                    break;
-                }
-            }
-            if let Some(idx) = index_to_remove {
-                self.usable.remove(idx);
-            }
-        }
-
-        // Remove clauses from SOS by finding and removing each ID
-        for id_to_remove in sos_ids_to_remove {
-            let mut index_to_remove = None;
-            for (idx, id) in self.sos.iter().enumerate() {
-                if *id == id_to_remove {
-                    index_to_remove = Some(idx);
-                    break;
-                }
-            }
-            if let Some(idx) = index_to_remove {
-                self.sos.remove(idx);
-            }
+        let removed: Vec<ClauseId> =
+            self.usable.iter().chain(self.sos.iter()).copied().filter(|id| subsumed.contains(id)).collect();
+        self.usable.retain(|id| !subsumed.contains(&id));
+        self.sos.retain(|id| !subsumed.contains(&id));
+        self.clauses_back_subsumed += removed.len();
+        for id in removed {
+            self.subsumption_index.remove(id);
+            self.partner_index.remove(id);
+            self.arena.delete(id);
         }
     }
 
     /// Apply a new demodulator to all existing clauses (back-demodulation).
     ///
-    /// This rewrites clauses in both usable and SOS with the new demodulator,
-    /// which can simplify the clause set and help find proofs faster.
-    /// If a t != t contradiction is found, sets proof_from_back_demod.
+    /// Each clause in usable and SOS that the demodulator rewrites is replaced
+    /// by a new clause justified as a rewritten copy of the old one, so the
+    /// old clause stays in the arena for proofs that already use it.
+    /// If a t != t contradiction is found, sets pending_proof.
     fn back_demodulate(&mut self, new_demod: &Demodulator) {
         let eq_sym = self.eq_symbol;
+        let step = self.demod_step(new_demod);
+
+        for in_sos in [false, true] {
+            let list = if in_sos { &self.sos } else { &self.usable };
+            let ids: Vec<ClauseId> = list.iter().copied().collect();
+            for (index, clause_id) in ids.into_iter().enumerate() {
+                // A demodulator does not rewrite its own clause
+                if Some(clause_id) == new_demod.source_id {
+                    continue;
+                }
+                let clause = match self.arena.get(clause_id) {
+                    Some(clause) => clause,
+                    None => continue,
+                };
+                let simplified = demodulate_clause_with_limit(clause, std::slice::from_ref(new_demod), self.config.max_demod_iterations);
 
-        // Apply to usable clauses
-        for clause_id in self.usable.iter() {
-            if let Some(clause) = self.arena.get(*clause_id).cloned() {
-                let simplified = demodulate_clause_with_limit(&clause, &[new_demod.clone()], self.config.max_demod_iterations);
-
-                // Only update if the clause actually changed
-                if clause.literals != simplified.literals {
-                    // Check for xx_res: t != t becomes empty clause
-                    if let Some(eq_s) = eq_sym {
-                        if simplified.literals.len() == 1 {
-                            let lit = &simplified.literals[0];
-                            if !lit.sign {
-                                if let crate::data::Term::Application { symbol, args } = &lit.atom {
-                                    if *symbol == eq_s && args.len() == 2 && args[0] == args[1] {
-                                        // Found t != t - create empty clause
-                                        let empty = Clause::new(vec![]);
-                                        let empty_id = self.arena.insert(empty);
-                                        self.proof_from_back_demod = Some(empty_id);
-                                        return;
-                                    }
-                                }
-                            }
-                        }
-                    }
+                // Only replace if the clause actually changed
+                if clause.literals == simplified.literals {
+                    continue;
+                }
 
-                    if let Some(mut_clause) = self.arena.get_mut(*clause_id) {
-                        *mut_clause = simplified;
-                    }
+                let mut copy = Clause::new(simplified.literals)
+                    .with_rule(InferenceRule::BackDemod)
+                    .with_justification(Justification::new(Inference::Copy { parent: clause_id }));
+                copy.proof_depth = clause.proof_depth;
+                copy.attributes = clause.attributes.clone();
+                copy.add_parent(clause_id);
+                if let Some(step) = step {
+                    copy.add_simplification(Simplification::Demod(vec![step]));
                 }
-            }
-        }
 
-        // Apply to SOS clauses
-        for clause_id in self.sos.iter() {
-            if let Some(clause) = self.arena.get(*clause_id).cloned() {
-                let simplified = demodulate_clause_with_limit(&clause, &[new_demod.clone()], self.config.max_demod_iterations);
-
-                // Only update if the clause actually changed
-                if clause.literals != simplified.literals {
-                    // Check for xx_res: t != t becomes empty clause
-                    if let Some(eq_s) = eq_sym {
-                        if simplified.literals.len() == 1 {
-                            let lit = &simplified.literals[0];
-                            if !lit.sign {
-                                if let crate::data::Term::Application { symbol, args } = &lit.atom {
-                                    if *symbol == eq_s && args.len() == 2 && args[0] == args[1] {
-                                        // Found t != t - create empty clause
-                                        let empty = Clause::new(vec![]);
-                                        let empty_id = self.arena.insert(empty);
-                                        self.proof_from_back_demod = Some(empty_id);
-                                        return;
-                                    }
+                // Check for xx_res: t != t becomes empty clause
+                if let Some(eq_s) = eq_sym {
+                    if copy.literals.len() == 1 {
+                        let lit = &copy.literals[0];
+                        if !lit.sign {
+                            if let crate::data::Term::Application { symbol, args } = &lit.atom {
+                                if *symbol == eq_s && args.len() == 2 && args[0] == args[1] {
+                                    // Found t != t - create empty clause
+                                    copy.literals.clear();
+                                    copy.add_simplification(Simplification::XxRes);
+                                    let empty_id = self.arena.insert(copy);
+                                    self.pending_proof = Some(empty_id);
+                                    return;
                                 }
                             }
                         }
                     }
+                }
 
-                    // Need to recalculate weight for SOS clauses since they may be selected
-                    if let Some(mut_clause) = self.arena.get_mut(*clause_id) {
-                        *mut_clause = simplified;
-                        mut_clause.pick_weight = self.weight_table.weight_clause(mut_clause);
+                // Recalculate the weight since SOS clauses may be selected
+                copy.pick_weight = self.weight_table.weight_clause(&copy);
+                let copy_id = self.arena.insert(copy);
+                if in_sos {
+                    self.sos.replace(index, copy_id);
+                } else {
+                    self.usable.replace(index, copy_id);
+                    if let Some(copy) = self.arena.get(copy_id) {
+                        self.partner_index.replace(clause_id, copy_id, copy);
                     }
                 }
+                self.subsumption_index.remove(clause_id);
+                self.index_for_subsumption(copy_id);
+                self.arena.delete(clause_id);
             }
         }
     }
 
     /// Run the proof search.
     pub fn search(&mut self) -> ProofResult {
+        let start = std::time::Instant::now();
+        self.proofs.clear();
+        self.memory_max_weight = i32::MAX;
+        self.next_memory_control = 0;
+        let result = self.run_search();
+        self.search_time += start.elapsed();
+        // A search that found fewer than max_proofs proofs before stopping
+        // still reports the ones it found
+        if self.proofs.is_empty() { result } else { self.proof_result() }
+    }
+
+    /// Record the proof ending in clause `id`, unless it only repeats the
+    /// answer of an earlier proof.  Returns whether max_proofs is reached.
+    fn record_proof(&mut self, id: ClauseId) -> bool {
+        let repeated = self.arena.get(id).is_some_and(|clause| {
+            !clause.literals.is_empty()
+                && self.proofs.iter().filter_map(|proof_id| self.arena.get(*proof_id)).any(|earlier| {
+                    subsumes(earlier, clause) && subsumes(clause, earlier)
+                })
+        });
+        if !repeated {
+            self.proofs.push(id);
+        }
+        self.proofs.len() >= self.config.max_proofs
+    }
+
+    fn proof_result(&self) -> ProofResult {
+        ProofResult::Proof {
+            empty_clause_id: self.proofs[0],
+            clauses_generated: self.clauses_generated,
+            clauses_kept: self.clauses_kept,
+            answers: self.proofs.iter().flat_map(|id| self.answers(*id)).collect(),
+            proofs: self.proofs.clone(),
+        }
+    }
+
+    /// The answer literals of the proof clause `id`.
+    fn answers(&self, id: ClauseId) -> Vec<Answer> {
+        let Some(clause) = self.arena.get(id) else {
+            return Vec::new();
+        };
+        clause
+            .literals
+            .iter()
+            .filter_map(|lit| match &lit.atom {
+                Term::Application { symbol, args } => Some(Answer { predicate: *symbol, args: args.clone() }),
+                Term::Variable { .. } => None,
+            })
+            .collect()
+    }
+
+    /// The first proof found by the last search, ordered from input clauses
+    /// to the empty (or answer) clause.
+    pub fn proof(&self) -> Option<Proof> {
+        Proof::from_arena(&self.arena, *self.proofs.first()?)
+    }
+
+    /// Every proof found by the last search, in the order found.
+    pub fn proofs(&self) -> Vec<Proof> {
+        self.proofs.iter().filter_map(|id| Proof::from_arena(&self.arena, *id)).collect()
+    }
+
+    /// Replay the first proof step by step with the independent checker,
+    /// `None` if there is no proof.
+    pub fn check_proof(&self) -> Option<Result<(), ProofCheckError>> {
+        let eval = EvalContext { evaluator: &self.evaluator, symbols: &self.symbols };
+        Some(check_proof_with_evaluator(&self.proof()?, self.eq_symbol, Some(eval)))
+    }
+
+    /// The first proof expanded into an Ivy-style proof object, `None` if
+    /// there is no proof.
+    pub fn proof_object(&self) -> Option<Result<ProofObject, ProofCheckError>> {
+        Some(ProofObject::from_proof(&self.proof()?, self.eq_symbol))
+    }
+
+    fn run_search(&mut self) -> ProofResult {
         eprintln!("DEBUG: Starting search, SOS={}, usable={}", self.sos.len(), self.usable.len());
+        self.usable_at_start = Some(self.usable.len());
+        // Superposition only infers with the given clause, so input clauses
+        // left in usable would never be inferred with each other
+        if self.config.use_superposition {
+            self.move_usable_to_sos();
+        }
         // Pre-process to extract initial demodulators
         self.preprocess_initial_clauses();
         eprintln!("DEBUG: After preprocess, demodulators={}", self.demodulators.len());
 
+        // Input units may already conflict with the passive list
+        let inputs: Vec<ClauseId> = self.usable.iter().chain(self.sos.iter()).copied().collect();
+        for id in inputs {
+            if let Some(conflict) = self.passive_conflict(id) {
+                let empty_id = self.arena.insert(conflict);
+                self.clauses_kept += 1;
+                if self.record_proof(empty_id) {
+                    return self.proof_result();
+                }
+            }
+        }
+
         // Start timer for max_seconds check
         let start_time = std::time::Instant::now();
 
         while !self.sos.is_empty() {
             // Check if back-demodulation found a proof (t != t contradiction)
-            if let Some(empty_id) = self.proof_from_back_demod.take() {
+            if let Some(empty_id) = self.pending_proof.take() {
                 self.clauses_kept += 1;
-                return ProofResult::Proof {
-                    empty_clause_id: empty_id,
-                    clauses_generated: self.clauses_generated,
-                    clauses_kept: self.clauses_kept,
-                };
+                if self.record_proof(empty_id) {
+                    return self.proof_result();
+                }
             }
 
             // Check resource limits
@@ -705,29 +1194,49 @@ impl Prover {
                 }
             }
 
-            // Check memory limit (every 10 iterations to reduce overhead)
-            if self.config.max_memory_bytes > 0 && self.given_count % 10 == 0 {
-                use sysinfo::{ProcessRefreshKind, System};
-                let mut system = System::new();
-                if let Ok(pid) = sysinfo::get_current_pid() {
-                    system.refresh_process_specifics(pid, ProcessRefreshKind::new().with_memory());
-                    if let Some(process) = system.process(pid) {
-                        let memory_bytes = process.memory() * 1024; // Convert KB to bytes
-                        if memory_bytes as usize > self.config.max_memory_bytes {
-                            return ProofResult::ResourceLimit {
-                                clauses_generated: self.clauses_generated,
-                                clauses_kept: self.clauses_kept,
-                                limit_type: format!("max_memory ({} MB used)", memory_bytes / 1024 / 1024),
-                            };
-                        }
+            // Deleted clauses are reclaimed now and then whatever the
+            // memory limit, so the arena does not keep every one of them
+            if self.given_count > 0 && self.given_count.is_multiple_of(GC_INTERVAL) {
+                self.collect_garbage();
+            }
+
+            // Check memory limit
+            if self.config.max_memory_bytes > 0 {
+                if self.config.control_memory {
+                    self.control_memory();
+                }
+                if self.memory_bytes() > self.config.max_memory_bytes {
+                    self.collect_garbage();
+                }
+                let used = self.memory_bytes();
+                if used > self.config.max_memory_bytes {
+                    if !self.config.control_memory {
+                        return ProofResult::ResourceLimit {
+                            clauses_generated: self.clauses_generated,
+                            clauses_kept: self.clauses_kept,
+                            limit_type: "max_mem".to_string(),
+                        };
+                    }
+                    // Degrade instead: give up the heaviest half of sos and
+                    // keep nothing heavier than what remains
+                    self.purge_sos(self.sos.len() / 2);
+                    if let Some(heaviest) = self.sos_weights().last() {
+                        self.memory_max_weight = self.memory_max_weight.min(*heaviest);
                     }
                 }
             }
 
+            if self.config.max_sos > 0 && self.sos.len() > self.config.max_sos {
+                self.purge_sos(self.config.max_sos);
+            }
+
             // Select given clause using pick_given_ratio strategy
             // Pick by weight for first N clauses, then by FIFO for 1 clause, repeat
             let select_by_weight = self.pick_count < self.config.pick_given_ratio;
-            self.pick_count = (self.pick_count + 1) % (self.config.pick_given_ratio + 1);
+            self.pick_count = match self.config.pick_given_ratio.checked_add(1) {
+                Some(period) => (self.pick_count + 1) % period,
+                None => 0,
+            };
 
             let given_id = if select_by_weight {
                 self.select_lightest_clause()
@@ -758,51 +1267,39 @@ impl Prover {
             // This is critical for Knuth-Bendix completion
             if self.config.use_demod {
                 if let Some(eq_sym) = self.eq_symbol {
-                    if let Some(demod) = extract_demodulator(&given_clause, eq_sym, Some(&self.lrpo)) {
+                    if let Some(demod) = extract_demodulator(&given_clause, eq_sym, Some(self.ordering.as_ref())) {
+                        let demod = demod.with_source(given_id);
                         // Apply back-demodulation with new demodulator
                         if self.config.use_back_demod {
                             self.back_demodulate(&demod);
                             // Check if back-demod found a proof (t != t contradiction)
-                            if let Some(empty_id) = self.proof_from_back_demod.take() {
+                            if let Some(empty_id) = self.pending_proof.take() {
                                 self.clauses_kept += 1;
-                                return ProofResult::Proof {
-                                    empty_clause_id: empty_id,
-                                    clauses_generated: self.clauses_generated,
-                                    clauses_kept: self.clauses_kept,
-                                };
+                                if self.record_proof(empty_id) {
+                                    return self.proof_result();
+                                }
                             }
                         }
-                        self.demodulators.push(demod);
+                        self.push_demodulator(demod);
                     }
                 }
             }
 
-            // Collect usable clauses for inference
-            // Build paired list to ensure IDs and clauses stay in sync
-            let usable_pairs: Vec<(ClauseId, Clause)> = self.usable
-                .iter()
-                .filter_map(|id| self.arena.get(*id).cloned().map(|c| (*id, c)))
-                .collect();
-            let usable_ids: Vec<ClauseId> = usable_pairs.iter().map(|(id, _)| *id).collect();
-            let usable_clauses: Vec<Clause> = usable_pairs.into_iter().map(|(_, c)| c).collect();
-            let usable_id_opts: Vec<Option<ClauseId>> = usable_ids.iter().map(|id| Some(*id)).collect();
-
-            // Collect SOS clauses snapshot for forward subsumption (C Foras checks both usable AND SOS)
-            let sos_clauses_snapshot: Vec<Clause> = self.sos
-                .iter()
-                .filter_map(|id| self.arena.get(*id).cloned())
-                .collect();
-
             // Perform inference rules within a labeled block to allow early exit on limits
             'given_clause_iteration: {
             // Perform hyperresolution if enabled
-            // In hyperresolution, the given clause (positive satellite) is resolved
-            // against usable clauses (nuclei with negative literals)
+            // In hyperresolution, nuclei with negative literals are resolved
+            // against positive unit satellites, one of them the given clause
             if self.config.use_hyper_res {
-                // Try hyperresolving each usable clause (nucleus) with given + other usable (satellites)
-                for (nucleus_idx, nucleus_id) in usable_ids.iter().enumerate() {
-                    let nucleus = &usable_clauses[nucleus_idx];
-
+                // A positive unit given clause is a satellite of the usable
+                // nuclei it clashes with; any other is itself the nucleus
+                let given_is_satellite = given_clause.literals.len() == 1 && given_clause.literals[0].sign;
+                let nuclei = if given_is_satellite {
+                    self.partner_clauses(self.partner_index.resolution_partners(&given_clause))
+                } else {
+                    vec![(given_id, given_clause.clone())]
+                };
+                for (nucleus_id, nucleus) in nuclei {
                     // Check if nucleus has negative literals
                     if !nucleus.literals.iter().any(|lit| !lit.sign) {
                         continue;
@@ -811,21 +1308,23 @@ impl Prover {
                     // Build list of satellites: given clause + other positive units from usable
                     let mut satellites = vec![];
                     let mut satellite_ids = vec![];
-
-                    // Add given clause if it's a positive unit
-                    if given_clause.literals.len() == 1 && given_clause.literals[0].sign {
+                    if given_is_satellite {
                         satellites.push(given_clause.clone());
                         satellite_ids.push(Some(given_id));
                     }
 
-                    for (sat_idx, sat_id) in usable_ids.iter().enumerate() {
-                        if sat_idx != nucleus_idx {
-                            let sat = &usable_clauses[sat_idx];
-                            // Add positive units as potential satellites
-                            if sat.literals.len() == 1 && sat.literals[0].sign {
-                                satellites.push(sat.clone());
-                                satellite_ids.push(Some(*sat_id));
-                            }
+                    for sat_id in self.partner_index.resolution_partners(&nucleus) {
+                        if sat_id == nucleus_id {
+                            continue;
+                        }
+                        // Add positive units as potential satellites
+                        let sat = match self.arena.get(sat_id) {
+                            Some(c) => c,
+                            None => continue,
+                        };
+                        if sat.literals.len() == 1 && sat.literals[0].sign {
+                            satellites.push(sat.clone());
+                            satellite_ids.push(Some(sat_id));
                         }
                     }
 
@@ -834,8 +1333,8 @@ impl Prover {
                     }
 
                     let hyper_resolvents = hyperresolve_units(
-                        nucleus,
-                        Some(*nucleus_id),
+                        &nucleus,
+                        Some(nucleus_id),
                         &satellites,
                         &satellite_ids,
                     );
@@ -852,7 +1351,11 @@ impl Prover {
                         }
 
                         // Process the clause (demodulate, extract demodulators)
-                        let processed = match self.process_new_clause(resolvent.clause) {
+                        let processed = match self.process_new_clause(
+                            resolvent.clause
+                                .with_rule(InferenceRule::HyperRes)
+                                .with_justification(nucleus_justification(nucleus_id, &resolvent.clashes, &resolvent.satellite_ids)),
+                        ) {
                             Some(c) => c,
                             None => continue,
                         };
@@ -861,36 +1364,25 @@ impl Prover {
                         if self.is_proof(&processed) {
                             let empty_id = self.arena.insert(processed);
                             self.clauses_kept += 1;
-                            return ProofResult::Proof {
-                                empty_clause_id: empty_id,
-                                clauses_generated: self.clauses_generated,
-                                clauses_kept: self.clauses_kept,
-                            };
+                            if self.record_proof(empty_id) {
+                                return self.proof_result();
+                            }
+                            continue;
                         }
 
                         // Unit deletion: simplify clause using unit clauses
                         let mut final_clause = processed;
                         if self.config.use_unit_deletion {
-                            if let Some(unit_deleted) = forward_unit_deletion(
-                                &final_clause,
-                                None,
-                                &usable_clauses,
-                                &usable_ids.iter().map(|id| Some(*id)).collect::<Vec<_>>(),
-                            ) {
+                            if let Some(unit_deleted) = self.forward_unit_deletion(&final_clause) {
                                 final_clause = unit_deleted.clause;
-                                // Restore parents
-                                for parent in unit_deleted.parents {
-                                    final_clause.add_parent(parent);
-                                }
                                 // Check again for proof after unit deletion
                                 if self.is_proof(&final_clause) {
                                     let empty_id = self.arena.insert(final_clause);
                                     self.clauses_kept += 1;
-                                    return ProofResult::Proof {
-                                        empty_clause_id: empty_id,
-                                        clauses_generated: self.clauses_generated,
-                                        clauses_kept: self.clauses_kept,
-                                    };
+                                    if self.record_proof(empty_id) {
+                                        return self.proof_result();
+                                    }
+                                    continue;
                                 }
                             }
                         }
@@ -909,13 +1401,10 @@ impl Prover {
                         }
 
                         // Forward subsumption: check if new clause is subsumed by existing clauses
-                        // Check against both usable and SOS (like C Foras)
-                        if self.config.use_subsumption {
-                            let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                            let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                            if self.is_forward_subsumed(&final_clause, &usable_refs, &sos_refs) {
-                                continue; // Skip this clause, it's subsumed
-                            }
+                        // Check against usable, sos and passive (like C Foras)
+                        if self.config.use_subsumption && self.is_forward_subsumed(&final_clause) {
+                            self.clauses_forward_subsumed += 1;
+                            continue; // Skip this clause, it's subsumed
                         }
 
                         // Backward subsumption: remove clauses subsumed by this new clause
@@ -933,9 +1422,8 @@ impl Prover {
 
             // Perform binary resolution if enabled
             if self.config.use_binary_res {
-                for (i, usable_id) in usable_ids.iter().enumerate() {
-                    let usable_clause = &usable_clauses[i];
-
+                let partners = self.partner_clauses(self.partner_index.resolution_partners(&given_clause));
+                for (usable_id, usable_clause) in &partners {
                     let resolvents = all_resolvents(
                         &given_clause,
                         usable_clause,
@@ -954,7 +1442,11 @@ impl Prover {
                         }
 
                         // Process the clause (demodulate, extract demodulators)
-                        let processed = match self.process_new_clause(resolvent.clause) {
+                        let processed = match self.process_new_clause(
+                            resolvent.clause
+                                .with_rule(InferenceRule::BinaryRes)
+                                .with_justification(Justification::binary(given_id, resolvent.lit1_index, *usable_id, resolvent.lit2_index)),
+                        ) {
                             Some(c) => c,
                             None => continue,
                         };
@@ -963,36 +1455,25 @@ impl Prover {
                         if self.is_proof(&processed) {
                             let empty_id = self.arena.insert(processed);
                             self.clauses_kept += 1;
-                            return ProofResult::Proof {
-                                empty_clause_id: empty_id,
-                                clauses_generated: self.clauses_generated,
-                                clauses_kept: self.clauses_kept,
-                            };
+                            if self.record_proof(empty_id) {
+                                return self.proof_result();
+                            }
+                            continue;
                         }
 
                         // Unit deletion: simplify clause using unit clauses
                         let mut final_clause = processed;
                         if self.config.use_unit_deletion {
-                            if let Some(unit_deleted) = forward_unit_deletion(
-                                &final_clause,
-                                None,
-                                &usable_clauses,
-                                &usable_ids.iter().map(|id| Some(*id)).collect::<Vec<_>>(),
-                            ) {
+                            if let Some(unit_deleted) = self.forward_unit_deletion(&final_clause) {
                                 final_clause = unit_deleted.clause;
-                                // Restore parents
-                                for parent in unit_deleted.parents {
-                                    final_clause.add_parent(parent);
-                                }
                                 // Check again for proof after unit deletion
                                 if self.is_proof(&final_clause) {
                                     let empty_id = self.arena.insert(final_clause);
                                     self.clauses_kept += 1;
-                                    return ProofResult::Proof {
-                                        empty_clause_id: empty_id,
-                                        clauses_generated: self.clauses_generated,
-                                        clauses_kept: self.clauses_kept,
-                                    };
+                                    if self.record_proof(empty_id) {
+                                        return self.proof_result();
+                                    }
+                                    continue;
                                 }
                             }
                         }
@@ -1011,13 +1492,10 @@ impl Prover {
                         }
 
                         // Forward subsumption: check if new clause is subsumed by existing clauses
-                        // Check against both usable and SOS (like C Foras)
-                        if self.config.use_subsumption {
-                            let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                            let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                            if self.is_forward_subsumed(&final_clause, &usable_refs, &sos_refs) {
-                                continue; // Skip this clause, it's subsumed
-                            }
+                        // Check against usable, sos and passive (like C Foras)
+                        if self.config.use_subsumption && self.is_forward_subsumed(&final_clause) {
+                            self.clauses_forward_subsumed += 1;
+                            continue; // Skip this clause, it's subsumed
                         }
 
                         // Backward subsumption: remove clauses subsumed by this new clause
@@ -1035,19 +1513,28 @@ impl Prover {
 
             // Perform UR-resolution if enabled
             if self.config.use_ur_res {
-                // Collect usable clauses for UR-resolution
+                // Satellites are the usable clauses that clash with the given clause
+                let (satellite_ids, satellites): (Vec<Option<ClauseId>>, Vec<Clause>) = self
+                    .partner_clauses(self.partner_index.resolution_partners(&given_clause))
+                    .into_iter()
+                    .map(|(id, clause)| (Some(id), clause))
+                    .unzip();
                 let ur_resolvents = ur_resolve(
                     &given_clause,
                     Some(given_id),
-                    &usable_clauses,
-                    &usable_id_opts,
+                    &satellites,
+                    &satellite_ids,
                 );
 
                 for resolvent in ur_resolvents {
                     self.clauses_generated += 1;
 
                     // Process the clause
-                    let processed = match self.process_new_clause(resolvent.clause) {
+                    let processed = match self.process_new_clause(
+                        resolvent.clause
+                            .with_rule(InferenceRule::UrRes)
+                            .with_justification(nucleus_justification(given_id, &resolvent.clashes, &resolvent.satellite_ids)),
+                    ) {
                         Some(c) => c,
                         None => continue,
                     };
@@ -1056,20 +1543,16 @@ impl Prover {
                     if self.is_proof(&processed) {
                         let empty_id = self.arena.insert(processed);
                         self.clauses_kept += 1;
-                        return ProofResult::Proof {
-                            empty_clause_id: empty_id,
-                            clauses_generated: self.clauses_generated,
-                            clauses_kept: self.clauses_kept,
-                        };
+                        if self.record_proof(empty_id) {
+                            return self.proof_result();
+                        }
+                        continue;
                     }
 
-                    // Forward subsumption: check against both usable and SOS
-                    if self.config.use_subsumption {
-                        let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                        let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                        if self.is_forward_subsumed(&processed, &usable_refs, &sos_refs) {
-                            continue;
-                        }
+                    // Forward subsumption: check against usable, sos and passive
+                    if self.config.use_subsumption && self.is_forward_subsumed(&processed) {
+                        self.clauses_forward_subsumed += 1;
+                        continue;
                     }
 
                     // Backward subsumption
@@ -1087,6 +1570,10 @@ impl Prover {
             // Perform Linked UR-resolution if enabled
             if self.config.use_linked_ur_res {
                 let linked_ur_config = LinkedURConfig::default();
+                // Linked chains may pass through usable clauses that do not
+                // clash with the given clause, so all of usable takes part
+                let (usable_ids, usable_clauses): (Vec<ClauseId>, Vec<Clause>) =
+                    self.partner_clauses(self.usable.iter().copied().collect()).into_iter().unzip();
                 let linked_ur_resolvents = linked_ur_resolve(
                     &given_clause,
                     Some(given_id),
@@ -1094,11 +1581,27 @@ impl Prover {
                     &linked_ur_config,
                 );
 
-                for resolvent in linked_ur_resolvents {
+                for mut resolvent in linked_ur_resolvents {
                     self.clauses_generated += 1;
 
+                    let mut parents = vec![given_id];
+                    parents.extend(resolvent.satellites.iter().map(|idx| usable_ids[*idx]));
+                    for id in &parents[1..] {
+                        if !resolvent.clause.parents.iter().any(|parent| parent == id) {
+                            resolvent.clause.add_parent(*id);
+                        }
+                    }
+                    let justification = Justification::new(Inference::Resolution {
+                        parents,
+                        clashes: resolvent.clashes,
+                    });
+
                     // Process the clause
-                    let processed = match self.process_new_clause(resolvent.clause) {
+                    let processed = match self.process_new_clause(
+                        resolvent.clause
+                            .with_rule(InferenceRule::LinkedUrRes)
+                            .with_justification(justification),
+                    ) {
                         Some(c) => c,
                         None => continue,
                     };
@@ -1107,20 +1610,16 @@ impl Prover {
                     if self.is_proof(&processed) {
                         let empty_id = self.arena.insert(processed);
                         self.clauses_kept += 1;
-                        return ProofResult::Proof {
-                            empty_clause_id: empty_id,
-                            clauses_generated: self.clauses_generated,
-                            clauses_kept: self.clauses_kept,
-                        };
+                        if self.record_proof(empty_id) {
+                            return self.proof_result();
+                        }
+                        continue;
                     }
 
-                    // Forward subsumption: check against both usable and SOS
-                    if self.config.use_subsumption {
-                        let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                        let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                        if self.is_forward_subsumed(&processed, &usable_refs, &sos_refs) {
-                            continue;
-                        }
+                    // Forward subsumption: check against usable, sos and passive
+                    if self.config.use_subsumption && self.is_forward_subsumed(&processed) {
+                        self.clauses_forward_subsumed += 1;
+                        continue;
                     }
 
                     // Backward subsumption
@@ -1136,14 +1635,28 @@ impl Prover {
             }
 
             // Perform paramodulation if enabled and we have an equality symbol
-            if (self.config.use_para_into || self.config.use_para_from) && self.eq_symbol.is_some() {
+            if (self.config.use_para_into || self.config.use_para_from)
+                && !self.config.use_superposition
+                && self.eq_symbol.is_some()
+            {
                 let eq_sym = self.eq_symbol.unwrap();
+                let into_partners: HashSet<ClauseId> = if self.config.use_para_into {
+                    self.partner_index.para_into_partners(&given_clause, eq_sym).into_iter().collect()
+                } else {
+                    HashSet::new()
+                };
+                let from_partners: HashSet<ClauseId> = if self.config.use_para_from {
+                    self.partner_index.para_from_partners(&given_clause, eq_sym).into_iter().collect()
+                } else {
+                    HashSet::new()
+                };
+                let mut partner_ids: Vec<ClauseId> = into_partners.union(&from_partners).copied().collect();
+                self.partner_index.sort_by_place(&mut partner_ids);
+                let partners = self.partner_clauses(partner_ids);
 
-                for (i, usable_id) in usable_ids.iter().enumerate() {
-                    let usable_clause = &usable_clauses[i];
-
+                for (usable_id, usable_clause) in &partners {
                     // Para into: given contains equality, paramodulate into usable
-                    if self.config.use_para_into {
+                    if into_partners.contains(usable_id) {
                         let paramodulants = paramodulate_into(
                             &given_clause,
                             Some(given_id),
@@ -1160,7 +1673,7 @@ impl Prover {
                             self.clauses_generated += 1;
 
                             // Process the clause (demodulate, extract demodulators)
-                            let processed = match self.process_new_clause(paramodulant.clause) {
+                            let processed = match self.process_new_clause(para_clause(paramodulant, InferenceRule::ParaFrom)) {
                                 Some(c) => c,
                                 None => continue,
                             };
@@ -1168,21 +1681,17 @@ impl Prover {
                             if self.is_proof(&processed) {
                                 let empty_id = self.arena.insert(processed);
                                 self.clauses_kept += 1;
-                                return ProofResult::Proof {
-                                    empty_clause_id: empty_id,
-                                    clauses_generated: self.clauses_generated,
-                                    clauses_kept: self.clauses_kept,
-                                };
+                                if self.record_proof(empty_id) {
+                                    return self.proof_result();
+                                }
+                                continue;
                             }
 
                             // Forward subsumption: check if new clause is subsumed by existing clauses
-                            // Check against both usable and SOS (like C Foras)
-                            if self.config.use_subsumption {
-                                let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                                let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                                if self.is_forward_subsumed(&processed, &usable_refs, &sos_refs) {
-                                    continue; // Skip this clause, it's subsumed
-                                }
+                            // Check against usable, sos and passive (like C Foras)
+                            if self.config.use_subsumption && self.is_forward_subsumed(&processed) {
+                                self.clauses_forward_subsumed += 1;
+                                continue; // Skip this clause, it's subsumed
                             }
 
                             // Backward subsumption: remove clauses subsumed by this new clause
@@ -1194,7 +1703,7 @@ impl Prover {
                     }
 
                     // Para from: usable contains equality, paramodulate into given
-                    if self.config.use_para_from {
+                    if from_partners.contains(usable_id) {
                         let paramodulants = paramodulate_into(
                             usable_clause,
                             Some(*usable_id),
@@ -1211,7 +1720,7 @@ impl Prover {
                             self.clauses_generated += 1;
 
                             // Process the clause (demodulate, extract demodulators)
-                            let processed = match self.process_new_clause(paramodulant.clause) {
+                            let processed = match self.process_new_clause(para_clause(paramodulant, InferenceRule::ParaInto)) {
                                 Some(c) => c,
                                 None => continue,
                             };
@@ -1219,21 +1728,17 @@ impl Prover {
                             if self.is_proof(&processed) {
                                 let empty_id = self.arena.insert(processed);
                                 self.clauses_kept += 1;
-                                return ProofResult::Proof {
-                                    empty_clause_id: empty_id,
-                                    clauses_generated: self.clauses_generated,
-                                    clauses_kept: self.clauses_kept,
-                                };
+                                if self.record_proof(empty_id) {
+                                    return self.proof_result();
+                                }
+                                continue;
                             }
 
                             // Forward subsumption: check if new clause is subsumed by existing clauses
-                            // Check against both usable and SOS (like C Foras)
-                            if self.config.use_subsumption {
-                                let usable_refs: Vec<&Clause> = usable_clauses.iter().collect();
-                                let sos_refs: Vec<&Clause> = sos_clauses_snapshot.iter().collect();
-                                if self.is_forward_subsumed(&processed, &usable_refs, &sos_refs) {
-                                    continue; // Skip this clause, it's subsumed
-                                }
+                            // Check against usable, sos and passive (like C Foras)
+                            if self.config.use_subsumption && self.is_forward_subsumed(&processed) {
+                                self.clauses_forward_subsumed += 1;
+                                continue; // Skip this clause, it's subsumed
                             }
 
                             // Backward subsumption: remove clauses subsumed by this new clause
@@ -1249,10 +1754,100 @@ impl Prover {
             if iteration_clause_limit > 0 && (self.clauses_generated - clauses_at_iteration_start) >= iteration_clause_limit {
                 break 'given_clause_iteration;
             }
+
+            // Perform superposition if enabled, in place of paramodulation
+            if self.config.use_superposition && self.eq_symbol.is_some() {
+                let eq_sym = self.eq_symbol.unwrap();
+                let ordering = self.ordering.as_ref();
+
+                // The given clause is not usable yet, so its inferences with
+                // itself are made here
+                let mut inferences: Vec<Clause> = equality_resolvents(&given_clause, Some(given_id), eq_sym, ordering)
+                    .into_iter()
+                    .map(equality_resolvent_clause)
+                    .collect();
+                inferences.extend(
+                    equality_factors(&given_clause, Some(given_id), eq_sym, ordering)
+                        .into_iter()
+                        .map(equality_factor_clause),
+                );
+                let mut superpositions =
+                    superpose(&given_clause, Some(given_id), &given_clause, Some(given_id), eq_sym, ordering);
+                for (usable_id, usable_clause) in self.partner_clauses(self.partner_index.para_into_partners(&given_clause, eq_sym)) {
+                    superpositions.extend(superpose(
+                        &given_clause,
+                        Some(given_id),
+                        &usable_clause,
+                        Some(usable_id),
+                        eq_sym,
+                        ordering,
+                    ));
+                }
+                for (usable_id, usable_clause) in self.partner_clauses(self.partner_index.para_from_partners(&given_clause, eq_sym)) {
+                    superpositions.extend(superpose(
+                        &usable_clause,
+                        Some(usable_id),
+                        &given_clause,
+                        Some(given_id),
+                        eq_sym,
+                        ordering,
+                    ));
+                }
+                inferences.extend(
+                    superpositions
+                        .into_iter()
+                        .map(|superposition| para_clause(superposition, InferenceRule::Superposition)),
+                );
+
+                for inference in inferences {
+                    self.clauses_generated += 1;
+
+                    // Process the clause (demodulate, extract demodulators)
+                    let processed = match self.process_new_clause(inference) {
+                        Some(c) => c,
+                        None => continue,
+                    };
+
+                    if self.is_proof(&processed) {
+                        let empty_id = self.arena.insert(processed);
+                        self.clauses_kept += 1;
+                        if self.record_proof(empty_id) {
+                            return self.proof_result();
+                        }
+                        continue;
+                    }
+
+                    // Forward subsumption: check against usable, sos and passive
+                    if self.config.use_subsumption && self.is_forward_subsumed(&processed) {
+                        self.clauses_forward_subsumed += 1;
+                        continue;
+                    }
+
+                    // Backward subsumption
+                    self.perform_back_subsumption(&processed);
+
+                    // Add to sos (with max_weight filtering)
+                    self.try_keep_clause(processed);
+                }
+            }
+            // Check if we've exceeded per-iteration clause limit after superposition
+            if iteration_clause_limit > 0 && (self.clauses_generated - clauses_at_iteration_start) >= iteration_clause_limit {
+                break 'given_clause_iteration;
+            }
             } // End of 'given_clause_iteration labeled block
 
             // Move given clause to usable
             self.usable.push(given_id);
+            self.index_as_partner(given_id);
+        }
+
+        // The clause that conflicted with the passive list may have been
+        // back subsumed out of sos, ending the loop early
+        if let Some(empty_id) = self.pending_proof.take() {
+            self.clauses_kept += 1;
+            if self.record_proof(empty_id) {
+                return self.proof_result();
+            }
         }
 
         ProofResult::Saturated {
@@ -1266,11 +1861,53 @@ impl Prover {
         &self.arena
     }
 
+    /// Whether saturation of the last search shows the input is satisfiable.
+    ///
+    /// This holds only for binary resolution with factoring, without weight,
+    /// per-given or sos limits, on input without equality (paramodulation here is
+    /// not complete), and with an initially empty usable list, so that the
+    /// set-of-support restriction loses no inferences.
+    pub fn saturation_is_complete(&self) -> bool {
+        let has_equality = self.eq_symbol.is_some_and(|eq_sym| {
+            self.arena.iter().any(|clause| {
+                clause.literals.iter().any(|lit| {
+                    matches!(&lit.atom, crate::data::Term::Application { symbol, .. } if *symbol == eq_sym)
+                })
+            })
+        });
+        self.config.use_binary_res
+            && self.config.use_factor
+            && self.max_weight() == i32::MAX
+            && self.config.max_clauses_per_given == 0
+            && self.clauses_purged == 0
+            && !has_equality
+            && self.usable_at_start == Some(0)
+    }
+
+    /// Get the symbol table used by the prover's clauses.
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
     /// Get statistics about the search.
     pub fn stats(&self) -> (usize, usize, usize) {
         (self.clauses_generated, self.clauses_kept, self.given_count)
     }
 
+    /// Full search statistics, as printed in Otter's statistics section.
+    pub fn statistics(&self) -> ProverStats {
+        ProverStats {
+            clauses_given: self.given_count,
+            clauses_generated: self.clauses_generated,
+            clauses_kept: self.clauses_kept,
+            clauses_forward_subsumed: self.clauses_forward_subsumed,
+            clauses_back_subsumed: self.clauses_back_subsumed,
+            wall_clock_time: self.search_time,
+            kbytes_malloced: self.memory_bytes() / 1024,
+            ..ProverStats::default()
+        }
+    }
+
     /// Get the prover configuration.
     pub fn config(&self) -> &ProverConfig {
         &self.config
@@ -1282,6 +1919,62 @@ impl Prover {
     }
 }
 
+/// Justification of a hyper or UR resolvent whose satellites are units.
+fn nucleus_justification(
+    nucleus: ClauseId,
+    clashes: &[(usize, usize)],
+    satellite_ids: &[Option<ClauseId>],
+) -> Justification {
+    Justification::nucleus(
+        nucleus,
+        clashes
+            .iter()
+            .zip(satellite_ids)
+            .filter_map(|((nucleus_lit, sat_lit), id)| id.map(|id| (*nucleus_lit, id, *sat_lit))),
+    )
+}
+
+/// The paramodulant's clause, with its rule and justification attached.
+fn para_clause(paramodulant: Paramodulant, rule: InferenceRule) -> Clause {
+    let clause = paramodulant.clause.with_rule(rule);
+    match (paramodulant.from_id, paramodulant.into_id) {
+        (Some(from), Some(into)) => clause.with_justification(Justification::new(Inference::Paramodulation {
+            from,
+            from_literal: paramodulant.from_literal,
+            from_right: paramodulant.from_right,
+            into,
+            into_literal: paramodulant.into_literal,
+            position: paramodulant.position,
+        })),
+        _ => clause,
+    }
+}
+
+fn equality_resolvent_clause(resolvent: EqualityResolvent) -> Clause {
+    let clause = resolvent.clause.with_rule(InferenceRule::EqualityResolution);
+    match resolvent.parent_id {
+        Some(parent) => clause.with_justification(Justification::new(Inference::EqualityResolution {
+            parent,
+            literal: resolvent.literal,
+        })),
+        None => clause,
+    }
+}
+
+fn equality_factor_clause(factor: EqualityFactor) -> Clause {
+    let clause = factor.clause.with_rule(InferenceRule::EqualityFactoring);
+    match factor.parent_id {
+        Some(parent) => clause.with_justification(Justification::new(Inference::EqualityFactoring {
+            parent,
+            literal: factor.literal,
+            right: factor.right,
+            other: factor.other,
+            other_right: factor.other_right,
+        })),
+        None => clause,
+    }
+}
+
 impl Default for Prover {
     fn default() -> Self {
         Self::new()
@@ -1292,7 +1985,7 @@ impl Default for Prover {
 mod tests {
     use super::*;
     use crate::data::symbol::{SymbolKind, SymbolTable};
-    use crate::data::{Literal, Term, VariableId};
+    use crate::data::{ClauseState, Literal, Term, VariableId};
 
     fn make_var(id: u16) -> Term {
         Term::variable(VariableId::new(id))
@@ -1330,6 +2023,160 @@ mod tests {
         assert!(matches!(result, ProofResult::Proof { .. }));
     }
 
+    /// P(a) in sos and -P(x) | P(f(x)) in usable: an endless chain of ever
+    /// heavier P(f(...f(a)...)).
+    fn endless_chain(config: ProverConfig) -> Prover {
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let x = make_var(0);
+        let f = table.intern("f", 1, SymbolKind::Function);
+        let p_a = make_pred(&table, "P", vec![a]);
+        let p_x = make_pred(&table, "P", vec![x.clone()]);
+        let p_fx = make_pred(&table, "P", vec![Term::application(f, vec![x])]);
+
+        let mut prover = Prover::with_config(config, table);
+        prover.add_sos(Clause::new(vec![Literal::new(true, p_a)]));
+        prover.add_usable(Clause::new(vec![Literal::new(false, p_x), Literal::new(true, p_fx)]));
+        prover
+    }
+
+    #[test]
+    fn memory_limit_stops_or_degrades_search() {
+        let config = ProverConfig { max_memory_bytes: 4096, ..ProverConfig::default() };
+        let mut prover = endless_chain(config.clone());
+        let result = prover.search();
+        assert!(
+            matches!(&result, ProofResult::ResourceLimit { limit_type, .. } if limit_type == "max_mem"),
+            "{:?}",
+            result
+        );
+        assert!(prover.memory_bytes() > 4096);
+        // The subsumption and partner indexes count beside the clauses
+        let lists = prover.sos.len() + prover.usable.len() + prover.passive.len();
+        assert!(
+            prover.memory_bytes()
+                >= prover.arena.memory_bytes()
+                    + prover.subsumption_index.memory_bytes()
+                    + prover.partner_index.memory_bytes()
+                    + lists * std::mem::size_of::<ClauseId>()
+        );
+        assert!(prover.subsumption_index.memory_bytes() > 0 && prover.partner_index.memory_bytes() > 0);
+
+        let mut prover = endless_chain(ProverConfig { control_memory: true, ..config });
+        let result = prover.search();
+        assert!(matches!(result, ProofResult::Saturated { .. }), "{:?}", result);
+        assert!(prover.max_weight() < i32::MAX);
+        assert_eq!(prover.config().max_weight, i32::MAX);
+        assert!(!prover.saturation_is_complete());
+    }
+
+    #[test]
+    fn saturation_with_input_usable_clauses_is_incomplete() {
+        // P(a) and -P(x) only conflict with each other, both in usable, so
+        // the set of support keeps them apart
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let p_a = make_pred(&table, "P", vec![a]);
+        let p_x = make_pred(&table, "P", vec![make_var(0)]);
+        let q_b = make_pred(&table, "Q", vec![b]);
+        let config = ProverConfig { use_factor: true, ..ProverConfig::default() };
+        let mut prover = Prover::with_config(config, table);
+        prover.add_usable(Clause::new(vec![Literal::new(true, p_a)]));
+        prover.add_usable(Clause::new(vec![Literal::new(false, p_x)]));
+        prover.add_sos(Clause::new(vec![Literal::new(true, q_b)]));
+        let result = prover.search();
+        assert!(matches!(result, ProofResult::Saturated { .. }), "{:?}", result);
+        assert!(!prover.saturation_is_complete());
+        assert_eq!(prover.usable.len(), 3);
+    }
+
+    #[test]
+    fn purge_drops_the_heaviest_sos_clauses() {
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let f = table.intern("f", 1, SymbolKind::Function);
+        let mut term = a;
+        let mut clauses = Vec::new();
+        for _ in 0..4 {
+            clauses.push(Clause::new(vec![Literal::new(true, make_pred(&table, "P", vec![term.clone()]))]));
+            term = Term::application(f, vec![term]);
+        }
+        let mut prover = Prover::with_config(ProverConfig::default(), table);
+        let ids: Vec<ClauseId> = clauses.into_iter().map(|clause| prover.add_sos(clause)).collect();
+        let bytes = prover.memory_bytes();
+
+        prover.purge_sos(2);
+        assert_eq!(prover.sos.iter().copied().collect::<Vec<_>>(), ids[..2]);
+        assert!(prover.arena.get(ids[3]).is_none());
+        assert!(prover.memory_bytes() < bytes);
+        assert_eq!(prover.clauses_purged, 2);
+    }
+
+    #[test]
+    fn collection_reclaims_deleted_clauses_outside_proofs() {
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let x = make_var(0);
+        let lit = |sign: bool, name: &str, arg: &Term| Literal::new(sign, make_pred(&table, name, vec![arg.clone()]));
+        let usable = [
+            Clause::new(vec![lit(false, "S", &x), lit(true, "P", &x)]),
+            Clause::new(vec![lit(false, "P", &x), lit(true, "Q", &x)]),
+            Clause::new(vec![lit(false, "Q", &a)]),
+        ];
+        let sos = [Clause::new(vec![lit(true, "S", &a)]), Clause::new(vec![lit(true, "P", &a), lit(true, "R", &b)])];
+
+        let config = ProverConfig { use_subsumption: true, ..ProverConfig::default() };
+        let mut prover = Prover::with_config(config, table);
+        for clause in usable {
+            prover.add_usable(clause);
+        }
+        let ids: Vec<ClauseId> = sos.into_iter().map(|clause| prover.add_sos(clause)).collect();
+        let result = prover.search();
+        assert!(matches!(result, ProofResult::Proof { .. }), "{:?}", result);
+
+        // The derived P(a) back subsumes P(a) | R(b), which no clause descends from
+        assert_eq!(prover.arena.state(ids[1]), Some(ClauseState::Deleted));
+        let stored = prover.arena.len();
+        assert_eq!(prover.collect_garbage(), 1);
+        assert!(prover.arena.get(ids[1]).is_none());
+        assert_eq!(prover.arena.len(), stored - 1);
+        assert!(prover.arena.is_active(ids[0]));
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn search_collects_garbage_without_a_memory_limit() {
+        let table = SymbolTable::new();
+        let x = make_var(0);
+        let lit = |sign: bool, name: &str, arg: &Term| Literal::new(sign, make_pred(&table, name, vec![arg.clone()]));
+        let usable = Clause::new(vec![lit(false, "S", &x), lit(true, "P", &x)]);
+        // Each S(c) yields P(c), which back subsumes the heavier P(c) | R(c)
+        let sos: Vec<(Clause, Clause)> = (0..GC_INTERVAL)
+            .map(|i| {
+                let c = make_const(&table, &format!("c{}", i));
+                (Clause::new(vec![lit(true, "S", &c)]), Clause::new(vec![lit(true, "P", &c), lit(true, "R", &c)]))
+            })
+            .collect();
+
+        let config = ProverConfig { use_subsumption: true, ..ProverConfig::default() };
+        assert_eq!(config.max_memory_bytes, 0);
+        let mut prover = Prover::with_config(config, table);
+        prover.add_usable(usable);
+        let mut subsumed = Vec::new();
+        for (unit, disjunction) in sos {
+            prover.add_sos(unit);
+            subsumed.push(prover.add_sos(disjunction));
+        }
+        let result = prover.search();
+        assert!(matches!(result, ProofResult::Saturated { .. }), "{:?}", result);
+
+        assert!(prover.clauses_reclaimed > 0);
+        assert!(prover.arena.get(subsumed[0]).is_none());
+        assert_eq!(prover.clauses_reclaimed + prover.collect_garbage(), GC_INTERVAL);
+    }
+
     #[test]
     fn saturates_without_proof() {
         // P(a) and Q(b) cannot derive contradiction
@@ -1385,6 +2232,122 @@ mod tests {
         assert!(matches!(result, ProofResult::Proof { .. }));
     }
 
+    #[test]
+    fn proof_is_extracted_after_search() {
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let y = make_var(1);
+        let clause1 = Clause::new(vec![Literal::new(true, make_pred(&table, "P", vec![a.clone()]))]);
+        let clause2 = Clause::new(vec![
+            Literal::new(false, make_pred(&table, "P", vec![y.clone()])),
+            Literal::new(true, make_pred(&table, "Q", vec![y])),
+        ]);
+        let clause3 = Clause::new(vec![Literal::new(false, make_pred(&table, "Q", vec![a]))]);
+
+        let mut prover = Prover::with_config(ProverConfig::default(), table);
+        assert!(prover.proof().is_none());
+        prover.add_sos(clause1);
+        prover.add_usable(clause2);
+        prover.add_usable(clause3);
+
+        let ProofResult::Proof { empty_clause_id, .. } = prover.search() else {
+            panic!("expected a proof");
+        };
+        let proof = prover.proof().expect("proof after search");
+        let conclusion = proof.conclusion().unwrap();
+        assert_eq!(conclusion.id(), empty_clause_id);
+        assert!(conclusion.clause.literals.is_empty());
+        assert_eq!(conclusion.rule, Some(InferenceRule::BinaryRes));
+        assert_eq!(proof.steps().iter().filter(|step| step.is_input()).count(), 3);
+        assert_eq!(proof.level(), 2);
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn proof_result_carries_answers() {
+        let table = SymbolTable::new();
+        let a = make_const(&table, "a");
+        let f_a = make_pred(&table, "f", vec![a]);
+        let x = make_var(0);
+        let clause1 = Clause::new(vec![Literal::new(true, make_pred(&table, "P", vec![f_a]))]);
+        let clause2 = Clause::new(vec![
+            Literal::new(false, make_pred(&table, "P", vec![x.clone()])),
+            Literal::new(true, make_pred(&table, "$Ans", vec![x])),
+        ]);
+
+        let mut prover = Prover::with_config(ProverConfig::default(), table);
+        prover.add_sos(clause1);
+        prover.add_usable(clause2);
+
+        let ProofResult::Proof { answers, .. } = prover.search() else {
+            panic!("expected a proof");
+        };
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0].format_args(prover.symbols()), vec!["f(a)".to_string()]);
+        assert_eq!(answers[0].format(prover.symbols()), "$Ans(f(a))");
+    }
+
+    #[test]
+    fn equational_proof_passes_the_proof_checker() {
+        let table = SymbolTable::new();
+        let eq = table.intern("=", 2, SymbolKind::Predicate);
+        let f = table.intern("f", 1, SymbolKind::Function);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let c = make_const(&table, "c");
+        let f_of = |t: Term| Term::application(f, vec![t]);
+        // f(a) = b, a = c, f(c) != b
+        let clause1 = Clause::new(vec![Literal::new(true, Term::application(eq, vec![f_of(a.clone()), b.clone()]))]);
+        let clause2 = Clause::new(vec![Literal::new(true, Term::application(eq, vec![a, c.clone()]))]);
+        let clause3 = Clause::new(vec![Literal::new(false, Term::application(eq, vec![f_of(c), b]))]);
+
+        let config = ProverConfig {
+            use_binary_res: false,
+            use_para_into: true,
+            use_para_from: true,
+            use_demod: true,
+            use_back_demod: true,
+            ..ProverConfig::default()
+        };
+        let mut prover = Prover::with_config(config, table);
+        prover.set_eq_symbol(eq);
+        prover.add_sos(clause1);
+        prover.add_sos(clause2);
+        prover.add_sos(clause3);
+
+        assert!(matches!(prover.search(), ProofResult::Proof { .. }));
+        assert_eq!(prover.check_proof(), Some(Ok(())));
+    }
+
+    #[test]
+    fn shared_terms_count_toward_memory_until_collected() {
+        let table = SymbolTable::new();
+        let eq = table.intern("=", 2, SymbolKind::Predicate);
+        let f = table.intern("f", 1, SymbolKind::Function);
+        let g = table.intern("g", 1, SymbolKind::Function);
+        let a = make_const(&table, "a");
+        let x = make_var(0);
+        // f(x) = g(x) rewrites the P(f(a)) resolved from Q(a)
+        let f_x = Term::application(f, vec![x.clone()]);
+        let rule = Term::application(eq, vec![f_x.clone(), Term::application(g, vec![x.clone()])]);
+        let q_a = make_pred(&table, "Q", vec![a]);
+        let q_x = make_pred(&table, "Q", vec![x.clone()]);
+        let p_fx = make_pred(&table, "P", vec![f_x]);
+
+        let mut prover = Prover::with_config(ProverConfig::default(), table);
+        prover.set_eq_symbol(eq);
+        prover.add_demodulator(Clause::new(vec![Literal::new(true, rule)])).expect("demodulator");
+        prover.add_usable(Clause::new(vec![Literal::new(false, q_x), Literal::new(true, p_fx)]));
+        prover.add_sos(Clause::new(vec![Literal::new(true, q_a)]));
+        assert!(matches!(prover.search(), ProofResult::Saturated { .. }));
+        assert!(!prover.term_bank.is_empty());
+
+        let bytes = prover.memory_bytes();
+        prover.collect_garbage();
+        assert!(prover.term_bank.is_empty());
+        assert!(prover.memory_bytes() < bytes);
+    }
+
     #[test]
     fn respects_max_given_limit() {
         let config = ProverConfig {
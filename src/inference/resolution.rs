@@ -59,6 +59,20 @@ pub fn rename_variables(clause: &Clause, offset: u16) -> Clause {
     new_clause
 }
 
+/// The first variable id above every variable of `clause`: renamed by this
+/// offset, another clause shares no variable with it.
+pub fn variable_offset(clause: &Clause) -> u16 {
+    clause.literals.iter().map(|lit| term_variable_offset(&lit.atom)).max().unwrap_or(0)
+}
+
+/// [`variable_offset`] of a single term.
+pub(crate) fn term_variable_offset(term: &Term) -> u16 {
+    match term {
+        Term::Variable { id, .. } => id.as_u16() + 1,
+        Term::Application { args, .. } => args.iter().map(term_variable_offset).max().unwrap_or(0),
+    }
+}
+
 fn rename_term_variables(term: &Term, offset: u16) -> Term {
     match term {
         Term::Variable { id, symbol } => {
@@ -154,7 +168,7 @@ pub fn all_resolvents(
     let mut results = Vec::new();
 
     // Rename variables in clause2 to avoid conflicts
-    let clause2_renamed = rename_variables(clause2, 100);
+    let clause2_renamed = rename_variables(clause2, variable_offset(clause1));
 
     for i in 0..clause1.literals.len() {
         for j in 0..clause2_renamed.literals.len() {
@@ -0,0 +1,170 @@
+//! Iterative proof shortening with hints.
+//!
+//! Veroff's hint methodology: after a proof is found, its clauses are fed
+//! back as hints and the search is run again.  Generated clauses that match
+//! a hint get the low pick weight `bsub_hint_wt`/`fsub_hint_wt`, and by
+//! default `max_weight` drops to that weight too, so the next search mostly
+//! retraces the old proof, and ancestor subsumption lets it keep shorter
+//! derivations of the same clauses.
+//! [`shorten_proof`] repeats this until the proof stops getting shorter, a
+//! round fails, or the time budget runs out, and keeps the shortest proof.
+
+use crate::inference::{ProofResult, Prover, ProverBuilder};
+use crate::parser::ForasFile;
+use std::time::{Duration, Instant};
+
+/// Settings for [`shorten_proof`].
+#[derive(Clone, Debug)]
+pub struct ShortenConfig {
+    /// Wall-clock time shared by all rounds
+    pub time_budget: Duration,
+    /// Maximum number of searches, counting the first one
+    pub max_rounds: usize,
+    /// Pick weight of clauses matching a hint; used for both
+    /// `bsub_hint_wt` and `fsub_hint_wt`
+    pub hint_wt: i32,
+    /// Lower `max_weight` to `hint_wt`, discarding clauses that match no
+    /// hint and are heavier than the hinted ones
+    pub restrict: bool,
+}
+
+impl Default for ShortenConfig {
+    fn default() -> Self {
+        Self {
+            time_budget: Duration::from_secs(60),
+            max_rounds: 10,
+            hint_wt: 1,
+            restrict: true,
+        }
+    }
+}
+
+/// The outcome of [`shorten_proof`].
+pub struct ShortenedSearch {
+    /// Prover of the round with the shortest proof, or of the first round
+    /// if no round found a proof
+    pub prover: Prover,
+    /// Search result of that round
+    pub result: ProofResult,
+    /// Proof length of each round that found a proof, in round order
+    pub lengths: Vec<usize>,
+}
+
+/// Search for a proof of `file`, then repeatedly search again with the
+/// shortest proof so far as hints, and return the round with the shortest
+/// proof.
+pub fn shorten_proof(file: &ForasFile, config: &ShortenConfig) -> Result<ShortenedSearch, String> {
+    let start = Instant::now();
+    let mut prover = ProverBuilder::new().build(file)?;
+    limit_seconds(&mut prover, config.time_budget);
+    let result = prover.search();
+
+    let mut lengths = Vec::new();
+    let Some(proof) = prover.proof() else {
+        return Ok(ShortenedSearch { prover, result, lengths });
+    };
+    lengths.push(proof.length());
+    let mut best = ShortenedSearch { prover, result, lengths: Vec::new() };
+    let mut best_length = proof.length();
+    let mut hints: Vec<_> = proof
+        .steps()
+        .iter()
+        .filter(|step| !step.is_input())
+        .map(|step| step.clause.clone())
+        .collect();
+
+    for _ in 1..config.max_rounds {
+        let Some(remaining) = config.time_budget.checked_sub(start.elapsed()) else {
+            break;
+        };
+        let mut prover = ProverBuilder::new().build(file)?;
+        limit_seconds(&mut prover, remaining);
+        let settings = prover.config_mut();
+        settings.bsub_hint_wt = config.hint_wt;
+        settings.fsub_hint_wt = config.hint_wt;
+        settings.use_ancestor_subsume = true;
+        if config.restrict {
+            settings.max_weight = settings.max_weight.min(config.hint_wt);
+        }
+        for hint in &hints {
+            prover.add_hint(hint.clone());
+        }
+
+        let result = prover.search();
+        let Some(proof) = prover.proof() else {
+            break;
+        };
+        lengths.push(proof.length());
+        if proof.length() >= best_length {
+            break;
+        }
+        best_length = proof.length();
+        hints = proof
+            .steps()
+            .iter()
+            .filter(|step| !step.is_input())
+            .map(|step| step.clause.clone())
+            .collect();
+        best = ShortenedSearch { prover, result, lengths: Vec::new() };
+    }
+
+    best.lengths = lengths;
+    Ok(best)
+}
+
+/// Cap the prover's `max_seconds` at what is left of the budget.
+fn limit_seconds(prover: &mut Prover, remaining: Duration) {
+    let seconds = remaining.as_secs().max(1);
+    let settings = prover.config_mut();
+    if settings.max_seconds == 0 || settings.max_seconds > seconds {
+        settings.max_seconds = seconds;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shorten_proof, ShortenConfig};
+    use crate::inference::ProofResult;
+    use crate::parser::Parser;
+    use std::time::Duration;
+
+    fn config() -> ShortenConfig {
+        ShortenConfig { time_budget: Duration::from_secs(10), ..ShortenConfig::default() }
+    }
+
+    #[test]
+    fn keeps_the_shortest_proof_of_all_rounds() {
+        let input = r#"
+set(auto).
+list(usable).
+x = x.
+e * x = x.
+g(x) * x = e.
+(x * y) * z = x * (y * z).
+a * e != a.
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let search = shorten_proof(&file, &config()).expect("build prover");
+
+        assert!(matches!(search.result, ProofResult::Proof { .. }));
+        assert!(search.lengths.len() >= 2, "a second, hinted round should run");
+        let shortest = search.lengths.iter().copied().min().unwrap();
+        assert_eq!(search.prover.proof().unwrap().length(), shortest);
+    }
+
+    #[test]
+    fn reports_no_lengths_without_a_proof() {
+        let input = r#"
+set(binary_res).
+list(sos).
+P(a).
+end_of_list.
+"#;
+        let file = Parser::new().parse_str(input).expect("parse");
+        let search = shorten_proof(&file, &config()).expect("build prover");
+
+        assert!(search.lengths.is_empty());
+        assert!(search.prover.proof().is_none());
+    }
+}
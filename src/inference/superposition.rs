@@ -0,0 +1,465 @@
+//! Superposition: paramodulation restricted by a term ordering.
+//!
+//! Otter's paramodulation rewrites with either side of any equality into
+//! any subterm.  Superposition keeps only the inferences a refutational
+//! completeness proof needs:
+//!
+//! - [`superpose`] rewrites with a side of a strictly maximal equality that
+//!   is not smaller than the other side, into a non-variable subterm of a
+//!   maximal literal, and within an equality only into a side that is not
+//!   smaller than the other one.
+//! - [`equality_resolvents`] drop a maximal `s!=t` whose sides unify.
+//! - [`equality_factors`] turn `s=t | s'=t'`, with `s` and `s'` unifiable
+//!   and `s=t` maximal, into `t!=t' | s'=t'`.
+//!
+//! Together with resolution and factoring on the other literals these
+//! rules are refutationally complete for clauses with equality, provided
+//! every clause is eventually given: the prover moves the input clauses
+//! of usable into sos in this mode.  Every
+//! restriction is checked on the instantiated parents, so the rules work
+//! with any [`TermOrdering`].  Literals are compared as multisets of terms:
+//! `s=t` as `{s, t}`, `s!=t` as `{s, s, t, t}`, and other atoms `P` as
+//! `{P}` or `{P, P}`.
+
+use crate::data::ordering::terms_identical;
+use crate::data::{Clause, ClauseId, Literal, SymbolId, Term, TermOrdering};
+use crate::inference::para::build_paramodulant;
+use crate::inference::resolution::{rename_variables, variable_offset};
+use crate::inference::{Paramodulant, Substitution, Unifier};
+
+/// Result of equality resolution.
+#[derive(Clone, Debug)]
+pub struct EqualityResolvent {
+    /// The resulting clause
+    pub clause: Clause,
+    pub parent_id: Option<ClauseId>,
+    /// Index of the disequality dropped
+    pub literal: usize,
+    /// The substitution used
+    pub substitution: Substitution,
+}
+
+/// Result of equality factoring.
+#[derive(Clone, Debug)]
+pub struct EqualityFactor {
+    /// The resulting clause
+    pub clause: Clause,
+    pub parent_id: Option<ClauseId>,
+    /// Index of the maximal equality, which becomes a disequality
+    pub literal: usize,
+    /// Its right side was the one unified
+    pub right: bool,
+    /// Index of the equality whose side it was unified with
+    pub other: usize,
+    /// The right side of `other` was the one unified
+    pub other_right: bool,
+    /// The substitution used
+    pub substitution: Substitution,
+}
+
+/// All superpositions from the positive equalities of `from_clause` into
+/// `into_clause`.  The from clause is renamed apart first, so a clause may
+/// superpose into itself.
+pub fn superpose(
+    from_clause: &Clause,
+    from_id: Option<ClauseId>,
+    into_clause: &Clause,
+    into_id: Option<ClauseId>,
+    eq_symbol: SymbolId,
+    ordering: &dyn TermOrdering,
+) -> Vec<Paramodulant> {
+    let mut results = Vec::new();
+    let renamed_from = rename_variables(from_clause, variable_offset(into_clause));
+
+    for (from_lit_idx, from_lit) in renamed_from.literals.iter().enumerate() {
+        let Some((left, right)) = equation(from_lit, eq_symbol).filter(|_| from_lit.sign) else {
+            continue;
+        };
+        for from_right in [false, true] {
+            let (pattern, replacement) = if from_right { (right, left) } else { (left, right) };
+
+            for (into_lit_idx, into_lit) in into_clause.literals.iter().enumerate() {
+                for (position, subterm) in rewritable_subterms(&into_lit.atom) {
+                    let mut unifier = Unifier::new();
+                    if unifier.unify(pattern, subterm).is_err() {
+                        continue;
+                    }
+                    let subst = unifier.into_substitution();
+
+                    // The side rewritten with must not be smaller
+                    if ordering.greater_or_equal(&subst.apply(replacement), &subst.apply(pattern)) {
+                        continue;
+                    }
+                    let from_instance = instance(&renamed_from, &subst);
+                    if !is_maximal(&from_instance, from_lit_idx, true, eq_symbol, ordering) {
+                        continue;
+                    }
+                    let into_instance = instance(into_clause, &subst);
+                    if !is_maximal(&into_instance, into_lit_idx, into_lit.sign, eq_symbol, ordering) {
+                        continue;
+                    }
+                    if let Some((into_left, into_right)) = equation(&into_instance[into_lit_idx], eq_symbol) {
+                        let (side, other) =
+                            if position[0] == 0 { (into_left, into_right) } else { (into_right, into_left) };
+                        if ordering.greater_or_equal(other, side) {
+                            continue;
+                        }
+                    }
+
+                    let new_atom = replace_at(&into_lit.atom, &position, replacement);
+                    results.push(build_paramodulant(
+                        &renamed_from,
+                        from_id,
+                        into_clause,
+                        into_id,
+                        (from_lit_idx, from_right),
+                        (into_lit_idx, position),
+                        new_atom,
+                        subst,
+                    ));
+                }
+            }
+        }
+    }
+
+    results
+}
+
+/// All equality resolvents of `clause`: a maximal disequality whose sides
+/// unify is dropped.
+pub fn equality_resolvents(
+    clause: &Clause,
+    parent_id: Option<ClauseId>,
+    eq_symbol: SymbolId,
+    ordering: &dyn TermOrdering,
+) -> Vec<EqualityResolvent> {
+    let mut results = Vec::new();
+
+    for (index, lit) in clause.literals.iter().enumerate() {
+        let Some((left, right)) = equation(lit, eq_symbol).filter(|_| !lit.sign) else {
+            continue;
+        };
+        let mut unifier = Unifier::new();
+        if unifier.unify(left, right).is_err() {
+            continue;
+        }
+        let subst = unifier.into_substitution();
+        let mut literals = instance(clause, &subst);
+        if !is_maximal(&literals, index, false, eq_symbol, ordering) {
+            continue;
+        }
+        literals.remove(index);
+
+        results.push(EqualityResolvent {
+            clause: derived(literals, clause, parent_id),
+            parent_id,
+            literal: index,
+            substitution: subst,
+        });
+    }
+
+    results
+}
+
+/// All equality factors of `clause`: for equalities `s=t` and `s'=t'`
+/// with `s` and `s'` unifiable, `s` not smaller than `t` and `s=t`
+/// maximal, `s=t` is replaced by `t!=t'`.
+pub fn equality_factors(
+    clause: &Clause,
+    parent_id: Option<ClauseId>,
+    eq_symbol: SymbolId,
+    ordering: &dyn TermOrdering,
+) -> Vec<EqualityFactor> {
+    let mut results = Vec::new();
+    let positive: Vec<(usize, &Term, &Term)> = clause
+        .literals
+        .iter()
+        .enumerate()
+        .filter(|(_, lit)| lit.sign)
+        .filter_map(|(index, lit)| equation(lit, eq_symbol).map(|(left, right)| (index, left, right)))
+        .collect();
+
+    for &(literal, left, right) in &positive {
+        for &(other, other_left, other_right) in &positive {
+            if other == literal {
+                continue;
+            }
+            for (right_side, (side, rest)) in [(false, (left, right)), (true, (right, left))] {
+                for (other_right_side, (other_side, other_rest)) in
+                    [(false, (other_left, other_right)), (true, (other_right, other_left))]
+                {
+                    let mut unifier = Unifier::new();
+                    if unifier.unify(side, other_side).is_err() {
+                        continue;
+                    }
+                    let subst = unifier.into_substitution();
+                    let rest = subst.apply(rest);
+                    if ordering.greater_or_equal(&rest, &subst.apply(side)) {
+                        continue;
+                    }
+                    let mut literals = instance(clause, &subst);
+                    if !is_maximal(&literals, literal, false, eq_symbol, ordering) {
+                        continue;
+                    }
+                    literals[literal] =
+                        Literal::new(false, Term::application(eq_symbol, vec![rest, subst.apply(other_rest)]));
+
+                    results.push(EqualityFactor {
+                        clause: derived(literals, clause, parent_id),
+                        parent_id,
+                        literal,
+                        right: right_side,
+                        other,
+                        other_right: other_right_side,
+                        substitution: subst,
+                    });
+                }
+            }
+        }
+    }
+
+    results
+}
+
+/// Sides of an equality literal of either sign.
+fn equation(lit: &Literal, eq_symbol: SymbolId) -> Option<(&Term, &Term)> {
+    match &lit.atom {
+        Term::Application { symbol, args } if *symbol == eq_symbol && args.len() == 2 => Some((&args[0], &args[1])),
+        _ => None,
+    }
+}
+
+fn instance(clause: &Clause, subst: &Substitution) -> Vec<Literal> {
+    clause.literals.iter().map(|lit| subst.apply_to_literal(lit)).collect()
+}
+
+/// A clause inferred from `parent` alone.
+fn derived(literals: Vec<Literal>, parent: &Clause, parent_id: Option<ClauseId>) -> Clause {
+    let mut clause = Clause::new(literals);
+    clause.proof_depth = parent.proof_depth + 1;
+    if let Some(id) = parent_id {
+        clause.add_parent(id);
+    }
+    clause
+}
+
+/// Non-variable subterms below the root of `atom`, with their paths.  The
+/// first step of a path into an equality names the side.
+fn rewritable_subterms(atom: &Term) -> Vec<(Vec<usize>, &Term)> {
+    fn walk<'t>(term: &'t Term, path: &mut Vec<usize>, out: &mut Vec<(Vec<usize>, &'t Term)>) {
+        if let Term::Application { args, .. } = term {
+            out.push((path.clone(), term));
+            for (index, arg) in args.iter().enumerate() {
+                path.push(index);
+                walk(arg, path, out);
+                path.pop();
+            }
+        }
+    }
+    let mut out = Vec::new();
+    if let Term::Application { args, .. } = atom {
+        for (index, arg) in args.iter().enumerate() {
+            walk(arg, &mut vec![index], &mut out);
+        }
+    }
+    out
+}
+
+fn replace_at(term: &Term, position: &[usize], replacement: &Term) -> Term {
+    match (term, position.split_first()) {
+        (Term::Application { symbol, args }, Some((index, rest))) => {
+            let mut args = args.clone();
+            args[*index] = replace_at(&args[*index], rest, replacement);
+            Term::Application { symbol: *symbol, args }
+        }
+        _ => replacement.clone(),
+    }
+}
+
+/// Whether no other literal of `literals` is greater than literal `index`,
+/// nor, for a `strict` maximum, identical to it.
+fn is_maximal(
+    literals: &[Literal],
+    index: usize,
+    strict: bool,
+    eq_symbol: SymbolId,
+    ordering: &dyn TermOrdering,
+) -> bool {
+    let lit = &literals[index];
+    let terms = literal_terms(lit, eq_symbol);
+    literals.iter().enumerate().all(|(other_idx, other)| {
+        other_idx == index
+            || !(strict && other.sign == lit.sign && terms_identical(&other.atom, &lit.atom)
+                || multiset_greater(&literal_terms(other, eq_symbol), &terms, ordering))
+    })
+}
+
+/// The multiset of terms a literal is compared by.
+fn literal_terms(lit: &Literal, eq_symbol: SymbolId) -> Vec<&Term> {
+    let terms = match equation(lit, eq_symbol) {
+        Some((left, right)) => vec![left, right],
+        None => vec![&lit.atom],
+    };
+    if lit.sign {
+        terms
+    } else {
+        terms.iter().flat_map(|term| [*term, *term]).collect()
+    }
+}
+
+/// The multiset extension of `ordering`: after removing the terms `m` and
+/// `n` share, something is left of `m` and each remaining term of `n` is
+/// smaller than one of `m`.
+fn multiset_greater(m: &[&Term], n: &[&Term], ordering: &dyn TermOrdering) -> bool {
+    let mut m_rest = m.to_vec();
+    let mut n_rest = Vec::new();
+    for term in n {
+        match m_rest.iter().position(|other| terms_identical(other, term)) {
+            Some(index) => {
+                m_rest.swap_remove(index);
+            }
+            None => n_rest.push(*term),
+        }
+    }
+    !m_rest.is_empty() && n_rest.iter().all(|small| m_rest.iter().any(|big| ordering.greater(big, small)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::symbol::{SymbolKind, SymbolTable};
+    use crate::data::{VariableId, LRPO};
+
+    fn make_var(id: u16) -> Term {
+        Term::variable(VariableId::new(id))
+    }
+
+    fn make_const(table: &SymbolTable, name: &str) -> Term {
+        let sym = table.intern(name, 0, SymbolKind::Constant);
+        Term::application(sym, vec![])
+    }
+
+    fn make_fun(table: &SymbolTable, name: &str, args: Vec<Term>) -> Term {
+        let sym = table.intern(name, args.len() as u8, SymbolKind::Function);
+        Term::application(sym, args)
+    }
+
+    fn make_pred(table: &SymbolTable, name: &str, args: Vec<Term>) -> Term {
+        let sym = table.intern(name, args.len() as u8, SymbolKind::Predicate);
+        Term::application(sym, args)
+    }
+
+    /// LRPO with the symbols named in `order` from highest to lowest
+    /// precedence.
+    fn lrpo(table: &SymbolTable, order: &[(&str, u8)]) -> LRPO {
+        let mut lrpo = LRPO::new();
+        for (prec, (name, arity)) in order.iter().enumerate() {
+            let kind = if *arity == 0 { SymbolKind::Constant } else { SymbolKind::Function };
+            lrpo.set_precedence(table.intern(*name, *arity, kind), prec as u32);
+        }
+        lrpo
+    }
+
+    #[test]
+    fn superposition_rewrites_only_with_the_greater_side() {
+        let table = SymbolTable::new();
+        let eq = table.intern("=", 2, SymbolKind::Function);
+        let ordering = lrpo(&table, &[("f", 1), ("a", 0), ("b", 0)]);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+
+        // From: f(x) = b, into: P(f(a)) | P(b)
+        let from = Clause::new(vec![Literal::new(
+            true,
+            Term::application(eq, vec![make_fun(&table, "f", vec![make_var(0)]), b.clone()]),
+        )]);
+        let into = Clause::new(vec![
+            Literal::new(true, make_pred(&table, "P", vec![make_fun(&table, "f", vec![a.clone()])])),
+            Literal::new(true, make_pred(&table, "Q", vec![b.clone()])),
+        ]);
+
+        // Otter paramodulates b into Q(b) too; superposition rewrites only
+        // f(a), and only if P(f(a)) is maximal
+        let results = superpose(&from, None, &into, None, eq, &ordering);
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].from_right);
+        assert_eq!(results[0].position, vec![0]);
+        assert_eq!(results[0].clause.literals[0].atom, make_pred(&table, "P", vec![b.clone()]));
+
+        // Into a literal that is not maximal: P(f(a)) | P(f(f(a)))
+        let into = Clause::new(vec![
+            Literal::new(true, make_pred(&table, "P", vec![make_fun(&table, "f", vec![a.clone()])])),
+            Literal::new(
+                true,
+                make_pred(&table, "P", vec![make_fun(&table, "f", vec![make_fun(&table, "f", vec![a.clone()])])]),
+            ),
+        ]);
+        let results = superpose(&from, None, &into, None, eq, &ordering);
+        assert!(results.iter().all(|result| result.into_literal == 1), "{:?}", results);
+        assert_eq!(results.len(), 2, "f(f(a)) and its subterm f(a)");
+
+        // Never into a variable: from f(x) = b into x = a
+        let into = Clause::new(vec![Literal::new(true, Term::application(eq, vec![make_var(0), a]))]);
+        assert!(superpose(&from, None, &into, None, eq, &ordering).is_empty());
+    }
+
+    #[test]
+    fn equality_resolution_needs_a_maximal_disequality() {
+        let table = SymbolTable::new();
+        let eq = table.intern("=", 2, SymbolKind::Function);
+        let ordering = lrpo(&table, &[("f", 1), ("a", 0)]);
+        let a = make_const(&table, "a");
+        let x = make_var(0);
+
+        // f(x) != f(a) | P(x)
+        let clause = Clause::new(vec![
+            Literal::new(
+                false,
+                Term::application(eq, vec![make_fun(&table, "f", vec![x.clone()]), make_fun(&table, "f", vec![a.clone()])]),
+            ),
+            Literal::new(true, make_pred(&table, "P", vec![x.clone()])),
+        ]);
+        let results = equality_resolvents(&clause, Some(ClauseId(3)), eq, &ordering);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].literal, 0);
+        assert_eq!(results[0].clause.literals, vec![Literal::new(true, make_pred(&table, "P", vec![a.clone()]))]);
+        assert_eq!(results[0].clause.parents.iter().copied().collect::<Vec<_>>(), vec![ClauseId(3)]);
+
+        // x != a | f(f(x)) = a: the disequality is smaller than the equality
+        let clause = Clause::new(vec![
+            Literal::new(false, Term::application(eq, vec![x.clone(), a.clone()])),
+            Literal::new(
+                true,
+                Term::application(eq, vec![make_fun(&table, "f", vec![make_fun(&table, "f", vec![x])]), a]),
+            ),
+        ]);
+        assert!(equality_resolvents(&clause, None, eq, &ordering).is_empty());
+    }
+
+    #[test]
+    fn equality_factoring_replaces_the_maximal_equality() {
+        let table = SymbolTable::new();
+        let eq = table.intern("=", 2, SymbolKind::Function);
+        let ordering = lrpo(&table, &[("f", 1), ("a", 0), ("b", 0)]);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let f = |arg: Term| make_fun(&table, "f", vec![arg]);
+
+        // f(x) = a | f(b) = b
+        let clause = Clause::new(vec![
+            Literal::new(true, Term::application(eq, vec![f(make_var(0)), a.clone()])),
+            Literal::new(true, Term::application(eq, vec![f(b.clone()), b.clone()])),
+        ]);
+        let results = equality_factors(&clause, None, eq, &ordering);
+        // f(b) = a is the greater equality after instantiation
+        assert_eq!(results.len(), 1, "{:?}", results);
+        assert_eq!((results[0].literal, results[0].right, results[0].other, results[0].other_right), (0, false, 1, false));
+        assert_eq!(
+            results[0].clause.literals,
+            vec![
+                Literal::new(false, Term::application(eq, vec![a, b.clone()])),
+                Literal::new(true, Term::application(eq, vec![f(b.clone()), b])),
+            ]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! SZS status reporting and TSTP derivation output.
+//!
+//! Maps a [`ProofResult`] onto the SZS ontology used by TPTP tools and
+//! renders a refutation as a TSTP derivation of `cnf(...)` lines, so foras
+//! can sit in pipelines built around other ATPs.
+
+use crate::data::{
+    Clause, ClauseArena, ClauseAttributeValue, Literal, SymbolTable, Term,
+};
+use crate::inference::{Proof, ProofResult};
+use std::fmt;
+use std::fmt::Write;
+
+/// SZS result status of a run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SzsStatus {
+    /// The conjecture follows from the axioms.
+    Theorem,
+    /// The clause set (no conjecture) is unsatisfiable.
+    Unsatisfiable,
+    /// Saturation showed the conjecture does not follow.
+    CounterSatisfiable,
+    /// Saturation showed the clause set (no conjecture) is satisfiable.
+    Satisfiable,
+    /// The search ended without an answer, e.g. an incomplete strategy
+    /// saturated.
+    GaveUp,
+    /// The `max_seconds` limit was reached.
+    Timeout,
+    /// Any other resource limit was reached.
+    ResourceOut,
+}
+
+impl SzsStatus {
+    /// Derive the status of a search.
+    ///
+    /// `has_conjecture` tells whether the input contained a conjecture (see
+    /// [`input_has_conjecture`]); `saturation_complete` whether the strategy
+    /// was refutationally complete, which is what lets a saturated search
+    /// claim (counter-)satisfiability instead of giving up.
+    pub fn from_result(
+        result: &ProofResult,
+        has_conjecture: bool,
+        saturation_complete: bool,
+    ) -> Self {
+        match result {
+            ProofResult::Proof { .. } if has_conjecture => SzsStatus::Theorem,
+            ProofResult::Proof { .. } => SzsStatus::Unsatisfiable,
+            ProofResult::Saturated { .. } if !saturation_complete => {
+                SzsStatus::GaveUp
+            }
+            ProofResult::Saturated { .. } if has_conjecture => {
+                SzsStatus::CounterSatisfiable
+            }
+            ProofResult::Saturated { .. } => SzsStatus::Satisfiable,
+            ProofResult::ResourceLimit { limit_type, .. }
+                if limit_type == "max_seconds" =>
+            {
+                SzsStatus::Timeout
+            }
+            ProofResult::ResourceLimit { .. } => SzsStatus::ResourceOut,
+        }
+    }
+
+    /// The SZS name of the status.
+    pub fn name(self) -> &'static str {
+        match self {
+            SzsStatus::Theorem => "Theorem",
+            SzsStatus::Unsatisfiable => "Unsatisfiable",
+            SzsStatus::CounterSatisfiable => "CounterSatisfiable",
+            SzsStatus::Satisfiable => "Satisfiable",
+            SzsStatus::GaveUp => "GaveUp",
+            SzsStatus::Timeout => "Timeout",
+            SzsStatus::ResourceOut => "ResourceOut",
+        }
+    }
+
+    /// The standard `% SZS status <Status> for <problem>` line.
+    pub fn status_line(self, problem: &str) -> String {
+        format!("% SZS status {} for {}", self.name(), problem)
+    }
+}
+
+impl fmt::Display for SzsStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Whether any input clause came from a TPTP `conjecture` (the TPTP reader
+/// tags those clauses with `# role(conjecture)`).
+pub fn input_has_conjecture(arena: &ClauseArena) -> bool {
+    arena.iter().any(|clause| text_attribute(clause, "role") == Some("conjecture"))
+}
+
+/// Render a proof as a TSTP CNF refutation, wrapped in the SZS output
+/// start/end markers.
+///
+/// Input clauses cite their source as `file('<problem>', <label>)`; derived
+/// clauses carry `inference(<rule>, [status(thm)], [<parents>])`.
+pub fn tstp_derivation(
+    proof: &Proof,
+    symbols: &SymbolTable,
+    problem: &str,
+) -> String {
+    let mut output = String::new();
+    writeln!(&mut output, "% SZS output start CNFRefutation for {}", problem)
+        .unwrap();
+    for step in proof.steps() {
+        let clause = &step.clause;
+        let (role, source) = if step.is_input() {
+            let role = match text_attribute(clause, "role") {
+                Some("conjecture") => "negated_conjecture",
+                Some(role) => role,
+                None if text_attribute(clause, "list") == Some("sos") => {
+                    "negated_conjecture"
+                }
+                None => "axiom",
+            };
+            let source = match text_attribute(clause, "label") {
+                Some(label) => format!("file({}, {})", quote(problem), label),
+                None => format!("file({})", quote(problem)),
+            };
+            (role, source)
+        } else {
+            let rule = step.rule.map_or("unknown", |rule| rule.name());
+            let parent_names = step
+                .parents
+                .iter()
+                .map(|parent| format!("c_{}", parent.0))
+                .collect::<Vec<_>>()
+                .join(", ");
+            ("plain", format!("inference({}, [status(thm)], [{}])", rule, parent_names))
+        };
+        writeln!(
+            &mut output,
+            "cnf(c_{}, {}, {}, {}).",
+            step.id().0,
+            role,
+            tstp_clause(clause, symbols),
+            source
+        )
+        .unwrap();
+    }
+    writeln!(&mut output, "% SZS output end CNFRefutation for {}", problem)
+        .unwrap();
+    output
+}
+
+fn text_attribute<'a>(clause: &'a Clause, name: &str) -> Option<&'a str> {
+    clause.attributes.iter().find_map(|attr| match &attr.value {
+        ClauseAttributeValue::Text(value) if attr.name == name => {
+            Some(value.as_str())
+        }
+        _ => None,
+    })
+}
+
+/// A clause in TSTP CNF syntax; the empty clause is `$false`.
+fn tstp_clause(clause: &Clause, symbols: &SymbolTable) -> String {
+    if clause.literals.is_empty() {
+        return "$false".to_string();
+    }
+    clause
+        .literals
+        .iter()
+        .map(|literal| tstp_literal(literal, symbols))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn tstp_literal(literal: &Literal, symbols: &SymbolTable) -> String {
+    if let Term::Application { symbol, args } = &literal.atom {
+        let is_equality = args.len() == 2
+            && symbols.get(*symbol).is_some_and(|sym| sym.name == "=");
+        if is_equality {
+            let op = if literal.sign { "=" } else { "!=" };
+            return format!(
+                "{} {} {}",
+                tstp_term(&args[0], symbols),
+                op,
+                tstp_term(&args[1], symbols)
+            );
+        }
+    }
+    let sign = if literal.sign { "" } else { "~" };
+    format!("{}{}", sign, tstp_term(&literal.atom, symbols))
+}
+
+fn tstp_term(term: &Term, symbols: &SymbolTable) -> String {
+    match term {
+        Term::Variable { id, .. } => format!("X{}", id.0),
+        Term::Application { symbol, args } => {
+            let name = symbols
+                .get(*symbol)
+                .map_or_else(|| format!("s{}", symbol.as_raw()), |sym| sym.name);
+            let name = if is_tptp_word(&name) { name } else { quote(&name) };
+            if args.is_empty() {
+                name
+            } else {
+                let args = args
+                    .iter()
+                    .map(|arg| tstp_term(arg, symbols))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{}({})", name, args)
+            }
+        }
+    }
+}
+
+/// Lower words and integers can be written bare; anything else is quoted.
+fn is_tptp_word(name: &str) -> bool {
+    let lower_word = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let integer = !name.is_empty() && name.chars().all(|c| c.is_ascii_digit());
+    lower_word || integer
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SzsStatus, input_has_conjecture, tstp_derivation};
+    use crate::data::{
+        Clause, ClauseArena, ClauseAttribute, ClauseAttributeValue,
+        InferenceRule, Literal, SymbolKind, SymbolTable, Term, VariableId,
+    };
+    use crate::inference::{Proof, ProofResult};
+
+    fn limit(limit_type: &str) -> ProofResult {
+        ProofResult::ResourceLimit {
+            clauses_generated: 0,
+            clauses_kept: 0,
+            limit_type: limit_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn statuses_follow_result_and_conjecture() {
+        let proof = ProofResult::Proof {
+            empty_clause_id: crate::data::ClauseId(1),
+            clauses_generated: 0,
+            clauses_kept: 0,
+            answers: Vec::new(),
+            proofs: vec![crate::data::ClauseId(1)],
+        };
+        let saturated =
+            ProofResult::Saturated { clauses_generated: 0, clauses_kept: 0 };
+        assert_eq!(SzsStatus::from_result(&proof, true, false), SzsStatus::Theorem);
+        assert_eq!(
+            SzsStatus::from_result(&proof, false, false),
+            SzsStatus::Unsatisfiable
+        );
+        assert_eq!(
+            SzsStatus::from_result(&saturated, true, true),
+            SzsStatus::CounterSatisfiable
+        );
+        assert_eq!(
+            SzsStatus::from_result(&saturated, false, true),
+            SzsStatus::Satisfiable
+        );
+        assert_eq!(SzsStatus::from_result(&saturated, true, false), SzsStatus::GaveUp);
+        assert_eq!(
+            SzsStatus::from_result(&limit("max_seconds"), true, true),
+            SzsStatus::Timeout
+        );
+        assert_eq!(
+            SzsStatus::from_result(&limit("max_given"), true, true),
+            SzsStatus::ResourceOut
+        );
+        assert_eq!(
+            SzsStatus::Theorem.status_line("PUZ001+1"),
+            "% SZS status Theorem for PUZ001+1"
+        );
+    }
+
+    #[test]
+    fn derivation_lists_proof_clauses() {
+        let symbols = SymbolTable::new();
+        let p = symbols.intern("p", 1, SymbolKind::Predicate);
+        let a = symbols.intern("a", 0, SymbolKind::Constant);
+        let x = Term::variable(VariableId::new(0));
+        let mut arena = ClauseArena::new();
+
+        let mut axiom = Clause::new(vec![Literal::new(
+            true,
+            Term::application(p, vec![x]),
+        )]);
+        axiom.add_attribute(ClauseAttribute::new(
+            "label",
+            ClauseAttributeValue::Text("ax".into()),
+        ));
+        let axiom_id = arena.insert(axiom);
+        let mut goal = Clause::new(vec![Literal::new(
+            false,
+            Term::application(p, vec![Term::application(a, vec![])]),
+        )]);
+        goal.add_attribute(ClauseAttribute::new(
+            "role",
+            ClauseAttributeValue::Text("conjecture".into()),
+        ));
+        let goal_id = arena.insert(goal);
+        // An unrelated clause must not appear in the derivation.
+        arena.insert(Clause::new(vec![]));
+        let mut empty = Clause::new(vec![]).with_rule(InferenceRule::BinaryRes);
+        empty.add_parent(goal_id);
+        empty.add_parent(axiom_id);
+        empty.add_parent(axiom_id);
+        let empty_id = arena.insert(empty);
+
+        assert!(input_has_conjecture(&arena));
+        let proof = Proof::from_arena(&arena, empty_id).unwrap();
+        let derivation = tstp_derivation(&proof, &symbols, "t.p");
+        let lines: Vec<&str> = derivation.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "% SZS output start CNFRefutation for t.p",
+                "cnf(c_1, axiom, p(X0), file('t.p', ax)).",
+                "cnf(c_2, negated_conjecture, ~p(a), file('t.p')).",
+                "cnf(c_4, plain, $false, inference(binary, [status(thm)], [c_2, c_1])).",
+                "% SZS output end CNFRefutation for t.p",
+            ]
+        );
+    }
+
+    #[test]
+    fn non_tptp_names_are_quoted() {
+        let symbols = SymbolTable::new();
+        let eq = symbols.intern("=", 2, SymbolKind::Predicate);
+        let big = symbols.intern("P", 0, SymbolKind::Constant);
+        let zero = symbols.intern("0", 0, SymbolKind::Constant);
+        let mut arena = ClauseArena::new();
+        let clause = Clause::new(vec![Literal::new(
+            false,
+            Term::application(
+                eq,
+                vec![Term::application(big, vec![]), Term::application(zero, vec![])],
+            ),
+        )]);
+        let id = arena.insert(clause);
+        let proof = Proof::from_arena(&arena, id).unwrap();
+        let derivation = tstp_derivation(&proof, &symbols, "it's");
+        assert!(derivation.contains("cnf(c_1, axiom, 'P' != 0, file('it\\'s'))."));
+    }
+}
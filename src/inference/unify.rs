@@ -2,7 +2,7 @@
 //!
 //! Implements the standard Robinson unification algorithm with occurs check.
 
-use crate::data::{Literal, Term, VariableId};
+use crate::data::{Literal, SharedTerm, Term, TermBank, TermId, VariableId};
 use std::collections::HashMap;
 use std::fmt;
 
@@ -76,6 +76,19 @@ impl Substitution {
         }
     }
 
+    /// Apply a matching substitution to `term`: each bound variable is
+    /// replaced by its binding once, without following the bindings of the
+    /// variables in it, which belong to the matched term rather than the
+    /// pattern and may share its ids.
+    pub fn instantiate(&self, term: &Term) -> Term {
+        match term {
+            Term::Variable { id, .. } => self.lookup(*id).cloned().unwrap_or_else(|| term.clone()),
+            Term::Application { symbol, args } => {
+                Term::Application { symbol: *symbol, args: args.iter().map(|arg| self.instantiate(arg)).collect() }
+            }
+        }
+    }
+
     /// Apply this substitution to a literal.
     pub fn apply_to_literal(&self, lit: &Literal) -> Literal {
         Literal::new(lit.sign, self.apply(&lit.atom)).with_target(lit.target)
@@ -285,6 +298,76 @@ pub fn unify(t1: &Term, t2: &Term) -> Result<Substitution, UnificationError> {
     Ok(unifier.into_substitution())
 }
 
+/// A substitution of shared terms, as forward demodulation matches them:
+/// variables mapped to terms of a [`TermBank`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SharedSubstitution {
+    bindings: HashMap<VariableId, TermId>,
+}
+
+impl SharedSubstitution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind(&mut self, var: VariableId, term: TermId) {
+        self.bindings.insert(var, term);
+    }
+
+    pub fn lookup(&self, var: VariableId) -> Option<TermId> {
+        self.bindings.get(&var).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// [`Substitution::instantiate`] for shared terms.
+    pub fn instantiate(&self, bank: &mut TermBank, term: TermId) -> TermId {
+        if bank.is_ground(term) || self.is_empty() {
+            return term;
+        }
+        match bank.get(term).clone() {
+            SharedTerm::Variable { id, .. } => self.lookup(id).unwrap_or(term),
+            SharedTerm::Application { symbol, args } => {
+                let new_args: Vec<TermId> = args.iter().map(|arg| self.instantiate(bank, *arg)).collect();
+                if *new_args == *args {
+                    term
+                } else {
+                    bank.application(symbol, new_args)
+                }
+            }
+        }
+    }
+
+}
+
+/// One-way matching of shared terms: a substitution for the variables of
+/// `pattern` making it `term`, extending `subst`.
+pub fn match_shared(bank: &TermBank, pattern: TermId, term: TermId, subst: &mut SharedSubstitution) -> bool {
+    if bank.is_ground(pattern) {
+        return pattern == term;
+    }
+    match bank.get(pattern) {
+        SharedTerm::Variable { id, .. } => match subst.lookup(*id) {
+            Some(bound) => bound == term,
+            None => {
+                subst.bind(*id, term);
+                true
+            }
+        },
+        SharedTerm::Application { symbol, args } => {
+            bank.symbol(term) == Some(*symbol)
+                && bank.args(term).len() == args.len()
+                && args.iter().zip(bank.args(term)).all(|(p, t)| match_shared(bank, *p, *t, subst))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,4 +547,43 @@ mod tests {
         assert_eq!(composed.apply(&x), a);
         assert_eq!(composed.apply(&y), a);
     }
+
+    #[test]
+    fn instantiate_does_not_follow_bindings() {
+        let table = SymbolTable::new();
+        let x = make_var(0);
+        let gx = make_app(&table, "g", vec![x.clone()]);
+        let mut matcher = Substitution::new();
+        matcher.bind(VariableId::new(0), gx.clone());
+
+        let fx = make_app(&table, "f", vec![x.clone()]);
+        assert_eq!(matcher.instantiate(&fx), make_app(&table, "f", vec![gx]));
+    }
+
+    #[test]
+    fn shared_terms_match() {
+        let table = SymbolTable::new();
+        let x = make_var(0);
+        let y = make_var(1);
+        let a = make_const(&table, "a");
+        let b = make_const(&table, "b");
+        let mut bank = TermBank::new();
+        let mut shared = |term: Term| bank.intern(&term);
+        let fxy = shared(make_app(&table, "f", vec![x.clone(), make_app(&table, "g", vec![y.clone()])]));
+        let fab = shared(make_app(&table, "f", vec![a.clone(), make_app(&table, "g", vec![b.clone()])]));
+        let faa = shared(make_app(&table, "f", vec![a.clone(), make_app(&table, "g", vec![a.clone()])]));
+        let fxx = shared(make_app(&table, "f", vec![x.clone(), make_app(&table, "g", vec![x.clone()])]));
+        let gx = shared(make_app(&table, "g", vec![x.clone()]));
+        let x = shared(x);
+
+        let mut matcher = SharedSubstitution::new();
+        assert!(match_shared(&bank, fxx, faa, &mut matcher));
+        assert!(!match_shared(&bank, fxx, fab, &mut SharedSubstitution::new()));
+        assert!(!match_shared(&bank, fab, fxy, &mut SharedSubstitution::new()), "only pattern variables bind");
+
+        // x matched against g(x): the x in the binding is the term's own
+        let mut matcher = SharedSubstitution::new();
+        assert!(match_shared(&bank, x, gx, &mut matcher));
+        assert_eq!(matcher.instantiate(&mut bank, x), gx);
+    }
 }
@@ -7,14 +7,16 @@
 //! Example: Given clause `P(x) | Q(x)` and unit clause `~P(a)`,
 //! unit deletion produces `Q(a)` by deleting the `P(x)` literal.
 
-use crate::data::{Clause, ClauseId};
-use crate::inference::{apply_to_literal, rename_variables, unify};
+use crate::data::{Clause, ClauseId, Simplification};
+use crate::inference::{apply_to_literal, rename_variables, unify, variable_offset};
 
 /// Result of unit deletion on a clause.
 #[derive(Debug, Clone)]
 pub struct UnitDeleted {
     pub clause: Clause,
     pub parents: Vec<ClauseId>,
+    /// Units that deleted a literal, in the order they were applied
+    pub units: Vec<ClauseId>,
 }
 
 /// Attempt to delete literals from a clause using unit clauses.
@@ -49,6 +51,7 @@ pub fn unit_delete(
     if let Some(id) = clause_id {
         parents.push(id);
     }
+    let mut used_units = Vec::new();
     let mut deletions_made = false;
 
     // Keep trying to delete literals until no more can be deleted
@@ -62,62 +65,49 @@ pub fn unit_delete(
                 continue;
             }
 
-            let unit_lit = &unit.literals[0];
-
-            // Try to find a literal in current clause with opposite sign
-            let mut new_literals = Vec::new();
-            let mut found_match = false;
-            let mut substitution = None;
-
-            for lit in &current.literals {
-                // Check if opposite sign and atoms unify
-                if lit.sign != unit_lit.sign {
-                    if let Ok(subst) = unify(&lit.atom, &unit_lit.atom) {
-                        // Found a match - delete this literal
-                        found_match = true;
-                        substitution = Some(subst);
-                        deleted_this_round = true;
-                        deletions_made = true;
-
-                        // Add unit clause to parents
-                        if let Some(unit_id) = unit_ids.get(idx).and_then(|id| *id) {
-                            parents.push(unit_id);
-                        }
-                        
-                        // Update proof depth
-                        current.proof_depth = std::cmp::max(current.proof_depth, unit.proof_depth);
-
-                        // Don't add this literal to new_literals (delete it)
-                        continue;
-                    }
-                }
-
-                // Keep this literal
-                new_literals.push(lit.clone());
-            }
+            // Rename the unit apart from the clause before unifying
+            let renamed_unit = rename_variables(unit, variable_offset(&current));
+            let unit_lit = &renamed_unit.literals[0];
 
-            // If we deleted a literal, apply substitution to remaining literals
-            if found_match && !new_literals.is_empty() {
-                if let Some(ref subst) = substitution {
-                    new_literals = new_literals
-                        .iter()
-                        .map(|lit| apply_to_literal(subst, lit))
-                        .collect();
-                }
-
-                // Update current clause with new literals
-                current = Clause {
-                    literals: new_literals,
-                    ..current
-                };
-
-                // Break to restart from the beginning with the simplified clause
-                break;
-            } else if found_match && new_literals.is_empty() {
-                // Deleted all literals - this shouldn't happen with unit deletion
-                // but if it does, return a single-literal false clause
-                return None;
+            // Delete the first literal with opposite sign whose atom unifies
+            let position = current.literals.iter().position(|lit| {
+                lit.sign != unit_lit.sign && unify(&lit.atom, &unit_lit.atom).is_ok()
+            });
+            let Some(position) = position else {
+                continue;
+            };
+            if current.literals.len() == 1 {
+                // Deleting the last literal is unit conflict, not unit deletion
+                continue;
+            }
+            let subst = match unify(&current.literals[position].atom, &unit_lit.atom) {
+                Ok(subst) => subst,
+                Err(_) => continue,
+            };
+
+            deleted_this_round = true;
+            deletions_made = true;
+            if let Some(unit_id) = unit_ids.get(idx).and_then(|id| *id) {
+                parents.push(unit_id);
+                used_units.push(unit_id);
             }
+            current.proof_depth = std::cmp::max(current.proof_depth, unit.proof_depth);
+
+            // Apply the unifier to the remaining literals
+            let new_literals = current
+                .literals
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != position)
+                .map(|(_, lit)| apply_to_literal(&subst, lit))
+                .collect();
+            current = Clause {
+                literals: new_literals,
+                ..current
+            };
+
+            // Restart from the beginning with the simplified clause
+            break;
         }
 
         // If no deletions this round, we're done
@@ -127,15 +117,20 @@ pub fn unit_delete(
     }
 
     if deletions_made {
-        // Rename variables to avoid conflicts
-        let renamed = rename_variables(&current, 0);
-
-        // Debug: check parents
-        eprintln!("UNIT_DEL: Returning clause with parents: {:?}", parents);
+        // Rename variables to avoid conflicts, keeping the derivation
+        let mut renamed = rename_variables(&current, 0);
+        renamed.parents = clause.parents.clone();
+        renamed.rule = clause.rule;
+        renamed.justification = clause.justification.clone();
+        if let Some(id) = clause_id {
+            renamed.add_parent(id);
+        }
+        renamed.add_simplification(Simplification::UnitDel(used_units.clone()));
 
         Some(UnitDeleted {
             clause: renamed,
             parents,
+            units: used_units,
         })
     } else {
         None
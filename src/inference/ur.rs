@@ -15,7 +15,7 @@
 //!   Resolvent:   R(a)
 
 use crate::data::{Clause, ClauseId, Literal, Term, VariableId};
-use crate::inference::{Substitution, Unifier};
+use crate::inference::{variable_offset, Substitution, Unifier};
 
 /// Result of a UR-resolution step.
 #[derive(Clone, Debug)]
@@ -26,6 +26,9 @@ pub struct URResolvent {
     pub nucleus_id: Option<ClauseId>,
     /// The satellite clause IDs used (in order matching nucleus literals)
     pub satellite_ids: Vec<Option<ClauseId>>,
+    /// `(nucleus literal, satellite literal)` resolved with each satellite,
+    /// in the order of `satellite_ids`
+    pub clashes: Vec<(usize, usize)>,
     /// The combined substitution
     pub substitution: Substitution,
 }
@@ -135,7 +138,7 @@ fn try_ur_resolve_keeping(
     // Start with an empty substitution
     let mut current_subst = Substitution::new();
     let mut used_satellites = Vec::new();
-    let mut var_offset = 100; // Offset for renaming satellite variables
+    let mut var_offset = variable_offset(nucleus); // Offset for renaming satellite variables
     let mut max_parent_depth = nucleus.proof_depth;
 
     // Try to resolve each literal in resolve_literals
@@ -158,7 +161,7 @@ fn try_ur_resolve_keeping(
 
             // Rename satellite variables to avoid conflicts
             let renamed_sat_lit = rename_literal(sat_lit, var_offset);
-            var_offset += 100;
+            var_offset += variable_offset(satellite);
 
             // Try to unify
             let mut unifier = Unifier::new();
@@ -209,6 +212,7 @@ fn try_ur_resolve_keeping(
         clause,
         nucleus_id,
         satellite_ids: result_satellite_ids,
+        clashes: resolve_literals.iter().map(|(idx, _)| (*idx, 0)).collect(),
         substitution: current_subst,
     })
 }
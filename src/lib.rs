@@ -10,18 +10,19 @@ pub mod inference;
 pub mod parser;
 pub mod regression;
 
-pub use config::{Flag, FlagSet, ParameterSet, ParameterValue, Statistics};
+pub use config::{Flag, FlagSet, OptionError, ParameterSet, ParameterValue, Statistics};
 pub use data::{
-    Clause, ClauseArena, ClauseAttribute, ClauseAttributeValue, ClauseId,
-    ClauseList, Context, ContextStatus, ImdBfs, ImdNode, ImdNodeKind, IsNode,
-    Literal, MAX_VARS, ParentList, Symbol, SymbolId, SymbolKind, SymbolTable,
-    Term, TermKind, VariableId,
+    Clause, ClauseArena, ClauseAttribute, ClauseAttributeValue, ClauseId, ClauseState,
+    ClauseList, Context, ContextStatus, DiscriminationTree, FingerprintIndex, ImdBfs, ImdNode, ImdNodeKind, IsNode,
+    PartnerIndex, SubsumptionIndex,
+    InferenceRule, Literal, ParentList, Symbol, SymbolId, SymbolKind, SymbolTable,
+    SharedTerm, Term, TermBank, TermId, TermKind, VariableId,
 };
-pub use parser::{ListSection, ForasFile, ParseError, Parser};
+pub use parser::{ListSection, ForasFile, ParseError, Parser, TptpParser};
 pub use inference::{
     all_resolvents, apply_to_clause, apply_to_literal, binary_resolve,
-    rename_variables, ProofResult, Prover, ProverBuilder, Resolvent, Substitution,
-    UnificationError, Unifier,
+    rename_variables, variable_offset, Answer, ProofResult, Prover, ProverBuilder, Resolvent, SharedSubstitution, Substitution,
+    SzsStatus, UnificationError, Unifier,
 };
 pub use regression::{
     ExampleCase, ExampleSuite, ProverMetrics, RegressionExecutor,
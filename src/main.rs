@@ -3,7 +3,13 @@
 //! This is the main CLI entry point that mirrors the original Foras 3.3
 //! command-line interface while using the Rust-based prover engine.
 #![forbid(unsafe_code)]
-use foras::{ExampleSuite, Parser, ProverBuilder, RegressionExecutor};
+use foras::{
+    ExampleSuite, Parser, ProverBuilder, RegressionExecutor, SzsStatus,
+    TptpParser,
+};
+use foras::inference::{
+    json_report, proof_dot, shorten_proof, DotOptions, OutputFormatter, ShortenConfig,
+};
 use std::env;
 use std::fs;
 use std::io::{self, Read};
@@ -24,9 +30,21 @@ fn print_usage(program: &str) {
     eprintln!("Options:");
     eprintln!("  -h, --help       Show this help message");
     eprintln!("  -v, --version    Show version information");
+    eprintln!("  --list-options   List the supported flags and parameters");
     eprintln!("  --regression     Run regression tests");
+    eprintln!("  --tptp           Read the input as a TPTP problem (CNF/FOF)");
+    eprintln!("  --tstp           Print a found proof as a TSTP derivation");
+    eprintln!("  --dot=FILE       Write a found proof as a GraphViz graph to FILE");
+    eprintln!("  --dot-kept       Include kept clauses outside the proof in the graph");
+    eprintln!("  --output FORMAT  Print the result as text (default) or json");
+    eprintln!("  --shorten=SECS   Rerun with the proof as hints to shorten it, for SECS seconds");
     eprintln!();
     eprintln!("If no input file is provided, reads from stdin.");
+    eprintln!("Files ending in .p, .ax or .tptp are read as TPTP problems.");
+    eprintln!();
+    eprintln!("Exit status: 0 when a proof is found, 3 when a limit is reached, 4 when");
+    eprintln!("the search saturates, 2 for invalid options or input, 1 for I/O errors");
+    eprintln!("and failed regression runs.");
 }
 
 fn run_regression() -> io::Result<()> {
@@ -62,54 +80,179 @@ fn run_regression() -> io::Result<()> {
     Ok(())
 }
 
-fn run_prover(input_path: Option<&str>) -> io::Result<()> {
-    let parser = Parser::new();
+/// Whether `path` looks like a TPTP problem or axiom file.
+fn is_tptp_path(path: &str) -> bool {
+    matches!(
+        std::path::Path::new(path).extension().and_then(|ext| ext.to_str()),
+        Some("p" | "ax" | "tptp")
+    )
+}
+
+/// Problem name used in SZS lines: the input file's stem, or `stdin`.
+fn problem_name(input_path: Option<&str>) -> String {
+    input_path
+        .and_then(|path| std::path::Path::new(path).file_stem())
+        .map_or_else(|| "stdin".to_string(), |stem| stem.to_string_lossy().into_owned())
+}
 
-    let input = if let Some(path) = input_path {
-        fs::read_to_string(path)?
+/// Where and how to write the proof graph requested with `--dot`.
+struct DotRequest<'a> {
+    path: &'a str,
+    options: DotOptions,
+}
+
+/// Command-line options that shape a prover run.
+#[derive(Default)]
+struct RunOptions<'a> {
+    tptp: bool,
+    tstp: bool,
+    /// Print one JSON document instead of the text report
+    json: bool,
+    dot: Option<DotRequest<'a>>,
+    shorten: Option<ShortenConfig>,
+}
+
+/// Exit status for a search result: 0 for a proof, 3 when a limit was
+/// reached, 4 when the search saturated. Used for both the text and the
+/// JSON report, and listed in the usage message.
+fn exit_code(result: &foras::ProofResult) -> i32 {
+    match result {
+        foras::ProofResult::Proof { .. } => 0,
+        foras::ProofResult::ResourceLimit { .. } => 3,
+        foras::ProofResult::Saturated { .. } => 4,
+    }
+}
+
+fn run_prover(input_path: Option<&str>, options: RunOptions) -> io::Result<()> {
+    let parsed = if options.tptp || input_path.is_some_and(is_tptp_path) {
+        // Read from the file itself so includes resolve relative to it.
+        let parser = TptpParser::new();
+        match input_path {
+            Some(path) => parser.parse_file(path),
+            None => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                parser.parse_str(&buffer)
+            }
+        }
     } else {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        buffer
+        let input = if let Some(path) = input_path {
+            fs::read_to_string(path)?
+        } else {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        };
+        Parser::new().parse_str(&input)
     };
 
-    match parser.parse_str(&input) {
+    match parsed {
         Ok(foras_file) => {
-            println!("Parsed successfully:");
-            println!("  Lists: {}", foras_file.lists.len());
-            println!("  Commands: {}", foras_file.commands.len());
-            println!();
-
-            // Build and run the prover
-            match ProverBuilder::new().build(&foras_file) {
-                Ok(mut prover) => {
-                    println!("Running prover...");
+            if !options.json {
+                println!("Parsed successfully:");
+                println!("  Lists: {}", foras_file.lists.len());
+                println!("  Commands: {}", foras_file.commands.len());
+                println!();
+            }
+
+            // Build and run the prover, once or over shortening rounds
+            let searched = match &options.shorten {
+                Some(config) => shorten_proof(&foras_file, config).map(|search| {
+                    if !options.json {
+                        println!("Shortening proof...");
+                        let lengths: Vec<String> =
+                            search.lengths.iter().map(usize::to_string).collect();
+                        println!("Proof lengths by round: {}", lengths.join(", "));
+                    }
+                    (search.prover, search.result)
+                }),
+                None => ProverBuilder::new().build(&foras_file).map(|mut prover| {
+                    if !options.json {
+                        println!("Running prover...");
+                    }
                     let result = prover.search();
+                    (prover, result)
+                }),
+            };
+            match searched {
+                Ok((prover, result)) if options.json => {
+                    println!("{}", json_report(&prover, &result));
+                    process::exit(exit_code(&result));
+                }
+                Ok((prover, result)) => {
                     println!();
 
-                    match result {
-                        foras::ProofResult::Proof { clauses_generated, clauses_kept, .. } => {
+                    let problem = problem_name(input_path);
+                    let status = SzsStatus::from_result(
+                        &result,
+                        foras::inference::input_has_conjecture(prover.arena()),
+                        prover.saturation_is_complete(),
+                    );
+                    println!("{}", status.status_line(&problem));
+                    if let Some(proof) = prover.proof() {
+                        let mut formatter = OutputFormatter::new();
+                        formatter.write_proof(&proof, prover.symbols());
+                        for further in prover.proofs().iter().skip(1) {
+                            formatter.write_end_of_proof();
+                            formatter.write_proof(further, prover.symbols());
+                        }
+                        formatter.write_result(&result);
+                        if prover.config().build_proof_object {
+                            match prover.proof_object() {
+                                Some(Ok(object)) => {
+                                    formatter.write_proof_object(&object, prover.symbols())
+                                }
+                                Some(Err(err)) => {
+                                    eprintln!("Proof object not built: {}", err)
+                                }
+                                None => {}
+                            }
+                        }
+                        print!("{}", formatter.output());
+                        if options.tstp {
+                            println!();
+                            print!(
+                                "{}",
+                                foras::inference::tstp_derivation(
+                                    &proof,
+                                    prover.symbols(),
+                                    &problem,
+                                )
+                            );
+                        }
+                        if let Some(dot) = &options.dot {
+                            fs::write(
+                                dot.path,
+                                proof_dot(&proof, prover.arena(), prover.symbols(), &dot.options),
+                            )?;
+                        }
+                    }
+                    println!();
+
+                    match &result {
+                        foras::ProofResult::Proof { clauses_generated, clauses_kept, answers, .. } => {
                             println!("PROOF FOUND");
+                            for answer in answers {
+                                println!("  Answer: {}", answer.format(prover.symbols()));
+                            }
                             println!("  Given: {}", prover.stats().2);
                             println!("  Generated: {}", clauses_generated);
                             println!("  Kept: {}", clauses_kept);
-                            process::exit(0);
                         }
                         foras::ProofResult::ResourceLimit { clauses_generated, clauses_kept, limit_type } => {
                             println!("RESOURCE LIMIT REACHED: {}", limit_type);
                             println!("  Given: {}", prover.stats().2);
                             println!("  Generated: {}", clauses_generated);
                             println!("  Kept: {}", clauses_kept);
-                            process::exit(3);
                         }
                         foras::ProofResult::Saturated { clauses_generated, clauses_kept } => {
                             println!("SEARCH SATURATED (no proof found)");
                             println!("  Given: {}", prover.stats().2);
                             println!("  Generated: {}", clauses_generated);
                             println!("  Kept: {}", clauses_kept);
-                            process::exit(4);
                         }
                     }
+                    process::exit(exit_code(&result));
                 }
                 Err(err) => {
                     eprintln!("Build error: {}", err);
@@ -124,6 +267,36 @@ fn run_prover(input_path: Option<&str>) -> io::Result<()> {
     }
 }
 
+/// Options that take their value as the next argument.
+const VALUE_OPTIONS: &[&str] = &["--output"];
+
+/// The value of `name`, given as `name=value` or `name value`.
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg == name {
+            return rest.next().map(String::as_str);
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|tail| tail.strip_prefix('=')) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// The first argument that is neither an option nor an option's value.
+fn input_file(args: &[String]) -> Option<&str> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            rest.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     let program = args[0].as_str();
@@ -138,6 +311,10 @@ fn main() {
                 println!("{}", FORAS_VERSION);
                 return;
             }
+            "--list-options" => {
+                print!("{}", foras::config::options_table());
+                return;
+            }
             "--regression" => {
                 if let Err(err) = run_regression() {
                     eprintln!("Regression error: {}", err);
@@ -149,15 +326,45 @@ fn main() {
         }
     }
 
-    print_banner();
+    let json = match option_value(&args, "--output") {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(format) => {
+            eprintln!("Unknown output format: {} (expected text or json)", format);
+            process::exit(2);
+        }
+    };
+    if !json {
+        print_banner();
+    }
 
-    let input_file = if args.len() > 1 && !args[1].starts_with('-') {
-        Some(args[1].as_str())
-    } else {
-        None
+    let tptp = args.iter().skip(1).any(|arg| arg == "--tptp");
+    let tstp = args.iter().skip(1).any(|arg| arg == "--tstp");
+    let dot = args.iter().skip(1).find_map(|arg| arg.strip_prefix("--dot=")).map(|path| {
+        DotRequest {
+            path,
+            options: DotOptions {
+                include_kept: args.iter().skip(1).any(|arg| arg == "--dot-kept"),
+            },
+        }
+    });
+    let shorten = match args.iter().skip(1).find_map(|arg| arg.strip_prefix("--shorten=")) {
+        Some(seconds) => match seconds.parse() {
+            Ok(seconds) => Some(ShortenConfig {
+                time_budget: std::time::Duration::from_secs(seconds),
+                ..ShortenConfig::default()
+            }),
+            Err(_) => {
+                eprintln!("Invalid --shorten time: {}", seconds);
+                process::exit(2);
+            }
+        },
+        None => None,
     };
+    let input_file = input_file(&args);
 
-    if let Err(err) = run_prover(input_file) {
+    let options = RunOptions { tptp, tstp, json, dot, shorten };
+    if let Err(err) = run_prover(input_file, options) {
         eprintln!("Error: {}", err);
         process::exit(1);
     }
@@ -172,4 +379,26 @@ mod tests {
         assert!(!FORAS_VERSION.is_empty());
         assert!(!VERSION.is_empty());
     }
+
+    #[test]
+    fn option_values_are_not_input_files() {
+        let args: Vec<String> = ["foras", "--output", "json", "group.in"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(option_value(&args, "--output"), Some("json"));
+        assert_eq!(input_file(&args), Some("group.in"));
+
+        let args: Vec<String> =
+            ["foras", "--output=json"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(option_value(&args, "--output"), Some("json"));
+        assert_eq!(input_file(&args), None);
+    }
+
+    #[test]
+    fn tptp_paths_are_detected_by_extension() {
+        assert!(is_tptp_path("Problems/GRP/GRP001-1.p"));
+        assert!(is_tptp_path("Axioms/GRP004-0.ax"));
+        assert!(!is_tptp_path("examples/group.in"));
+    }
 }
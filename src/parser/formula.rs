@@ -9,6 +9,7 @@
 use crate::data::{Clause, Literal, SymbolId, SymbolTable, Term, VariableId};
 use crate::data::symbol::SymbolKind;
 use crate::parser::ParseError;
+use crate::parser::syntax::VariableTable;
 
 /// A first-order formula with quantifiers and logical connectives.
 #[derive(Clone, Debug, PartialEq)]
@@ -409,6 +410,7 @@ pub fn parse_formula(text: &str, symbols: &SymbolTable) -> Result<Formula, Parse
         text,
         pos: 0,
         symbols,
+        variables: VariableTable::new(false),
     };
 
     parser.parse_formula()
@@ -419,6 +421,8 @@ struct FormulaParser<'a> {
     text: &'a str,
     pos: usize,
     symbols: &'a SymbolTable,
+    /// Variables of the whole formula, shared by its atoms
+    variables: VariableTable,
 }
 
 impl<'a> FormulaParser<'a> {
@@ -637,6 +641,7 @@ impl<'a> FormulaParser<'a> {
         let (literal, _) = crate::parser::syntax::parse_literal_internal(
             &normalized,
             self.symbols,
+            &mut self.variables,
         )?;
 
         Ok(Formula::Atom(literal))
@@ -7,6 +7,7 @@
 mod syntax;
 mod formula;
 mod operator;
+mod tptp;
 
 pub use syntax::{
     ListKind, ListSection, ForasCommand, ForasFile, ParseError, Parser,
@@ -14,3 +15,4 @@ pub use syntax::{
 };
 pub use formula::{Formula, parse_formula};
 pub use operator::{Fixity, Operator, OperatorTable};
+pub use tptp::TptpParser;
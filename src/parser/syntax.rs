@@ -1,6 +1,7 @@
 use crate::data::{
     Clause, ClauseArena, ClauseAttribute, ClauseAttributeValue, ClauseList,
     Literal, SymbolId, SymbolKind, SymbolTable, Term, VariableId,
+    WeightPattern, WeightTemplate,
 };
 use std::fmt;
 
@@ -30,6 +31,9 @@ pub struct ListSection {
     pub name: String,
     pub kind: ListKind,
     pub raw_entries: Vec<String>,
+    /// Entries were written with `set(prolog_style_variables)` in effect, so
+    /// names starting with an upper-case letter or `_` are variables.
+    pub prolog_style_variables: bool,
 }
 
 impl ListSection {
@@ -52,7 +56,12 @@ impl ListSection {
 
         let mut list = ClauseList::new(&self.name);
         for entry in &self.raw_entries {
-            let mut clause = parse_clause(entry, symbols, operators)?;
+            let mut clause = parse_clause(
+                entry,
+                symbols,
+                operators,
+                self.prolog_style_variables,
+            )?;
             clause.add_attribute(ClauseAttribute::new(
                 "list",
                 ClauseAttributeValue::Text(self.name.clone()),
@@ -104,6 +113,27 @@ impl ListSection {
         Ok(entries)
     }
 
+    /// Parse the entries of a weight list into templates, in list order.
+    pub fn weight_templates(
+        &self,
+        symbols: &SymbolTable,
+        operators: &crate::parser::OperatorTable,
+    ) -> Result<Vec<WeightTemplate>, ParseError> {
+        let mut templates = Vec::new();
+        for entry in self.weight_entries()? {
+            let mut variables = VariableTable::new(self.prolog_style_variables);
+            let term = if find_equality_position(&entry.term).is_some() {
+                parse_literal(&entry.term, symbols, operators, &mut variables)?.0.atom
+            } else {
+                parse_term(&entry.term, symbols, operators, &mut variables)?
+            };
+            let pattern = WeightPattern::from_term(&term, symbols)
+                .map_err(|message| ParseError::new(0, 0, message))?;
+            templates.push(WeightTemplate::new(pattern, entry.weight));
+        }
+        Ok(templates)
+    }
+
     /// Convert formula list to clause list via Skolemization and CNF conversion
     pub fn to_clause_list_from_formulas(
         &self,
@@ -192,6 +222,7 @@ impl ForasFile {
 struct ListBuilder {
     name: String,
     kind: ListKind,
+    prolog_style_variables: bool,
     entries: Vec<String>,
     buffer: String,
     paren_depth: i32,
@@ -203,6 +234,7 @@ impl ListBuilder {
         Self {
             name,
             kind,
+            prolog_style_variables: false,
             entries: Vec::new(),
             buffer: String::new(),
             paren_depth: 0,
@@ -271,6 +303,7 @@ impl ListBuilder {
             name: self.name,
             kind: self.kind,
             raw_entries: self.entries,
+            prolog_style_variables: self.prolog_style_variables,
         })
     }
 }
@@ -342,7 +375,9 @@ impl Parser {
             }
 
             // Check for list header
-            if let Some(builder) = parse_list_header(line) {
+            if let Some(mut builder) = parse_list_header(line) {
+                builder.prolog_style_variables =
+                    prolog_style_variables(&file.commands);
                 current = Some(builder);
                 continue;
             }
@@ -419,6 +454,7 @@ fn parse_clause(
     entry: &str,
     symbols: &SymbolTable,
     operators: &crate::parser::OperatorTable,
+    prolog_style: bool,
 ) -> Result<Clause, ParseError> {
     let entry = entry.trim();
     if entry.is_empty() {
@@ -428,10 +464,14 @@ fn parse_clause(
     let (core, mut attributes) = split_clause_annotations(entry)?;
 
     let mut literals = Vec::new();
+    let mut variables = VariableTable::new(prolog_style);
 
-    for literal_text in split_literals(&core) {
+    // A clause that is nothing but annotations is the empty clause.
+    let literal_texts =
+        if core.trim().is_empty() { Vec::new() } else { split_literals(&core) };
+    for literal_text in literal_texts {
         let (literal, mut literal_attrs) =
-            parse_literal(literal_text, symbols, operators)?;
+            parse_literal(literal_text, symbols, operators, &mut variables)?;
         literals.push(literal);
         attributes.append(&mut literal_attrs);
     }
@@ -447,16 +487,18 @@ fn parse_clause(
 pub(crate) fn parse_literal_internal(
     text: &str,
     symbols: &SymbolTable,
+    variables: &mut VariableTable,
 ) -> Result<(Literal, Vec<ClauseAttribute>), ParseError> {
     // Formula parser doesn't have operators yet, so use default table
     let operators = crate::parser::OperatorTable::new();
-    parse_literal(text, symbols, &operators)
+    parse_literal(text, symbols, &operators, variables)
 }
 
 fn parse_literal(
     text: &str,
     symbols: &SymbolTable,
     operators: &crate::parser::OperatorTable,
+    variables: &mut VariableTable,
 ) -> Result<(Literal, Vec<ClauseAttribute>), ParseError> {
     let mut trimmed = text.trim();
     let mut attributes = Vec::new();
@@ -479,7 +521,7 @@ fn parse_literal(
             if close_idx == trimmed.len() - 1 {
                 // The parentheses wrap the entire literal, so strip them
                 let inner = &trimmed[1..trimmed.len() - 1];
-                let (inner_lit, inner_attrs) = parse_literal(inner, symbols, operators)?;
+                let (inner_lit, inner_attrs) = parse_literal(inner, symbols, operators, variables)?;
                 // Combine sign: if outer is negative and inner is negative, result is positive
                 let combined_sign = if sign { inner_lit.sign } else { !inner_lit.sign };
                 let mut combined_attrs = attributes;
@@ -489,13 +531,25 @@ fn parse_literal(
         }
     }
 
+    // A conditional demodulator `condition -> (lhs = rhs)` is one atom
+    if let Some(idx) = find_implication_position(trimmed) {
+        let condition = parse_term(&trimmed[..idx], symbols, operators, variables)?;
+        let (equation, _) = parse_literal(&trimmed[idx + 2..], symbols, operators, variables)?;
+        if !equation.sign {
+            return Err(ParseError::new(0, 0, "the right side of '->' must be a positive literal"));
+        }
+        let implies = symbols.intern("->", 2, SymbolKind::Predicate);
+        let term = Term::application(implies, vec![condition, equation.atom]);
+        return Ok((Literal::new(sign, term), attributes));
+    }
+
     // Check for != first (special case for disequality)
     if let Some(idx) = trimmed.find("!=") {
         let (lhs, rhs) = trimmed.split_at(idx);
         let rhs = &rhs[2..];
         let eq_symbol = intern_equality(symbols);
-        let left_term = parse_term(lhs, symbols, operators)?;
-        let right_term = parse_term(rhs, symbols, operators)?;
+        let left_term = parse_term(lhs, symbols, operators, variables)?;
+        let right_term = parse_term(rhs, symbols, operators, variables)?;
         let term = Term::application(eq_symbol, vec![left_term, right_term]);
         return Ok((Literal::new(false, term), attributes));
     }
@@ -506,8 +560,8 @@ fn parse_literal(
         let (lhs, rhs) = trimmed.split_at(idx);
         let rhs = &rhs[1..];
         let eq_symbol = intern_equality(symbols);
-        let left_term = parse_term(lhs, symbols, operators)?;
-        let right_term = parse_term(rhs, symbols, operators)?;
+        let left_term = parse_term(lhs, symbols, operators, variables)?;
+        let right_term = parse_term(rhs, symbols, operators, variables)?;
         let term = Term::application(eq_symbol, vec![left_term, right_term]);
         return Ok((Literal::new(sign, term), attributes));
     }
@@ -519,7 +573,7 @@ fn parse_literal(
         let args_text = &trimmed[open_paren + 1..close_paren];
         let args = split_arguments(args_text)
             .into_iter()
-            .map(|arg| parse_term(arg, symbols, operators))
+            .map(|arg| parse_term(arg, symbols, operators, variables))
             .collect::<Result<Vec<_>, _>>()?;
         let symbol_id = symbols.intern(
             name.trim(),
@@ -531,8 +585,8 @@ fn parse_literal(
     } else {
         // Check for infix operators (==, <, <=, >, >=) used as predicates
         if let Some((left, op, right)) = find_infix_operator(trimmed, operators) {
-            let left_term = parse_term(left, symbols, operators)?;
-            let right_term = parse_term(right, symbols, operators)?;
+            let left_term = parse_term(left, symbols, operators, variables)?;
+            let right_term = parse_term(right, symbols, operators, variables)?;
             let symbol_id = symbols.intern(op, 2, SymbolKind::Predicate);
             let term = Term::application(symbol_id, vec![left_term, right_term]);
             return Ok((Literal::new(sign, term), attributes));
@@ -553,34 +607,53 @@ fn is_foras_variable(name: &str) -> bool {
         return false;
     }
     let first_char = name.chars().next().unwrap();
-    // In Foras default mode, variables start with u-z
-    first_char >= 'u' && first_char <= 'z'
+    // In Foras default mode, variables start with u-z; `x + 1` is not one
+    first_char >= 'u' && first_char <= 'z' && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
-/// Compute a unique variable ID from a variable name.
-/// Maps single-char variables directly: x=0, y=1, z=2, u=3, v=4, w=5
-/// Multi-char variables get IDs starting from 6.
-fn compute_variable_id(name: &str) -> u16 {
-    if name.len() == 1 {
-        let c = name.chars().next().unwrap();
-        // Map x,y,z,u,v,w to 0-5
-        match c {
-            'x' => 0,
-            'y' => 1,
-            'z' => 2,
-            'u' => 3,
-            'v' => 4,
-            'w' => 5,
-            _ => 0, // Shouldn't happen if is_foras_variable is true
-        }
-    } else {
-        // For multi-char variables (x1, x2, etc.), compute a hash-based ID
-        // Start from 6 to avoid conflict with single-char vars
-        let mut id: u16 = 6;
-        for (i, c) in name.chars().enumerate() {
-            id = id.wrapping_add((c as u16).wrapping_mul((i as u16).wrapping_add(1)));
-        }
-        id
+/// Check if a name is a variable under `set(prolog_style_variables)`.
+/// Variables start with an upper-case letter or '_' (e.g. X, Y1, _Tail).
+fn is_prolog_variable(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The variables of one clause, numbered densely in order of first
+/// occurrence.  Each variable keeps its name, as a [`SymbolKind::Variable`]
+/// symbol, so the clause prints the way it was written.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct VariableTable {
+    /// Names start with an upper-case letter or `_` rather than `u`-`z`
+    prolog_style: bool,
+    /// Name of each variable, indexed by id
+    names: Vec<String>,
+}
+
+impl VariableTable {
+    pub(crate) fn new(prolog_style: bool) -> Self {
+        Self { prolog_style, names: Vec::new() }
+    }
+
+    /// Whether `name` is a variable under this table's convention.
+    pub(crate) fn is_variable(&self, name: &str) -> bool {
+        if self.prolog_style { is_prolog_variable(name) } else { is_foras_variable(name) }
+    }
+
+    /// The variable called `name`, numbered on its first occurrence.
+    pub(crate) fn variable(&mut self, name: &str, symbols: &SymbolTable) -> Result<Term, ParseError> {
+        let index = match self.names.iter().position(|known| known == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+        let id = u16::try_from(index)
+            .map_err(|_| ParseError::new(0, 0, format!("more than {} variables in one clause", u16::MAX as usize + 1)))?;
+        let symbol = symbols.intern(name, 0, SymbolKind::Variable);
+        Ok(Term::named_variable(VariableId::new(id), symbol))
     }
 }
 
@@ -588,37 +661,32 @@ fn parse_term(
     text: &str,
     symbols: &SymbolTable,
     operators: &crate::parser::OperatorTable,
+    variables: &mut VariableTable,
 ) -> Result<Term, ParseError> {
     let text = text.trim();
     if text.is_empty() {
         return Err(ParseError::new(0, 0, "empty term"));
     }
 
-    if text.starts_with('$') {
+    // `$T`, `$F` and the like; `$SUM(x, 1)` is an ordinary application
+    if text.starts_with('$') && !text.contains('(') {
         let id = symbols.intern(text, 0, SymbolKind::Evaluator);
         return Ok(Term::application(id, vec![]));
     }
 
-    // In Foras (default mode), variables are names starting with u-z.
-    // E.g., x, y, z, x1, y2, u, v, w are all variables.
-    // In Prolog style (set(prolog_style_variables)), variables start with A-Z or _.
-    // Here we implement the default Foras convention.
-    if is_foras_variable(text) {
-        // Map the first character to a variable ID
-        // We use the full variable name hash for multi-character names
-        let var_id = compute_variable_id(text);
-        return Ok(Term::variable(VariableId::new(var_id)));
+    if variables.is_variable(text) {
+        return variables.variable(text, symbols);
     }
 
     // Check for Prolog-style lists: [], [a,b,c], [H|T]
     if text.starts_with('[') && text.ends_with(']') {
-        return parse_list(text, symbols, operators);
+        return parse_list(text, symbols, operators, variables);
     }
 
     // Check for prefix operators (e.g., ~x, ~(A | B))
     // Must be checked before function application to handle prefix ops correctly
     if let Some((op, operand)) = find_prefix_operator(text, operators) {
-        let operand_term = parse_term(operand, symbols, operators)?;
+        let operand_term = parse_term(operand, symbols, operators, variables)?;
         let symbol_id = symbols.intern(op, 1, SymbolKind::Function);
         return Ok(Term::application(symbol_id, vec![operand_term]));
     }
@@ -626,31 +694,41 @@ fn parse_term(
     if let Some(open_paren) = text.find('(') {
         let close_paren = matching_paren_index(text, open_paren)
             .ok_or_else(|| ParseError::new(0, 0, "unterminated term"))?;
-        let name = &text[..open_paren];
-        let args_text = &text[open_paren + 1..close_paren];
-        let args = split_arguments(args_text)
-            .into_iter()
-            .map(|arg| parse_term(arg, symbols, operators))
-            .collect::<Result<Vec<_>, _>>()?;
-        let symbol_id = symbols.intern(
-            name.trim(),
-            args.len() as u8,
-            SymbolKind::Function,
-        );
-        return Ok(Term::application(symbol_id, args));
+        let name = text[..open_paren].trim();
+        // `f(a)` or `+(a, b)`, but not `(x + 1) * y` or `x * f(y)`, which
+        // are left to the infix operators below
+        let is_name = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            || name.chars().all(|c| !c.is_alphanumeric() && !c.is_whitespace());
+        if close_paren == text.len() - 1 && is_name {
+            let args_text = &text[open_paren + 1..close_paren];
+            let arg_texts = split_arguments(args_text);
+            if name.is_empty() && arg_texts.len() == 1 {
+                return parse_term(args_text, symbols, operators, variables);
+            }
+            let args = arg_texts
+                .into_iter()
+                .map(|arg| parse_term(arg, symbols, operators, variables))
+                .collect::<Result<Vec<_>, _>>()?;
+            let symbol_id = symbols.intern(
+                name,
+                args.len() as u8,
+                SymbolKind::Function,
+            );
+            return Ok(Term::application(symbol_id, args));
+        }
     }
 
     // Check for infix operators using the operator table
     if let Some((left, op, right)) = find_infix_operator(text, operators) {
-        let left_term = parse_term(left, symbols, operators)?;
-        let right_term = parse_term(right, symbols, operators)?;
+        let left_term = parse_term(left, symbols, operators, variables)?;
+        let right_term = parse_term(right, symbols, operators, variables)?;
         let symbol_id = symbols.intern(op, 2, SymbolKind::Function);
         return Ok(Term::application(symbol_id, vec![left_term, right_term]));
     }
 
     // Check for postfix operators (e.g., x^, a!)
     if let Some((operand, op)) = find_postfix_operator(text, operators) {
-        let operand_term = parse_term(operand, symbols, operators)?;
+        let operand_term = parse_term(operand, symbols, operators, variables)?;
         let symbol_id = symbols.intern(op, 1, SymbolKind::Function);
         return Ok(Term::application(symbol_id, vec![operand_term]));
     }
@@ -746,6 +824,7 @@ fn parse_list(
     text: &str,
     symbols: &SymbolTable,
     operators: &crate::parser::OperatorTable,
+    variables: &mut VariableTable,
 ) -> Result<Term, ParseError> {
     let inner = text[1..text.len() - 1].trim();
 
@@ -760,8 +839,8 @@ fn parse_list(
         let head_str = inner[..pipe_pos].trim();
         let tail_str = inner[pipe_pos + 1..].trim();
 
-        let head = parse_term(head_str, symbols, operators)?;
-        let tail = parse_term(tail_str, symbols, operators)?;
+        let head = parse_term(head_str, symbols, operators, variables)?;
+        let tail = parse_term(tail_str, symbols, operators, variables)?;
 
         let cons_id = symbols.intern("$cons", 2, SymbolKind::Function);
         return Ok(Term::application(cons_id, vec![head, tail]));
@@ -776,7 +855,7 @@ fn parse_list(
 
     // Build the list from right to left
     for elem_str in elements.iter().rev() {
-        let elem = parse_term(elem_str, symbols, operators)?;
+        let elem = parse_term(elem_str, symbols, operators, variables)?;
         let cons_id = symbols.intern("$cons", 2, SymbolKind::Function);
         result_term = Term::application(cons_id, vec![elem, result_term]);
     }
@@ -964,6 +1043,22 @@ fn find_equality_position(text: &str) -> Option<usize> {
     None
 }
 
+/// Position of the first `->` outside parentheses.
+fn find_implication_position(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let bytes = text.as_bytes();
+
+    for i in 0..text.len().saturating_sub(1) {
+        match bytes[i] as char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '-' if depth == 0 && bytes[i + 1] == b'>' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
 fn parse_command(text: &str) -> ForasCommand {
     let lower = text.to_ascii_lowercase();
     if lower.starts_with("set(") && text.ends_with(')') {
@@ -1029,6 +1124,16 @@ fn parse_command(text: &str) -> ForasCommand {
     ForasCommand::Generic(text.to_string())
 }
 
+/// Whether `set(prolog_style_variables)` is in effect after the commands seen
+/// so far; a later `clear(prolog_style_variables)` switches it back off.
+fn prolog_style_variables(commands: &[ForasCommand]) -> bool {
+    commands.iter().fold(false, |enabled, command| match command {
+        ForasCommand::Set(flag) if flag == "prolog_style_variables" => true,
+        ForasCommand::Clear(flag) if flag == "prolog_style_variables" => false,
+        _ => enabled,
+    })
+}
+
 fn parse_list_header(line: &str) -> Option<ListBuilder> {
     if !line.ends_with('.') {
         return None;
@@ -1245,7 +1350,7 @@ pub struct ParseError {
 }
 
 impl ParseError {
-    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+    pub(super) fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
         Self { line, column, message: message.into() }
     }
 }
@@ -1266,7 +1371,7 @@ impl std::error::Error for ParseError {}
 
 #[cfg(test)]
 mod tests {
-    use super::{ListKind, ForasCommand, Parser};
+    use super::{parse_term, ListKind, ForasCommand, Parser, VariableTable};
     use crate::data::{ClauseArena, SymbolTable};
 
     #[test]
@@ -1414,4 +1519,53 @@ mod tests {
         assert_eq!(weights.len(), 2);
         assert_eq!(weights[0].weight, -3);
     }
+    #[test]
+    fn prolog_style_variables_follow_set_and_clear() {
+        let parser = Parser::new();
+        let input = r#"
+            set(prolog_style_variables).
+            list(usable).
+            p(X, x, _Y).
+            end_of_list.
+            clear(prolog_style_variables).
+            list(sos).
+            p(X, x).
+            end_of_list.
+        "#;
+        let file = parser.parse_str(input).expect("parse lists");
+        assert!(file.lists[0].prolog_style_variables);
+        assert!(!file.lists[1].prolog_style_variables);
+
+        let mut arena = ClauseArena::new();
+        let symbols = SymbolTable::new();
+        let operators = crate::parser::OperatorTable::new();
+        let is_var = |term: &crate::data::Term| {
+            matches!(term, crate::data::Term::Variable { .. })
+        };
+        for (section, expected) in
+            file.lists.iter().zip([vec![true, false, true], vec![false, true]])
+        {
+            section
+                .to_clause_list(&mut arena, &symbols, &operators)
+                .expect("clause list");
+            let clause = arena.iter().last().expect("clause present");
+            let crate::data::Term::Application { args, .. } =
+                &clause.literals[0].atom
+            else {
+                panic!("expected application");
+            };
+            let vars: Vec<bool> = args.iter().map(is_var).collect();
+            assert_eq!(vars, expected);
+        }
+    }
+
+    #[test]
+    fn parenthesised_operands_are_not_applications() {
+        let symbols = SymbolTable::new();
+        let operators = crate::parser::OperatorTable::new();
+        let term = parse_term("(x + 1) * y", &symbols, &operators, &mut VariableTable::new(false)).expect("term");
+        assert_eq!(crate::data::format_term(&term, &symbols), "(x+1)*y");
+        let term = parse_term("x + 1", &symbols, &operators, &mut VariableTable::new(false)).expect("term");
+        assert!(matches!(term, crate::data::Term::Application { .. }));
+    }
 }
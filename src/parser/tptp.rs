@@ -0,0 +1,1382 @@
+//! Reader for TPTP problem files.
+//!
+//! Handles the `cnf(...)` and `fof(...)` annotated formulas of the TPTP
+//! language together with `include(...)` directives.  First-order formulas
+//! are clausified (negation normal form, Skolemization, distribution) and
+//! every clause is rendered back into Otter syntax, so the result is an
+//! ordinary [`ForasFile`] that `ProverBuilder::build` consumes unchanged.
+//!
+//! Roles are mapped onto lists the way tptp2X does for Otter: axioms and
+//! their relatives go to `usable`, hypotheses and negated conjectures go to
+//! `sos`, and conjectures are negated before being placed in `sos`.
+
+use super::syntax::{ForasCommand, ForasFile, ListKind, ListSection, ParseError};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nested `include(...)` directives deeper than this are rejected.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Clauses a disjunction may distribute into before a disjunct with
+/// several clauses is replaced by a fresh predicate (Tseitin naming).
+const MAX_DISTRIBUTED_CLAUSES: usize = 64;
+
+/// Parser for TPTP problems written in CNF or FOF.
+#[derive(Clone, Debug, Default)]
+pub struct TptpParser {
+    include_dir: Option<PathBuf>,
+}
+
+impl TptpParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve `include(...)` paths against `dir` (usually the TPTP root).
+    /// Without it, `$TPTP` and then the including file's directory are used.
+    pub fn with_include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include_dir = Some(dir.into());
+        self
+    }
+
+    pub fn parse_str(&self, source: &str) -> Result<ForasFile, ParseError> {
+        let mut problem = Problem::default();
+        self.read_source(source, None, None, &mut problem, 0)?;
+        problem.finish()
+    }
+
+    pub fn parse_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<ForasFile, ParseError> {
+        let path = path.as_ref();
+        let source = read_file(path)?;
+        let mut problem = Problem::default();
+        self.read_source(&source, path.parent(), None, &mut problem, 0)?;
+        problem.finish()
+    }
+
+    fn read_source(
+        &self,
+        source: &str,
+        base_dir: Option<&Path>,
+        selection: Option<&[String]>,
+        problem: &mut Problem,
+        depth: usize,
+    ) -> Result<(), ParseError> {
+        let mut stream = TokenStream::new(tokenize(source)?);
+        while !stream.at_end() {
+            match stream.annotated_unit()? {
+                Unit::Include { path, selection: names, line } => {
+                    if depth >= MAX_INCLUDE_DEPTH {
+                        return Err(ParseError::new(
+                            line,
+                            0,
+                            format!("include of `{}` nested too deeply", path),
+                        ));
+                    }
+                    let resolved = self.resolve_include(&path, base_dir);
+                    let included = read_file(&resolved).map_err(|err| {
+                        ParseError::new(line, 0, err.message)
+                    })?;
+                    self.read_source(
+                        &included,
+                        resolved.parent(),
+                        names.as_deref(),
+                        problem,
+                        depth + 1,
+                    )?;
+                }
+                Unit::Formula(input) => {
+                    let selected = selection
+                        .is_none_or(|names| names.contains(&input.name));
+                    if selected {
+                        problem.add(input)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_include(&self, path: &str, base_dir: Option<&Path>) -> PathBuf {
+        let relative = Path::new(path);
+        if relative.is_absolute() {
+            return relative.to_path_buf();
+        }
+        let tptp_root = std::env::var_os("TPTP").map(PathBuf::from);
+        let candidates = self
+            .include_dir
+            .iter()
+            .cloned()
+            .chain(tptp_root)
+            .chain(base_dir.map(Path::to_path_buf));
+        let mut fallback = None;
+        for dir in candidates {
+            let candidate = dir.join(relative);
+            if candidate.is_file() {
+                return candidate;
+            }
+            fallback.get_or_insert(candidate);
+        }
+        fallback.unwrap_or_else(|| relative.to_path_buf())
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, ParseError> {
+    fs::read_to_string(path).map_err(|err| {
+        ParseError::new(
+            0,
+            0,
+            format!("cannot read `{}`: {}", path.display(), err),
+        )
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Problem assembly
+// ---------------------------------------------------------------------------
+
+/// Where the clauses of an annotated formula end up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placement {
+    Usable,
+    Sos,
+    /// A conjecture: negated, then placed in `sos`.
+    Negated,
+}
+
+fn placement(role: &str) -> Option<Placement> {
+    match role {
+        "axiom" | "definition" | "lemma" | "theorem" | "corollary"
+        | "assumption" | "plain" | "unknown" => Some(Placement::Usable),
+        "hypothesis" | "negated_conjecture" => Some(Placement::Sos),
+        "conjecture" => Some(Placement::Negated),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Input {
+    name: String,
+    role: String,
+    formula: Formula,
+    line: usize,
+}
+
+#[derive(Default)]
+struct Problem {
+    inputs: Vec<(Input, Placement)>,
+    /// Every function and predicate name seen, so Skolem symbols stay fresh.
+    symbols: HashSet<String>,
+}
+
+impl Problem {
+    fn add(&mut self, input: Input) -> Result<(), ParseError> {
+        let placement = placement(&input.role).ok_or_else(|| {
+            ParseError::new(
+                input.line,
+                0,
+                format!(
+                    "unsupported role `{}` for formula `{}`",
+                    input.role, input.name
+                ),
+            )
+        })?;
+        input.formula.collect_symbols(&mut self.symbols);
+        self.inputs.push((input, placement));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ForasFile, ParseError> {
+        let mut skolems = SkolemNames { used: self.symbols, next: 1 };
+        let mut usable = Vec::new();
+        let mut sos = Vec::new();
+
+        // Several conjectures are proved together: their conjunction is
+        // negated as a whole, giving the single clause set placed in `sos`.
+        let mut conjectures = Vec::new();
+        for (input, placement) in self.inputs {
+            let target = match placement {
+                Placement::Usable => &mut usable,
+                Placement::Sos => &mut sos,
+                Placement::Negated => {
+                    conjectures.push(input);
+                    continue;
+                }
+            };
+            for clause in clausify(input.formula, &mut skolems) {
+                target.push(render_clause(&clause, &input.name, &input.role));
+            }
+        }
+        if let Some(first) = conjectures.first() {
+            let name = first.name.clone();
+            let conjunction = Formula::And(
+                conjectures.into_iter().map(|input| input.formula).collect(),
+            );
+            let negated = Formula::Not(Box::new(conjunction));
+            for clause in clausify(negated, &mut skolems) {
+                sos.push(render_clause(&clause, &name, "conjecture"));
+            }
+        }
+
+        Ok(ForasFile {
+            lists: vec![clause_section("usable", usable), clause_section("sos", sos)],
+            commands: vec![ForasCommand::Set(
+                "prolog_style_variables".to_string(),
+            )],
+            ..ForasFile::default()
+        })
+    }
+}
+
+fn clause_section(name: &str, raw_entries: Vec<String>) -> ListSection {
+    ListSection {
+        name: name.to_string(),
+        kind: ListKind::Clause,
+        raw_entries,
+        prolog_style_variables: true,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Formulas
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TptpTerm {
+    Variable(String),
+    Application(String, Vec<TptpTerm>),
+}
+
+impl TptpTerm {
+    fn collect_variables(&self, out: &mut Vec<String>) {
+        match self {
+            TptpTerm::Variable(name) => {
+                if !out.contains(name) {
+                    out.push(name.clone());
+                }
+            }
+            TptpTerm::Application(_, args) => {
+                args.iter().for_each(|arg| arg.collect_variables(out))
+            }
+        }
+    }
+
+    fn collect_symbols(&self, out: &mut HashSet<String>) {
+        if let TptpTerm::Application(name, args) = self {
+            out.insert(name.clone());
+            args.iter().for_each(|arg| arg.collect_symbols(out));
+        }
+    }
+
+    fn substitute(&self, env: &HashMap<String, TptpTerm>) -> TptpTerm {
+        match self {
+            TptpTerm::Variable(name) => {
+                env.get(name).cloned().unwrap_or_else(|| self.clone())
+            }
+            TptpTerm::Application(name, args) => TptpTerm::Application(
+                name.clone(),
+                args.iter().map(|arg| arg.substitute(env)).collect(),
+            ),
+        }
+    }
+}
+
+/// An atomic formula; equality uses the predicate name `=`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Atom {
+    predicate: String,
+    args: Vec<TptpTerm>,
+}
+
+impl Atom {
+    fn is_equality(&self) -> bool {
+        self.predicate == "=" && self.args.len() == 2
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Quantifier {
+    ForAll,
+    Exists,
+}
+
+/// A FOF formula.  `=>`, `<=`, `~|` and `~&` are expanded while parsing and
+/// `<~>` becomes a negated equivalence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Formula {
+    True,
+    False,
+    Atom(Atom),
+    Not(Box<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+    Iff(Box<Formula>, Box<Formula>),
+    Quantified(Quantifier, Vec<String>, Box<Formula>),
+}
+
+impl Formula {
+    fn collect_symbols(&self, out: &mut HashSet<String>) {
+        match self {
+            Formula::True | Formula::False => {}
+            Formula::Atom(atom) => {
+                out.insert(atom.predicate.clone());
+                atom.args.iter().for_each(|arg| arg.collect_symbols(out));
+            }
+            Formula::Not(inner) | Formula::Quantified(_, _, inner) => {
+                inner.collect_symbols(out)
+            }
+            Formula::And(parts) | Formula::Or(parts) => {
+                parts.iter().for_each(|part| part.collect_symbols(out))
+            }
+            Formula::Iff(left, right) => {
+                left.collect_symbols(out);
+                right.collect_symbols(out);
+            }
+        }
+    }
+
+    /// Free variables in order of first occurrence.
+    fn free_variables(&self, bound: &mut Vec<String>, out: &mut Vec<String>) {
+        match self {
+            Formula::True | Formula::False => {}
+            Formula::Atom(atom) => {
+                let mut vars = Vec::new();
+                atom.args.iter().for_each(|arg| arg.collect_variables(&mut vars));
+                for var in vars {
+                    if !bound.contains(&var) && !out.contains(&var) {
+                        out.push(var);
+                    }
+                }
+            }
+            Formula::Not(inner) => inner.free_variables(bound, out),
+            Formula::And(parts) | Formula::Or(parts) => {
+                parts.iter().for_each(|part| part.free_variables(bound, out))
+            }
+            Formula::Iff(left, right) => {
+                left.free_variables(bound, out);
+                right.free_variables(bound, out);
+            }
+            Formula::Quantified(_, vars, inner) => {
+                let depth = bound.len();
+                bound.extend(vars.iter().cloned());
+                inner.free_variables(bound, out);
+                bound.truncate(depth);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Clausification
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TptpLiteral {
+    sign: bool,
+    atom: Atom,
+}
+
+/// Quantifier-free negation normal form.
+enum Matrix {
+    True,
+    False,
+    Literal(TptpLiteral),
+    And(Vec<Matrix>),
+    Or(Vec<Matrix>),
+}
+
+struct SkolemNames {
+    used: HashSet<String>,
+    next: usize,
+}
+
+impl SkolemNames {
+    /// An unused symbol name made from `prefix` and a number.
+    fn fresh(&mut self, prefix: &str) -> String {
+        loop {
+            let name = format!("{}{}", prefix, self.next);
+            self.next += 1;
+            if self.used.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+}
+
+/// State threaded through NNF conversion and Skolemization of one formula.
+struct Skolemizer<'a> {
+    names: &'a mut SkolemNames,
+    /// Universal variables in scope, renamed apart, outermost first.
+    universals: Vec<String>,
+    next_variable: usize,
+}
+
+fn clausify(formula: Formula, names: &mut SkolemNames) -> Vec<Vec<TptpLiteral>> {
+    // Free variables (all of them, for cnf) are universally quantified.
+    let mut free = Vec::new();
+    formula.free_variables(&mut Vec::new(), &mut free);
+    let closed = if free.is_empty() {
+        formula
+    } else {
+        Formula::Quantified(Quantifier::ForAll, free, Box::new(formula))
+    };
+
+    let mut skolemizer =
+        Skolemizer { names, universals: Vec::new(), next_variable: 0 };
+    let matrix = skolemizer.transform(&closed, true, &HashMap::new());
+
+    let mut definitions = Vec::new();
+    let mut clauses = to_cnf(matrix, names, &mut definitions);
+    clauses.extend(definitions);
+    clauses.into_iter().filter_map(simplify_clause).collect()
+}
+
+impl Skolemizer<'_> {
+    /// Convert `formula` (negated when `positive` is false) to a Skolemized
+    /// matrix, with `env` mapping bound variables to their replacements.
+    fn transform(
+        &mut self,
+        formula: &Formula,
+        positive: bool,
+        env: &HashMap<String, TptpTerm>,
+    ) -> Matrix {
+        match formula {
+            Formula::True if positive => Matrix::True,
+            Formula::True => Matrix::False,
+            Formula::False if positive => Matrix::False,
+            Formula::False => Matrix::True,
+            Formula::Atom(atom) => Matrix::Literal(TptpLiteral {
+                sign: positive,
+                atom: Atom {
+                    predicate: atom.predicate.clone(),
+                    args: atom.args.iter().map(|arg| arg.substitute(env)).collect(),
+                },
+            }),
+            Formula::Not(inner) => self.transform(inner, !positive, env),
+            Formula::And(parts) | Formula::Or(parts) => {
+                let parts = parts
+                    .iter()
+                    .map(|part| self.transform(part, positive, env))
+                    .collect();
+                if matches!(formula, Formula::And(_)) == positive {
+                    Matrix::And(parts)
+                } else {
+                    Matrix::Or(parts)
+                }
+            }
+            Formula::Iff(left, right) => {
+                // a <=> b is (~a | b) & (a | ~b); its negation is
+                // (a | b) & (~a | ~b).
+                let first = Matrix::Or(vec![
+                    self.transform(left, !positive, env),
+                    self.transform(right, true, env),
+                ]);
+                let second = Matrix::Or(vec![
+                    self.transform(left, positive, env),
+                    self.transform(right, false, env),
+                ]);
+                Matrix::And(vec![first, second])
+            }
+            Formula::Quantified(quantifier, vars, body) => {
+                let universal = (*quantifier == Quantifier::ForAll) == positive;
+                let mut env = env.clone();
+                let scope = self.universals.len();
+                if universal {
+                    for var in vars {
+                        let fresh = format!("_{}", self.next_variable);
+                        self.next_variable += 1;
+                        env.insert(var.clone(), TptpTerm::Variable(fresh.clone()));
+                        self.universals.push(fresh);
+                    }
+                } else {
+                    let dependencies = self.dependencies(vars, body, &env);
+                    for var in vars {
+                        let skolem = TptpTerm::Application(
+                            self.names.fresh("sk"),
+                            dependencies.clone(),
+                        );
+                        env.insert(var.clone(), skolem);
+                    }
+                }
+                let matrix = self.transform(body, positive, &env);
+                self.universals.truncate(scope);
+                matrix
+            }
+        }
+    }
+
+    /// Universal variables in scope that the body of an existential actually
+    /// depends on; only these become Skolem function arguments.
+    fn dependencies(
+        &self,
+        vars: &[String],
+        body: &Formula,
+        env: &HashMap<String, TptpTerm>,
+    ) -> Vec<TptpTerm> {
+        let mut free = Vec::new();
+        body.free_variables(&mut vars.to_vec(), &mut free);
+        let mut used = Vec::new();
+        for var in &free {
+            if let Some(term) = env.get(var) {
+                term.collect_variables(&mut used);
+            }
+        }
+        self.universals
+            .iter()
+            .filter(|var| used.contains(var))
+            .map(|var| TptpTerm::Variable(var.clone()))
+            .collect()
+    }
+}
+
+/// Clauses of `matrix`.  A disjunct whose clauses would multiply those of
+/// the other disjuncts past `MAX_DISTRIBUTED_CLAUSES` is replaced by a fresh
+/// predicate `d(X1, .., Xn)` over its variables, and `-d(X1, .., Xn) | C`
+/// is added to `definitions` for each of its clauses `C`.  The matrix is in
+/// negation normal form, so the one direction of the definition suffices.
+fn to_cnf(
+    matrix: Matrix,
+    names: &mut SkolemNames,
+    definitions: &mut Vec<Vec<TptpLiteral>>,
+) -> Vec<Vec<TptpLiteral>> {
+    match matrix {
+        Matrix::True => Vec::new(),
+        Matrix::False => vec![Vec::new()],
+        Matrix::Literal(literal) => vec![vec![literal]],
+        Matrix::And(parts) => parts
+            .into_iter()
+            .flat_map(|part| to_cnf(part, names, definitions))
+            .collect(),
+        Matrix::Or(parts) => {
+            let mut clauses = vec![Vec::new()];
+            for part in parts {
+                let mut part_clauses = to_cnf(part, names, definitions);
+                if part_clauses.len() > 1
+                    && clauses.len() * part_clauses.len() > MAX_DISTRIBUTED_CLAUSES
+                {
+                    let mut variables = Vec::new();
+                    for literal in part_clauses.iter().flatten() {
+                        literal.atom.args.iter().for_each(|arg| arg.collect_variables(&mut variables));
+                    }
+                    let atom = Atom {
+                        predicate: names.fresh("def"),
+                        args: variables.into_iter().map(TptpTerm::Variable).collect(),
+                    };
+                    for clause in part_clauses {
+                        let mut definition = vec![TptpLiteral { sign: false, atom: atom.clone() }];
+                        definition.extend(clause);
+                        definitions.push(definition);
+                    }
+                    part_clauses = vec![vec![TptpLiteral { sign: true, atom }]];
+                }
+                let mut product = Vec::new();
+                for clause in &clauses {
+                    for extra in &part_clauses {
+                        let mut combined: Vec<TptpLiteral> = clause.clone();
+                        combined.extend(extra.iter().cloned());
+                        product.push(combined);
+                    }
+                }
+                clauses = product;
+            }
+            clauses
+        }
+    }
+}
+
+/// Drop duplicate and trivially false literals; `None` for tautologies.
+fn simplify_clause(literals: Vec<TptpLiteral>) -> Option<Vec<TptpLiteral>> {
+    let mut kept: Vec<TptpLiteral> = Vec::new();
+    for literal in literals {
+        let reflexive = literal.atom.is_equality()
+            && literal.atom.args[0] == literal.atom.args[1];
+        if reflexive && literal.sign {
+            return None;
+        }
+        if reflexive || kept.contains(&literal) {
+            continue;
+        }
+        if kept.iter().any(|other| {
+            other.sign != literal.sign && other.atom == literal.atom
+        }) {
+            return None;
+        }
+        kept.push(literal);
+    }
+    Some(kept)
+}
+
+// ---------------------------------------------------------------------------
+// Rendering as Otter clauses
+// ---------------------------------------------------------------------------
+
+/// Render a clause in Otter syntax with Prolog-style variables.  Variables
+/// are renamed `A`..`Z` by first occurrence, then `X26`, `X27`, ...
+fn render_clause(literals: &[TptpLiteral], name: &str, role: &str) -> String {
+    let mut variables = Vec::new();
+    for literal in literals {
+        for arg in &literal.atom.args {
+            arg.collect_variables(&mut variables);
+        }
+    }
+    let renaming: HashMap<String, String> = variables
+        .iter()
+        .enumerate()
+        .map(|(index, var)| {
+            let name = if index < 26 { ((b'A' + index as u8) as char).to_string() } else { format!("X{}", index) };
+            (var.clone(), name)
+        })
+        .collect();
+
+    let body = literals
+        .iter()
+        .map(|literal| render_literal(literal, &renaming))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let label = annotation_text(name);
+    let annotations = format!("# label({}) # role({})", label, role);
+    if body.is_empty() { annotations } else { format!("{} {}", body, annotations) }
+}
+
+fn render_literal(
+    literal: &TptpLiteral,
+    renaming: &HashMap<String, String>,
+) -> String {
+    let atom = &literal.atom;
+    if atom.is_equality() {
+        let op = if literal.sign { "=" } else { "!=" };
+        return format!(
+            "{} {} {}",
+            render_term(&atom.args[0], renaming),
+            op,
+            render_term(&atom.args[1], renaming)
+        );
+    }
+    let sign = if literal.sign { "" } else { "-" };
+    let application = TptpTerm::Application(atom.predicate.clone(), atom.args.clone());
+    format!("{}{}", sign, render_term(&application, renaming))
+}
+
+fn render_term(term: &TptpTerm, renaming: &HashMap<String, String>) -> String {
+    match term {
+        TptpTerm::Variable(var) => renaming[var].clone(),
+        TptpTerm::Application(name, args) if args.is_empty() => name.clone(),
+        TptpTerm::Application(name, args) => format!(
+            "{}({})",
+            name,
+            args.iter()
+                .map(|arg| render_term(arg, renaming))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+/// Formula names may be arbitrary quoted text; annotations need a plain word.
+fn annotation_text(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tokens
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TokenKind {
+    LowerWord(String),
+    UpperWord(String),
+    DollarWord(String),
+    SingleQuoted(String),
+    DistinctObject(String),
+    Number(String),
+    Punct(&'static str),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+const PUNCTUATION: &[&str] = &[
+    "<=>", "<~>", "!=", "=>", "<=", "~|", "~&", "(", ")", "[", "]", ",", ".",
+    ":", "!", "?", "~", "&", "|", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut line = 0;
+    let mut line_start = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let column = pos - line_start;
+        if c == '\n' {
+            pos += 1;
+            line += 1;
+            line_start = pos;
+            continue;
+        }
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+        if c == '%' {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(pos + 1) == Some(&'*') {
+            pos += 2;
+            loop {
+                match chars.get(pos) {
+                    None => {
+                        return Err(ParseError::new(
+                            line,
+                            column,
+                            "unterminated comment",
+                        ));
+                    }
+                    Some('*') if chars.get(pos + 1) == Some(&'/') => {
+                        pos += 2;
+                        break;
+                    }
+                    Some('\n') => {
+                        pos += 1;
+                        line += 1;
+                        line_start = pos;
+                    }
+                    Some(_) => pos += 1,
+                }
+            }
+            continue;
+        }
+
+        let word_end = |start: usize| {
+            let mut end = start;
+            while end < chars.len()
+                && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+            {
+                end += 1;
+            }
+            end
+        };
+
+        let kind = if c.is_ascii_lowercase() {
+            let end = word_end(pos);
+            let word: String = chars[pos..end].iter().collect();
+            pos = end;
+            TokenKind::LowerWord(word)
+        } else if c.is_ascii_uppercase() || c == '_' {
+            let end = word_end(pos);
+            let word: String = chars[pos..end].iter().collect();
+            pos = end;
+            TokenKind::UpperWord(word)
+        } else if c == '$' {
+            let start = pos;
+            pos += 1;
+            if chars.get(pos) == Some(&'$') {
+                pos += 1;
+            }
+            let end = word_end(pos);
+            let word: String = chars[start..end].iter().collect();
+            pos = end;
+            TokenKind::DollarWord(word)
+        } else if c == '\'' || c == '"' {
+            let mut text = String::new();
+            pos += 1;
+            loop {
+                match chars.get(pos) {
+                    None | Some('\n') => {
+                        return Err(ParseError::new(
+                            line,
+                            column,
+                            "unterminated quoted string",
+                        ));
+                    }
+                    Some('\\') if pos + 1 < chars.len() => {
+                        text.push(chars[pos + 1]);
+                        pos += 2;
+                    }
+                    Some(&q) if q == c => {
+                        pos += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        pos += 1;
+                    }
+                }
+            }
+            if c == '\'' {
+                TokenKind::SingleQuoted(text)
+            } else {
+                TokenKind::DistinctObject(text)
+            }
+        } else if c.is_ascii_digit()
+            || ((c == '-' || c == '+')
+                && chars.get(pos + 1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            let start = pos;
+            pos += 1;
+            while pos < chars.len()
+                && (chars[pos].is_ascii_alphanumeric()
+                    || matches!(chars[pos], '/' | '+' | '-')
+                    || (chars[pos] == '.'
+                        && chars.get(pos + 1).is_some_and(|d| d.is_ascii_digit())))
+            {
+                // A sign only continues a number as an exponent sign.
+                if matches!(chars[pos], '+' | '-')
+                    && !matches!(chars[pos - 1], 'e' | 'E')
+                {
+                    break;
+                }
+                pos += 1;
+            }
+            TokenKind::Number(chars[start..pos].iter().collect())
+        } else {
+            let punct = PUNCTUATION.iter().find(|p| {
+                p.chars().enumerate().all(|(i, pc)| chars.get(pos + i) == Some(&pc))
+            });
+            match punct {
+                Some(p) => {
+                    pos += p.chars().count();
+                    TokenKind::Punct(p)
+                }
+                None => {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        format!("unexpected character `{}`", c),
+                    ));
+                }
+            }
+        };
+        tokens.push(Token { kind, line, column });
+    }
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Annotated formulas
+// ---------------------------------------------------------------------------
+
+enum Unit {
+    Include { path: String, selection: Option<Vec<String>>, line: usize },
+    Formula(Input),
+}
+
+struct TokenStream {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl TokenStream {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(TokenKind::Punct(p)) if *p == punct)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let (line, column) = match self.tokens.get(self.pos) {
+            Some(token) => (token.line, token.column),
+            None => self
+                .tokens
+                .last()
+                .map_or((0, 0), |token| (token.line, token.column)),
+        };
+        ParseError::new(line, column, message)
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), ParseError> {
+        if self.peek_punct(punct) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", punct)))
+        }
+    }
+
+    fn annotated_unit(&mut self) -> Result<Unit, ParseError> {
+        let token = self.next()?;
+        let TokenKind::LowerWord(keyword) = &token.kind else {
+            self.pos -= 1;
+            return Err(self.error("expected `cnf`, `fof` or `include`"));
+        };
+        match keyword.as_str() {
+            "include" => {
+                self.expect("(")?;
+                let path = match self.next()?.kind {
+                    TokenKind::SingleQuoted(path) => path,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("expected quoted file name"));
+                    }
+                };
+                let mut selection = None;
+                if self.peek_punct(",") {
+                    self.pos += 1;
+                    self.expect("[")?;
+                    let mut names = Vec::new();
+                    while !self.peek_punct("]") {
+                        names.push(self.formula_name()?);
+                        if !self.peek_punct("]") {
+                            self.expect(",")?;
+                        }
+                    }
+                    self.pos += 1;
+                    selection = Some(names);
+                }
+                self.expect(")")?;
+                self.expect(".")?;
+                Ok(Unit::Include { path, selection, line: token.line })
+            }
+            "cnf" | "fof" => {
+                self.expect("(")?;
+                let name = self.formula_name()?;
+                self.expect(",")?;
+                let role = match self.next()?.kind {
+                    TokenKind::LowerWord(role) => role,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("expected formula role"));
+                    }
+                };
+                self.expect(",")?;
+                let formula = self.logic_formula()?;
+                if self.peek_punct(",") {
+                    self.skip_annotations()?;
+                }
+                self.expect(")")?;
+                self.expect(".")?;
+                Ok(Unit::Formula(Input { name, role, formula, line: token.line }))
+            }
+            "tff" | "thf" | "tcf" | "tpi" => {
+                self.pos -= 1;
+                Err(self.error(format!("`{}` formulas are not supported", keyword)))
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error(format!("unknown TPTP directive `{}`", keyword)))
+            }
+        }
+    }
+
+    fn formula_name(&mut self) -> Result<String, ParseError> {
+        match self.next()?.kind {
+            TokenKind::LowerWord(name)
+            | TokenKind::SingleQuoted(name)
+            | TokenKind::Number(name) => Ok(name),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected formula name"))
+            }
+        }
+    }
+
+    /// Skip `, source, useful_info` up to (not including) the closing `)`.
+    fn skip_annotations(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated annotations")),
+                Some(TokenKind::Punct(")")) if depth == 0 => return Ok(()),
+                Some(TokenKind::Punct("(" | "[")) => depth += 1,
+                Some(TokenKind::Punct(")" | "]")) => depth -= 1,
+                Some(_) => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn logic_formula(&mut self) -> Result<Formula, ParseError> {
+        let first = self.unitary_formula()?;
+        let Some(TokenKind::Punct(op)) = self.peek().cloned() else {
+            return Ok(first);
+        };
+        match op {
+            "&" | "|" => {
+                let mut parts = vec![first];
+                while self.peek_punct(op) {
+                    self.pos += 1;
+                    parts.push(self.unitary_formula()?);
+                }
+                Ok(if op == "&" { Formula::And(parts) } else { Formula::Or(parts) })
+            }
+            "<=>" | "=>" | "<=" | "<~>" | "~|" | "~&" => {
+                self.pos += 1;
+                let second = self.unitary_formula()?;
+                let not = |f: Formula| Formula::Not(Box::new(f));
+                Ok(match op {
+                    "<=>" => Formula::Iff(Box::new(first), Box::new(second)),
+                    "=>" => Formula::Or(vec![not(first), second]),
+                    "<=" => Formula::Or(vec![first, not(second)]),
+                    "<~>" => not(Formula::Iff(Box::new(first), Box::new(second))),
+                    "~|" => not(Formula::Or(vec![first, second])),
+                    _ => not(Formula::And(vec![first, second])),
+                })
+            }
+            _ => Ok(first),
+        }
+    }
+
+    fn unitary_formula(&mut self) -> Result<Formula, ParseError> {
+        match self.peek() {
+            Some(TokenKind::Punct("(")) => {
+                self.pos += 1;
+                let formula = self.logic_formula()?;
+                self.expect(")")?;
+                Ok(formula)
+            }
+            Some(TokenKind::Punct("~")) => {
+                self.pos += 1;
+                Ok(Formula::Not(Box::new(self.unitary_formula()?)))
+            }
+            Some(TokenKind::Punct(q @ ("!" | "?"))) => {
+                let quantifier =
+                    if *q == "!" { Quantifier::ForAll } else { Quantifier::Exists };
+                self.pos += 1;
+                self.expect("[")?;
+                let mut vars = Vec::new();
+                loop {
+                    match self.next()?.kind {
+                        TokenKind::UpperWord(var) => vars.push(var),
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("expected variable"));
+                        }
+                    }
+                    if self.peek_punct(",") {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                self.expect("]")?;
+                self.expect(":")?;
+                let body = self.unitary_formula()?;
+                Ok(Formula::Quantified(quantifier, vars, Box::new(body)))
+            }
+            _ => self.atomic_formula(),
+        }
+    }
+
+    fn atomic_formula(&mut self) -> Result<Formula, ParseError> {
+        if let Some(TokenKind::DollarWord(word)) = self.peek() {
+            let constant = match word.as_str() {
+                "$true" => Some(Formula::True),
+                "$false" => Some(Formula::False),
+                _ => None,
+            };
+            if let Some(constant) = constant {
+                self.pos += 1;
+                return Ok(constant);
+            }
+        }
+        let left = self.term()?;
+        if self.peek_punct("=") || self.peek_punct("!=") {
+            let equal = self.peek_punct("=");
+            self.pos += 1;
+            let right = self.term()?;
+            let atom = Atom { predicate: "=".to_string(), args: vec![left, right] };
+            return Ok(if equal {
+                Formula::Atom(atom)
+            } else {
+                Formula::Not(Box::new(Formula::Atom(atom)))
+            });
+        }
+        match left {
+            TptpTerm::Application(predicate, args) => {
+                Ok(Formula::Atom(Atom { predicate, args }))
+            }
+            TptpTerm::Variable(var) => Err(self.error(format!(
+                "variable `{}` used as a formula",
+                var
+            ))),
+        }
+    }
+
+    fn term(&mut self) -> Result<TptpTerm, ParseError> {
+        let token = self.next()?;
+        let name = match token.kind {
+            TokenKind::UpperWord(var) => return Ok(TptpTerm::Variable(var)),
+            TokenKind::LowerWord(name) => name,
+            TokenKind::SingleQuoted(name) if is_plain_word(&name) => name,
+            TokenKind::Number(number)
+                if number.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                number
+            }
+            other => {
+                self.pos -= 1;
+                let what = match other {
+                    TokenKind::SingleQuoted(name) => {
+                        format!("quoted symbol '{}' has no Otter spelling", name)
+                    }
+                    TokenKind::Number(number) => {
+                        format!("number `{}` is not supported", number)
+                    }
+                    TokenKind::DistinctObject(text) => {
+                        format!("distinct object \"{}\" is not supported", text)
+                    }
+                    TokenKind::DollarWord(word) => {
+                        format!("defined symbol `{}` is not supported", word)
+                    }
+                    _ => "expected term".to_string(),
+                };
+                return Err(self.error(what));
+            }
+        };
+        let mut args = Vec::new();
+        if self.peek_punct("(") {
+            self.pos += 1;
+            loop {
+                args.push(self.term()?);
+                if self.peek_punct(",") {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+        Ok(TptpTerm::Application(name, args))
+    }
+}
+
+/// A quoted symbol can be written unquoted in Otter syntax.
+fn is_plain_word(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TptpParser;
+    use crate::data::{ClauseArena, SymbolTable};
+
+    fn entries(source: &str, list: &str) -> Vec<String> {
+        let file = TptpParser::new().parse_str(source).expect("parse");
+        file.get_list(list).expect("list").raw_entries.clone()
+    }
+
+    #[test]
+    fn cnf_roles_map_to_lists() {
+        let source = "
+            % Comment line
+            cnf(ax1, axiom, p(X) | ~q(X, a)).
+            cnf(hyp, hypothesis, q(b, Y)).
+            cnf(goal, negated_conjecture, ~p(b)).
+        ";
+        assert_eq!(
+            entries(source, "usable"),
+            vec!["p(A) | -q(A,a) # label(ax1) # role(axiom)"]
+        );
+        assert_eq!(
+            entries(source, "sos"),
+            vec![
+                "q(b,A) # label(hyp) # role(hypothesis)",
+                "-p(b) # label(goal) # role(negated_conjecture)",
+            ]
+        );
+    }
+
+    #[test]
+    fn conjecture_is_negated_and_skolemized() {
+        let source = "
+            fof(refl, axiom, ![X]: r(X, X)).
+            fof(goal, conjecture, ?[Y]: r(c, Y)).
+        ";
+        assert_eq!(
+            entries(source, "sos"),
+            vec!["-r(c,A) # label(goal) # role(conjecture)"]
+        );
+
+        let source = "fof(goal, conjecture, ![X]: ?[Y]: f(X) = Y).";
+        assert_eq!(
+            entries(source, "sos"),
+            vec!["f(sk1) != A # label(goal) # role(conjecture)"]
+        );
+    }
+
+    #[test]
+    fn skolem_functions_depend_only_on_relevant_universals() {
+        let source = "fof(a, axiom, ![X, Y]: (q(X) & ?[Z]: p(Y, Z))).";
+        assert_eq!(
+            entries(source, "usable"),
+            vec![
+                "q(A) # label(a) # role(axiom)",
+                "p(A,sk1(A)) # label(a) # role(axiom)",
+            ]
+        );
+    }
+
+    #[test]
+    fn skolem_names_avoid_existing_symbols() {
+        let source = "
+            fof(a, axiom, ?[X]: p(X)).
+            cnf(b, axiom, sk1 = sk1 | q(sk1)).
+        ";
+        assert_eq!(
+            entries(source, "usable"),
+            vec!["p(sk2) # label(a) # role(axiom)"]
+        );
+    }
+
+    #[test]
+    fn large_disjunctions_are_named_instead_of_distributed() {
+        // Distributing twenty two-clause disjuncts would give 2^20 clauses;
+        // the six that fit are distributed and the rest are named
+        let disjuncts: Vec<String> =
+            (1..=20).map(|i| format!("(p{i}(X) & q{i})")).collect();
+        let source = format!("fof(a, axiom, ![X]: ({})).", disjuncts.join(" | "));
+        let clauses = entries(&source, "usable");
+        assert_eq!(clauses.len(), 64 + 2 * 14);
+        assert!(clauses[0].starts_with("p1(A) | p2(A) | p3(A) | p4(A) | p5(A) | p6(A) | def1(A) | def2(A)"));
+        assert!(clauses.contains(&"-def1(A) | p7(A) # label(a) # role(axiom)".to_string()));
+        assert!(clauses.contains(&"-def1(A) | q7 # label(a) # role(axiom)".to_string()));
+    }
+
+    #[test]
+    fn equivalence_and_implication_are_distributed() {
+        let source = "fof(a, axiom, (p <=> q) & (r => s)).";
+        assert_eq!(
+            entries(source, "usable"),
+            vec![
+                "-p | q # label(a) # role(axiom)",
+                "p | -q # label(a) # role(axiom)",
+                "-r | s # label(a) # role(axiom)",
+            ]
+        );
+    }
+
+    #[test]
+    fn tautologies_and_trivial_literals_are_removed() {
+        let source = "
+            cnf(t, axiom, p(X) | ~p(X)).
+            cnf(r, axiom, X = X | q).
+            cnf(d, axiom, a != a | q | q).
+            fof(f, axiom, $false | p).
+        ";
+        assert_eq!(
+            entries(source, "usable"),
+            vec!["q # label(d) # role(axiom)", "p # label(f) # role(axiom)"]
+        );
+    }
+
+    #[test]
+    fn annotations_and_block_comments_are_skipped() {
+        let source = "
+            /* A block
+               comment */
+            cnf(c1, axiom, p(a), file('x.p', c1), [useful(info)]).
+            cnf('quoted name', axiom, 'q'(a)).
+        ";
+        assert_eq!(
+            entries(source, "usable"),
+            vec![
+                "p(a) # label(c1) # role(axiom)",
+                "q(a) # label(quoted_name) # role(axiom)",
+            ]
+        );
+    }
+
+    #[test]
+    fn output_parses_as_prolog_style_clauses() {
+        let source = "
+            cnf(a, axiom, mult(X, mult(Y, Z)) = mult(mult(X, Y), Z)).
+            cnf(g, negated_conjecture, mult(a, b) != e).
+        ";
+        let file = TptpParser::new().parse_str(source).expect("parse");
+        let symbols = SymbolTable::new();
+        let mut arena = ClauseArena::new();
+        let usable = file
+            .get_list("usable")
+            .unwrap()
+            .to_clause_list(&mut arena, &symbols, &file.operators)
+            .expect("clauses");
+        let clause = arena.get(*usable.iter().next().unwrap()).unwrap();
+        let atom = &clause.literals[0].atom;
+        assert!(clause.literals[0].sign);
+        // Three distinct variables survive the round trip.
+        let mut vars = Vec::new();
+        fn collect(term: &crate::data::Term, out: &mut Vec<u16>) {
+            match term {
+                crate::data::Term::Variable { id, .. } => {
+                    if !out.contains(&id.0) {
+                        out.push(id.0);
+                    }
+                }
+                crate::data::Term::Application { args, .. } => {
+                    args.iter().for_each(|arg| collect(arg, out))
+                }
+            }
+        }
+        collect(atom, &mut vars);
+        assert_eq!(vars.len(), 3);
+    }
+
+    #[test]
+    fn rejects_unsupported_input() {
+        let parser = TptpParser::new();
+        assert!(parser.parse_str("tff(a, type, t: $tType).").is_err());
+        assert!(parser.parse_str("cnf(a, axiom, p(1.5)).").is_err());
+        assert!(parser.parse_str("cnf(a, axiom, p(\"obj\")).").is_err());
+        assert!(parser.parse_str("cnf(a, fi_domain, p).").is_err());
+        let err = parser.parse_str("cnf(a, axiom, p(X)\n").unwrap_err();
+        assert!(err.message.contains("expected"));
+    }
+
+    #[test]
+    fn includes_are_resolved_relative_to_the_include_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("foras_tptp_include_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Axioms")).unwrap();
+        std::fs::write(
+            dir.join("Axioms/SET001-0.ax"),
+            "cnf(member, axiom, in(X, s)).\ncnf(other, axiom, q).\n",
+        )
+        .unwrap();
+        let source = "include('Axioms/SET001-0.ax', [member]).\n\
+                      cnf(goal, negated_conjecture, ~in(a, s)).";
+        let file = TptpParser::new()
+            .with_include_dir(&dir)
+            .parse_str(source)
+            .expect("parse");
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(
+            file.get_list("usable").unwrap().raw_entries,
+            vec!["in(A,s) # label(member) # role(axiom)"]
+        );
+    }
+}
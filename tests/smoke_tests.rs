@@ -2,7 +2,7 @@
 //!
 //! These tests verify that the prover can solve basic problems correctly.
 
-use foras::{Parser, ProverBuilder};
+use foras::{Parser, ProverBuilder, SzsStatus, TptpParser};
 
 #[test]
 fn test_simple_resolution() {
@@ -185,3 +185,48 @@ end_of_list.
         foras::ProofResult::Saturated { .. }),
             "Should complete search");
 }
+
+#[test]
+fn test_tptp_problem() {
+    let input = r#"
+% Every man is mortal; Socrates is a man.
+fof(mortality, axiom, ![X]: (man(X) => mortal(X))).
+fof(socrates, axiom, man(socrates)).
+fof(goal, conjecture, ?[Y]: mortal(Y)).
+"#;
+
+    let parser = TptpParser::new();
+    let file = parser.parse_str(input).expect("parse failed");
+    let mut prover = ProverBuilder::new().build(&file).expect("build failed");
+
+    let result = prover.search();
+    assert!(matches!(result, foras::ProofResult::Proof { .. }),
+            "Should prove the TPTP conjecture");
+}
+
+#[test]
+fn test_szs_theorem_and_tstp_derivation() {
+    let input = r#"
+fof(mortality, axiom, ![X]: (man(X) => mortal(X))).
+fof(socrates, axiom, man(socrates)).
+fof(goal, conjecture, mortal(socrates)).
+"#;
+
+    let file = TptpParser::new().parse_str(input).expect("parse failed");
+    let mut prover = ProverBuilder::new().build(&file).expect("build failed");
+
+    let result = prover.search();
+    let has_conjecture = foras::inference::input_has_conjecture(prover.arena());
+    let status = SzsStatus::from_result(
+        &result,
+        has_conjecture,
+        prover.saturation_is_complete(),
+    );
+    assert_eq!(status, SzsStatus::Theorem);
+
+    let proof = prover.proof().expect("expected a proof");
+    let derivation =
+        foras::inference::tstp_derivation(&proof, prover.symbols(), "mortal");
+    assert!(derivation.contains("negated_conjecture, ~mortal(socrates)"));
+    assert!(derivation.contains("$false, inference(binary, [status(thm)]"));
+}